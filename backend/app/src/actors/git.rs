@@ -1,4 +1,5 @@
 use hannibal::prelude::*;
+use std::ops::RangeInclusive;
 use std::path::Path;
 
 #[derive(Actor)]
@@ -49,9 +50,10 @@ impl Handler<GetRevision> for GitActor {
     }
 }
 
-#[message(response = Result<git2_ox::CommitWithReferences, git2_ox::error::Error>)]
+#[message(response = Result<git2_ox::Checkout, git2_ox::error::Error>)]
 pub struct CheckoutRevision {
     pub revision: String,
+    pub mode: git2_ox::CheckoutMode,
 }
 
 impl Handler<CheckoutRevision> for GitActor {
@@ -59,8 +61,45 @@ impl Handler<CheckoutRevision> for GitActor {
         &mut self,
         _ctx: &mut Context<Self>,
         msg: CheckoutRevision,
+    ) -> Result<git2_ox::Checkout, git2_ox::error::Error> {
+        self.repository
+            .checkout_revision_with_mode(&msg.revision, msg.mode)
+    }
+}
+
+#[message(response = Result<(), git2_ox::error::Error>)]
+pub struct RestoreStash {
+    pub id: String,
+}
+
+impl Handler<RestoreStash> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: RestoreStash,
+    ) -> Result<(), git2_ox::error::Error> {
+        self.repository.restore_stash(&msg.id)
+    }
+}
+
+#[message(response = Result<git2_ox::CommitWithReferences, git2_ox::error::Error>)]
+pub struct CheckoutWorktree {
+    pub name: String,
+    pub path: std::path::PathBuf,
+    pub revision: String,
+}
+
+impl Handler<CheckoutWorktree> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: CheckoutWorktree,
     ) -> Result<git2_ox::CommitWithReferences, git2_ox::error::Error> {
-        self.repository.checkout_revision(&msg.revision)
+        let commit = self
+            .repository
+            .checkout_worktree(&msg.name, &msg.path, &msg.revision)?;
+        self.repository
+            .get_commit_for_revision(git2_ox::CommitProperties::id(&commit))
     }
 }
 
@@ -69,6 +108,10 @@ pub struct ListCommits {
     pub base_rev: Option<String>,
     pub head_rev: Option<String>,
     pub filter: Option<String>,
+    /// Only list commits changing this file or directory
+    pub path: Option<String>,
+    /// Follow renames of the file at `path`
+    pub follow_renames: bool,
 }
 
 impl Handler<ListCommits> for GitActor {
@@ -77,9 +120,17 @@ impl Handler<ListCommits> for GitActor {
         _ctx: &mut Context<Self>,
         msg: ListCommits,
     ) -> Result<Vec<git2_ox::CommitWithReferences>, git2_ox::error::Error> {
-        let commits_iter = self
-            .repository
-            .iter_commits(msg.base_rev.as_deref(), msg.head_rev.as_deref())?;
+        let base_rev = msg.base_rev.as_deref();
+        let head_rev = msg.head_rev.as_deref();
+        let commits_iter: Box<dyn Iterator<Item = _>> = match &msg.path {
+            Some(path) => Box::new(self.repository.iter_commits_for_path(
+                base_rev,
+                head_rev,
+                path,
+                msg.follow_renames,
+            )?),
+            None => Box::new(self.repository.iter_commits(base_rev, head_rev)?),
+        };
         let filter = msg.filter.unwrap_or_default();
 
         let mut commits = Vec::new();
@@ -97,6 +148,7 @@ impl Handler<ListCommits> for GitActor {
 pub struct GetDiff {
     pub base_rev: Option<String>,
     pub head_rev: Option<String>,
+    pub options: git2_ox::DiffOptions,
 }
 
 impl Handler<GetDiff> for GitActor {
@@ -105,8 +157,62 @@ impl Handler<GetDiff> for GitActor {
         _ctx: &mut Context<Self>,
         msg: GetDiff,
     ) -> Result<git2_ox::Diff, git2_ox::error::Error> {
+        self.repository.diff(
+            msg.base_rev.as_deref(),
+            msg.head_rev.as_deref(),
+            &msg.options,
+        )
+    }
+}
+
+#[message(response = Result<Vec<git2_ox::BlameHunk>, git2_ox::error::Error>)]
+pub struct Blame {
+    pub path: String,
+    pub revision: String,
+    pub line_range: Option<RangeInclusive<usize>>,
+    pub options: git2_ox::BlameOptions,
+}
+
+impl Handler<Blame> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: Blame,
+    ) -> Result<Vec<git2_ox::BlameHunk>, git2_ox::error::Error> {
         self.repository
-            .diff(msg.base_rev.as_deref(), msg.head_rev.as_deref())
+            .blame(&msg.path, &msg.revision, msg.line_range, &msg.options)
+    }
+}
+
+#[message(response = Result<Vec<git2_ox::TreeEntry>, git2_ox::error::Error>)]
+pub struct ListTree {
+    pub revision: String,
+    pub path: String,
+}
+
+impl Handler<ListTree> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: ListTree,
+    ) -> Result<Vec<git2_ox::TreeEntry>, git2_ox::error::Error> {
+        self.repository.list_tree(&msg.revision, &msg.path)
+    }
+}
+
+#[message(response = Result<git2_ox::Blob, git2_ox::error::Error>)]
+pub struct ReadBlob {
+    pub revision: String,
+    pub path: String,
+}
+
+impl Handler<ReadBlob> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: ReadBlob,
+    ) -> Result<git2_ox::Blob, git2_ox::error::Error> {
+        self.repository.read_blob(&msg.revision, &msg.path)
     }
 }
 
@@ -247,3 +353,54 @@ impl Handler<ListReferences> for GitActor {
         Ok(references)
     }
 }
+
+#[message(response = Result<Vec<git2_ox::Worktree>, git2_ox::error::Error>)]
+pub struct ListWorktrees;
+
+impl Handler<ListWorktrees> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        _msg: ListWorktrees,
+    ) -> Result<Vec<git2_ox::Worktree>, git2_ox::error::Error> {
+        self.repository.worktrees()
+    }
+}
+
+#[message(response = Result<(), git2_ox::error::Error>)]
+pub struct PruneWorktree {
+    pub name: String,
+}
+
+impl Handler<PruneWorktree> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: PruneWorktree,
+    ) -> Result<(), git2_ox::error::Error> {
+        self.repository.prune_worktree(&msg.name)
+    }
+}
+
+#[message(response = Result<git2_ox::Worktree, git2_ox::error::Error>)]
+pub struct LockWorktree {
+    pub name: String,
+    /// Lock the worktree if true, otherwise unlock it
+    pub lock: bool,
+    pub reason: Option<String>,
+}
+
+impl Handler<LockWorktree> for GitActor {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        msg: LockWorktree,
+    ) -> Result<git2_ox::Worktree, git2_ox::error::Error> {
+        match msg.lock {
+            true => self
+                .repository
+                .lock_worktree(&msg.name, msg.reason.as_deref()),
+            false => self.repository.unlock_worktree(&msg.name),
+        }
+    }
+}
@@ -0,0 +1,582 @@
+//! Actor running shell commands at the Git revisions of nodes
+//!
+//! The revision of a node is checked out in a linked worktree in `.debug-flow/worktrees/`, so running a
+//! command never touches the working tree of the user. The output of the command is sent line by line as
+//! [`RunEvent`]s, the result is attached to the node and sets the state of status nodes. Commands are run one
+//! after another.
+
+use crate::flow::{self, Attachment, CommandResult, StatusNodeState};
+use hannibal::prelude::*;
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io,
+    process::Stdio,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, BufReader},
+    process,
+    sync::broadcast,
+    task::JoinHandle,
+};
+
+/// Timeout of commands if none is given
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Maximum number of bytes kept of the end of the standard output and the standard error output
+const MAX_OUTPUT_LEN: usize = 16 * 1024;
+
+/// Time to wait for the remaining output after the command finished
+///
+/// Processes started by the command may keep the output open after the command exited or was killed.
+const OUTPUT_GRACE_PERIOD: Duration = Duration::from_secs(1);
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Failed to run '{command}': {source}")]
+    Io {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error(transparent)]
+    Flow(#[from] flow::Error),
+    #[error(transparent)]
+    Git(#[from] git2_ox::error::Error),
+}
+
+/// Output stream of a command
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Progress of a command run
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum RunEventKind {
+    /// The revision of the node is checked out and the command started
+    #[serde(rename_all = "camelCase")]
+    Started { commit: String },
+    /// Line of the output of the command
+    #[serde(rename_all = "camelCase")]
+    Output { stream: OutputStream, line: String },
+    /// The command finished and the result is attached to the node
+    #[serde(rename_all = "camelCase")]
+    Finished { result: CommandResult },
+    /// The command could not be run or the result could not be stored
+    #[serde(rename_all = "camelCase")]
+    Failed { message: String },
+}
+
+/// Event of a command run sent to subscribers
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunEvent {
+    /// ID of the run, see [`next_run_id`]
+    pub run_id: u64,
+    /// ID of the debug flow
+    pub flow_id: String,
+    /// ID of the node the command is run for
+    pub node_id: String,
+    #[serde(flatten)]
+    pub kind: RunEventKind,
+}
+
+/// Get a new ID to identify the events of a run
+pub fn next_run_id() -> u64 {
+    static NEXT_RUN_ID: AtomicU64 = AtomicU64::new(1);
+    NEXT_RUN_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Nodes with a command queued or running in their worktree, by debug flow ID and node ID
+///
+/// Each node has the number of its queued and running commands.
+#[derive(Clone, Default)]
+pub struct ActiveRuns {
+    nodes: Arc<Mutex<HashMap<(String, String), usize>>>,
+}
+
+impl ActiveRuns {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(String, String), usize>> {
+        self.nodes
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Check if a command is queued or running in the worktree of a node of a debug flow
+    pub fn contains(&self, flow_id: &str, node_id: &str) -> bool {
+        self.lock()
+            .contains_key(&(flow_id.to_string(), node_id.to_string()))
+    }
+
+    /// Mark the node as running a command until the returned guard is finished or dropped
+    ///
+    /// The guard is part of [`RunCommand`], so the node is marked from the moment the command is queued.
+    pub fn start(&self, flow_id: &str, node_id: &str) -> ActiveRun {
+        let key = (flow_id.to_string(), node_id.to_string());
+        *self.lock().entry(key.clone()).or_default() += 1;
+        ActiveRun {
+            runs: self.clone(),
+            key: Some(key),
+        }
+    }
+}
+
+/// Command queued or running in the worktree of a node, see [`ActiveRuns::start`]
+pub struct ActiveRun {
+    runs: ActiveRuns,
+    key: Option<(String, String)>,
+}
+
+impl ActiveRun {
+    /// Unmark the node, unless other commands are queued or running in its worktree
+    fn finish(&mut self) {
+        let Some(key) = self.key.take() else {
+            return;
+        };
+        let mut nodes = self.runs.lock();
+        if let Some(count) = nodes.get_mut(&key) {
+            *count -= 1;
+            if *count == 0 {
+                nodes.remove(&key);
+            }
+        }
+    }
+}
+
+impl Drop for ActiveRun {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+#[derive(Actor)]
+pub struct CommandRunner {
+    flows_dir: flow::FlowsDir,
+    events_tx: broadcast::Sender<RunEvent>,
+}
+pub type CommandRunnerAddr = Addr<CommandRunner>;
+
+impl CommandRunner {
+    pub fn new(flows_dir: flow::FlowsDir, events_tx: broadcast::Sender<RunEvent>) -> Self {
+        Self {
+            flows_dir,
+            events_tx,
+        }
+    }
+
+    fn send(&self, msg: &RunCommand, kind: RunEventKind) {
+        // Sending only fails if there are no subscribers
+        let _ = self.events_tx.send(RunEvent {
+            run_id: msg.run_id,
+            flow_id: msg.flow_id.clone(),
+            node_id: msg.node_id.clone(),
+            kind,
+        });
+    }
+
+    async fn run(&self, msg: &RunCommand) -> Result<CommandRun, Error> {
+        let flow = self.flows_dir.get_flow_by_id(&msg.flow_id)?;
+        let rev = flow.data().find_node(&msg.node_id)?.revision()?.to_string();
+        let repo = git2_ox::Repository::try_open(self.flows_dir.git_repo())?;
+        let path = self.flows_dir.worktree_path(&msg.flow_id, &msg.node_id)?;
+        let name = flow::FlowsDir::worktree_name(&msg.flow_id, &msg.node_id);
+        let commit = repo.checkout_worktree(&name, &path, &rev)?;
+        let commit = git2_ox::CommitProperties::id(&commit).to_string();
+        self.send(
+            msg,
+            RunEventKind::Started {
+                commit: commit.clone(),
+            },
+        );
+
+        let io_error = |source| Error::Io {
+            command: msg.command.clone(),
+            source,
+        };
+        let started_at = chrono::Utc::now();
+        let mut child = process::Command::new("sh")
+            .arg("-c")
+            .arg(&msg.command)
+            .current_dir(&path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // The shell leads a new process group, so the processes started by the command are killed with it
+            .process_group(0)
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(io_error)?;
+        let mut group = ProcessGroup::new(&msg.command, child.id());
+        let stdout = self.capture(msg, OutputStream::Stdout, child.stdout.take());
+        let stderr = self.capture(msg, OutputStream::Stderr, child.stderr.take());
+
+        let (exit_code, timed_out) = match tokio::time::timeout(msg.timeout, child.wait()).await {
+            Ok(status) => {
+                group.release();
+                (status.map_err(io_error)?.code(), false)
+            }
+            Err(_) => {
+                log::info!("Killing '{}' after {:?}", msg.command, msg.timeout);
+                group.kill();
+                if let Err(e) = child.kill().await {
+                    log::warn!("Failed to kill '{}': {e}", msg.command);
+                }
+                (None, true)
+            }
+        };
+        let result = CommandResult {
+            command: msg.command.clone(),
+            commit,
+            exit_code,
+            timed_out,
+            stdout: stdout.finish().await,
+            stderr: stderr.finish().await,
+            started_at,
+            finished_at: chrono::Utc::now(),
+        };
+
+        let revision = self.record(msg, &result)?;
+        Ok(CommandRun { result, revision })
+    }
+
+    /// Send the lines of an output stream of the command to the subscribers and keep the end of it
+    fn capture<R>(&self, msg: &RunCommand, stream: OutputStream, reader: Option<R>) -> Capture
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        let output = Arc::new(Mutex::new(String::new()));
+        let events_tx = self.events_tx.clone();
+        let (run_id, flow_id, node_id) = (msg.run_id, msg.flow_id.clone(), msg.node_id.clone());
+        let task_output = output.clone();
+        let task = tokio::spawn(async move {
+            let Some(reader) = reader else {
+                return;
+            };
+            let mut reader = BufReader::new(reader);
+            let mut buf = Vec::new();
+            while let Ok(1..) = reader.read_until(b'\n', &mut buf).await {
+                let line = String::from_utf8_lossy(&buf);
+                append_tail(&mut task_output.lock().unwrap(), &line, MAX_OUTPUT_LEN);
+                let _ = events_tx.send(RunEvent {
+                    run_id,
+                    flow_id: flow_id.clone(),
+                    node_id: node_id.clone(),
+                    kind: RunEventKind::Output {
+                        stream,
+                        line: line.trim_end_matches(['\n', '\r']).to_string(),
+                    },
+                });
+                buf.clear();
+            }
+        });
+        Capture { task, output }
+    }
+
+    /// Attach the result to the node and set the state of status nodes, returns the new revision of the flow
+    fn record(&self, msg: &RunCommand, result: &CommandResult) -> Result<String, Error> {
+        let flow = self.flows_dir.get_flow_by_id(&msg.flow_id)?;
+        let mut data = flow.data().clone();
+        let node = data.find_node_mut(&msg.node_id)?;
+        node.add_attachment(Attachment::CommandResult(result.clone()));
+        node.set_state(match result.is_success() {
+            true => StatusNodeState::Success,
+            false => StatusNodeState::Fail,
+        });
+        Ok(self
+            .flows_dir
+            .save_flow(&msg.flow_id, &data, flow.revision())?)
+    }
+}
+
+/// Process group of a running command
+///
+/// All processes of the group are killed when it is dropped, e.g. if the run is aborted, unless the group was
+/// released before.
+struct ProcessGroup {
+    command: String,
+    /// ID of the process group, the same as the process ID of the shell leading it
+    id: Option<rustix::process::Pid>,
+}
+
+impl ProcessGroup {
+    fn new(command: &str, id: Option<u32>) -> Self {
+        Self {
+            command: command.to_string(),
+            id: id.and_then(|id| rustix::process::Pid::from_raw(i32::try_from(id).ok()?)),
+        }
+    }
+
+    /// Kill all processes of the group
+    fn kill(&mut self) {
+        let Some(id) = self.id.take() else {
+            return;
+        };
+        if let Err(e) = rustix::process::kill_process_group(id, rustix::process::Signal::KILL) {
+            log::warn!("Failed to kill '{}': {e}", self.command);
+        }
+    }
+
+    /// Keep the processes of the group running when the group is dropped
+    fn release(&mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for ProcessGroup {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+/// Capture of an output stream of a running command
+struct Capture {
+    task: JoinHandle<()>,
+    output: Arc<Mutex<String>>,
+}
+
+impl Capture {
+    /// Wait for the end of the output and get the captured output
+    async fn finish(self) -> String {
+        let abort = self.task.abort_handle();
+        if tokio::time::timeout(OUTPUT_GRACE_PERIOD, self.task)
+            .await
+            .is_err()
+        {
+            abort.abort();
+        }
+        std::mem::take(&mut self.output.lock().unwrap())
+    }
+}
+
+/// Append `text` to `output` and drop the start of `output` if it gets longer than `max_len` bytes
+fn append_tail(output: &mut String, text: &str, max_len: usize) {
+    output.push_str(text);
+    if output.len() > max_len {
+        let mut start = output.len() - max_len;
+        while !output.is_char_boundary(start) {
+            start += 1;
+        }
+        output.drain(..start);
+    }
+}
+
+/// Result of a command run and the revision of the debug flow with the attached result
+#[derive(Clone, Debug)]
+pub struct CommandRun {
+    pub result: CommandResult,
+    pub revision: String,
+}
+
+/// Run a shell command at the revision of a node and attach the result to the node
+#[message(response = Result<CommandRun, Error>)]
+pub struct RunCommand {
+    /// ID of the run, see [`next_run_id`]
+    pub run_id: u64,
+    pub flow_id: String,
+    pub node_id: String,
+    /// Shell command, it is run with `sh -c` in the root of the worktree
+    pub command: String,
+    /// The command is killed if it runs longer
+    pub timeout: Duration,
+    /// Marks the node as running a command until the message is handled
+    pub active: ActiveRun,
+}
+
+impl Handler<RunCommand> for CommandRunner {
+    async fn handle(
+        &mut self,
+        _ctx: &mut Context<Self>,
+        mut msg: RunCommand,
+    ) -> Result<CommandRun, Error> {
+        let run = self.run(&msg).await;
+        msg.active.finish();
+        match &run {
+            Ok(run) => self.send(
+                &msg,
+                RunEventKind::Finished {
+                    result: run.result.clone(),
+                },
+            ),
+            Err(e) => self.send(
+                &msg,
+                RunEventKind::Failed {
+                    message: e.to_string(),
+                },
+            ),
+        }
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_tail() {
+        let mut output = String::new();
+        append_tail(&mut output, "abc\n", 8);
+        append_tail(&mut output, "def\n", 8);
+        assert_eq!(output, "abc\ndef\n");
+        append_tail(&mut output, "g\n", 8);
+        assert_eq!(output, "c\ndef\ng\n");
+        // The start is moved to the next character boundary
+        append_tail(&mut output, "äöü", 5);
+        assert_eq!(output, "öü");
+    }
+
+    #[tokio::test]
+    async fn test_run_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2_ox::Repository::try_init(dir.path()).unwrap();
+        let git = repo.repo();
+        let mut config = git.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = git.signature().unwrap();
+        std::fs::write(dir.path().join("answer.txt"), "42\n").unwrap();
+        let mut index = git.index().unwrap();
+        index.add_path(std::path::Path::new("answer.txt")).unwrap();
+        let tree = git.find_tree(index.write_tree().unwrap()).unwrap();
+        let commit = git
+            .commit(Some("HEAD"), &signature, &signature, "Answer", &tree, &[])
+            .unwrap()
+            .to_string();
+
+        let flows_dir = flow::FlowsDir::try_new(dir.path()).unwrap();
+        flows_dir.create_flow("Runs", None, false).unwrap();
+        let data: flow::FlowData = serde_json::from_value(serde_json::json!({
+            "name": "Runs",
+            "reactflow": {"nodes": [
+                {"id": "1", "type": "statusNode", "position": {"x": 0, "y": 0},
+                 "data": {"title": "", "description": "", "state": "unknown", "isRootNode": true,
+                          "git": {"rev": "HEAD", "summary": "", "type": "branch"}}},
+                {"id": "2", "type": "actionNode", "position": {"x": 0, "y": 0},
+                 "data": {"title": "", "description": "", "git": null}}
+            ], "edges": []}
+        }))
+        .unwrap();
+        flows_dir.save_flow("runs", &data, None).unwrap();
+
+        let (events_tx, mut events_rx) = broadcast::channel(16);
+        let active_runs = ActiveRuns::new();
+        let runner = CommandRunner::new(flows_dir.clone(), events_tx).spawn();
+        let run = |node_id: &str, command: &str, timeout| RunCommand {
+            run_id: next_run_id(),
+            flow_id: "runs".to_string(),
+            node_id: node_id.to_string(),
+            command: command.to_string(),
+            timeout,
+            active: active_runs.start("runs", node_id),
+        };
+
+        let msg = run("1", "cat answer.txt; echo oops >&2", DEFAULT_TIMEOUT);
+        let run_id = msg.run_id;
+        let done = runner.call(msg).await.unwrap().unwrap();
+        assert!(done.result.is_success());
+        assert_eq!(done.result.commit, commit);
+        assert_eq!(done.result.stdout, "42\n");
+        assert_eq!(done.result.stderr, "oops\n");
+        let mut kinds = Vec::new();
+        while let Ok(event) = events_rx.try_recv() {
+            assert_eq!(event.run_id, run_id);
+            kinds.push(event.kind);
+        }
+        assert_eq!(
+            kinds[0],
+            RunEventKind::Started {
+                commit: commit.clone()
+            }
+        );
+        assert!(kinds.contains(&RunEventKind::Output {
+            stream: OutputStream::Stdout,
+            line: "42".to_string()
+        }));
+        assert!(matches!(kinds.last(), Some(RunEventKind::Finished { .. })));
+
+        let flow = flows_dir.get_flow_by_id("runs").unwrap();
+        assert_eq!(flow.revision(), Some(done.revision.as_str()));
+        let node = flow.data().reactflow().node("1").unwrap();
+        assert_eq!(node.state(), Some(StatusNodeState::Success));
+        assert_eq!(node.attachments(), [Attachment::CommandResult(done.result)]);
+        // The command ran in the worktree of the node
+        let worktree = flows_dir.worktree_path("runs", "1").unwrap();
+        assert!(worktree.join("answer.txt").is_file());
+        assert!(worktree.starts_with(flows_dir.path()));
+
+        // The node is marked as running a command from queuing it until the command finished
+        let queued = run("1", "sleep 5", Duration::from_millis(100));
+        assert!(active_runs.contains("runs", "1"));
+        let (done, ()) = tokio::join!(runner.call(queued), async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert!(active_runs.contains("runs", "1"));
+            assert!(!active_runs.contains("runs", "2"));
+        });
+        let done = done.unwrap().unwrap();
+        assert!(!active_runs.contains("runs", "1"));
+        let (first, second) = (
+            run("2", "true", DEFAULT_TIMEOUT),
+            run("2", "true", DEFAULT_TIMEOUT),
+        );
+        drop(first);
+        assert!(active_runs.contains("runs", "2"));
+        drop(second);
+        assert!(!active_runs.contains("runs", "2"));
+        assert!(done.result.timed_out);
+        assert_eq!(done.result.exit_code, None);
+        let flow = flows_dir.get_flow_by_id("runs").unwrap();
+        let node = flow.data().reactflow().node("1").unwrap();
+        assert_eq!(node.state(), Some(StatusNodeState::Fail));
+        assert_eq!(node.attachments().len(), 2);
+
+        // Processes started by the command are killed with it
+        let command = "sleep 30 & echo $! > background.pid; wait";
+        let done = runner
+            .call(run("1", command, Duration::from_millis(200)))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(done.result.timed_out);
+        let pid = std::fs::read_to_string(worktree.join("background.pid")).unwrap();
+        let stat = std::path::Path::new("/proc").join(pid.trim()).join("stat");
+        let is_running = || {
+            std::fs::read_to_string(&stat)
+                .is_ok_and(|stat| stat.split_whitespace().nth(2).is_none_or(|s| s != "Z"))
+        };
+        for _ in 0..50 {
+            if !is_running() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(!is_running());
+
+        assert!(matches!(
+            runner
+                .call(run("2", "true", DEFAULT_TIMEOUT))
+                .await
+                .unwrap(),
+            Err(Error::Flow(flow::Error::NoRevision(_)))
+        ));
+        assert!(matches!(
+            runner
+                .call(run("3", "true", DEFAULT_TIMEOUT))
+                .await
+                .unwrap(),
+            Err(Error::Flow(flow::Error::NodeNotFound(_)))
+        ));
+    }
+}
@@ -0,0 +1,425 @@
+//! Headless command line interface operating directly on a debug flow directory
+
+use crate::flow;
+use clap::{Args, Subcommand};
+use serde::Serialize;
+use std::{io::Write, path};
+
+#[derive(Args)]
+pub struct FlowsArgs {
+    /// Path to a git repository
+    #[arg(short = 'C', long, default_value = "./", global = true)]
+    repo: path::PathBuf,
+    /// Print machine-readable JSON instead of tables
+    #[arg(long, default_value_t = false, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: FlowsCommand,
+}
+
+#[derive(Subcommand)]
+pub enum FlowsCommand {
+    /// List all debug flows
+    List,
+    /// Show the nodes of a debug flow
+    Show {
+        /// ID of the debug flow
+        id: String,
+    },
+    /// Create a new debug flow
+    New {
+        /// Name of the debug flow
+        name: String,
+        /// ID of the template to seed the nodes and edges from, see `flows templates`
+        #[arg(short, long)]
+        template: Option<String>,
+        /// Overwrite an existing debug flow with the same ID
+        #[arg(long, default_value_t = false)]
+        force: bool,
+    },
+    /// List the templates in `.debug-flow/templates/`
+    Templates,
+    /// Delete a debug flow
+    Delete {
+        /// ID of the debug flow
+        id: String,
+    },
+    /// Rename a debug flow, this changes the ID of the debug flow
+    Rename {
+        /// ID of the debug flow
+        id: String,
+        /// New name of the debug flow
+        new_name: String,
+    },
+    /// Compare a debug flow in a Git revision with the stored debug flow
+    Diff {
+        /// ID of the debug flow
+        id: String,
+        /// Git revision of the repository, e.g. `HEAD` or `origin/main`
+        rev: String,
+    },
+    /// Export a debug flow to a text format
+    Export {
+        /// ID of the debug flow
+        id: String,
+        /// Format to export the debug flow to
+        #[arg(short, long, value_enum, default_value_t = flow::export::Format::Markdown)]
+        format: flow::export::Format,
+        /// File to write the export to, defaults to stdout
+        #[arg(short, long)]
+        output: Option<path::PathBuf>,
+    },
+}
+
+/// Name of the merge driver for debug flows in the git config and in `.gitattributes`
+pub const MERGE_DRIVER_NAME: &str = "debug-flow";
+
+#[derive(Args)]
+pub struct MergeDriverArgs {
+    /// Common ancestor of the debug flow, `%O` in the driver command
+    base: path::PathBuf,
+    /// Version of the current branch, `%A` in the driver command, the merge result is written to it
+    ours: path::PathBuf,
+    /// Version of the branch being merged, `%B` in the driver command
+    theirs: path::PathBuf,
+}
+
+#[derive(Args)]
+pub struct InstallMergeDriverArgs {
+    /// Path to a git repository
+    #[arg(short = 'C', long, default_value = "./")]
+    repo: path::PathBuf,
+}
+
+/// Run a `flows` subcommand
+pub fn run_flows(args: FlowsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let flows_dir = flow::FlowsDir::try_new(&args.repo)?;
+    let json = args.json;
+
+    match args.command {
+        FlowsCommand::List => {
+            let mut flows: Vec<flow::FlowMetadata> = flows_dir.metadatas()?.collect();
+            flows.sort_by(|a, b| b.last_modified_date().cmp(a.last_modified_date()));
+            if json {
+                return print_json(&flows);
+            }
+            print_table(
+                &[
+                    "ID",
+                    "NAME",
+                    "NODES",
+                    "EDGES",
+                    "LAST MODIFIED",
+                    "CREATED BY",
+                ],
+                flows.iter().map(|f| {
+                    vec![
+                        f.id().to_string(),
+                        f.name().to_string(),
+                        f.num_nodes().to_string(),
+                        f.num_edges().to_string(),
+                        f.last_modified_date().format("%Y-%m-%d %H:%M").to_string(),
+                        f.created_by().map(|a| a.name.clone()).unwrap_or_default(),
+                    ]
+                }),
+            );
+        }
+        FlowsCommand::Show { id } => {
+            let flow = flows_dir.get_flow_by_id(&id)?;
+            if json {
+                return print_json(flow.data());
+            }
+            println!("{} ({})", flow.name(), flow.id());
+            if let Some(revision) = flow.revision() {
+                println!("Revision: {revision}");
+            }
+            println!();
+            let reactflow = flow.data().reactflow();
+            print_table(
+                &["ID", "TYPE", "STATE", "REVISION", "TITLE"],
+                reactflow.nodes().iter().map(|n| {
+                    vec![
+                        n.id.clone(),
+                        match n.kind {
+                            flow::NodeKind::Action(_) => "action".to_string(),
+                            flow::NodeKind::Status(_) if n.is_root_node() => "root".to_string(),
+                            flow::NodeKind::Status(_) => "status".to_string(),
+                        },
+                        n.state().map(|s| s.as_str()).unwrap_or("").to_string(),
+                        n.git().map(|g| g.display_rev()).unwrap_or("").to_string(),
+                        n.title().to_string(),
+                    ]
+                }),
+            );
+        }
+        FlowsCommand::New {
+            name,
+            template,
+            force,
+        } => {
+            let flow = flows_dir.create_flow(&name, template.as_deref(), force)?;
+            print_flow(flow, json)?;
+        }
+        FlowsCommand::Templates => {
+            let templates = flows_dir.templates()?;
+            if json {
+                return print_json(&templates);
+            }
+            print_table(
+                &["ID", "NAME", "NODES", "EDGES"],
+                templates.iter().map(|t| {
+                    vec![
+                        t.id.clone(),
+                        t.name.clone(),
+                        t.num_nodes.to_string(),
+                        t.num_edges.to_string(),
+                    ]
+                }),
+            );
+        }
+        FlowsCommand::Delete { id } => {
+            flows_dir.delete_flow_by_id(&id)?;
+            if json {
+                return print_json(&serde_json::json!({ "id": id }));
+            }
+            println!("Deleted debug flow '{id}'");
+        }
+        FlowsCommand::Rename { id, new_name } => {
+            let flow = flows_dir.rename_flow(&id, &new_name)?;
+            print_flow(flow, json)?;
+        }
+        FlowsCommand::Diff { id, rev } => {
+            let diff = flows_dir.diff(&id, &rev)?;
+            if json {
+                return print_json(&diff);
+            }
+            print!("{}", format_diff(&diff));
+        }
+        FlowsCommand::Export { id, format, output } => {
+            let content = flows_dir.export(&id, format)?;
+            match output {
+                Some(path) => std::fs::write(path, content)?,
+                None => std::io::stdout().write_all(content.as_bytes())?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Run the git merge driver for debug flows, returns `false` if conflicts are left in the merged debug flow
+///
+/// The merged debug flow is written to the file of the current branch as git expects it.
+pub fn run_merge_driver(args: MergeDriverArgs) -> Result<bool, Box<dyn std::error::Error>> {
+    // The common ancestor is empty if the debug flow was added on both branches
+    let base = std::fs::read_to_string(&args.base)?;
+    let base = match base.trim() {
+        "" => None,
+        json => Some(flow::FlowData::from_json(json)?),
+    };
+    let ours = flow::FlowData::from_json(&std::fs::read_to_string(&args.ours)?)?;
+    let theirs = flow::FlowData::from_json(&std::fs::read_to_string(&args.theirs)?)?;
+
+    let outcome = flow::merge::merge(base.as_ref(), &ours, &theirs)?;
+    outcome.flow.to_file(&args.ours)?;
+    for conflict in &outcome.conflicts {
+        eprintln!(
+            "Conflict in debug flow '{}': {conflict}",
+            outcome.flow.name()
+        );
+    }
+    Ok(outcome.conflicts.is_empty())
+}
+
+/// Register the merge driver for debug flows in the git config and `.gitattributes` of a repository
+pub fn run_install_merge_driver(
+    args: InstallMergeDriverArgs,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = git2_ox::Repository::try_open(&args.repo)?;
+    let workdir = repo
+        .repo()
+        .workdir()
+        .ok_or("Merge drivers cannot be installed in bare repositories")?
+        .to_path_buf();
+
+    // Git runs the driver in a shell, quote the path of the executable
+    let exe = std::env::current_exe()?
+        .to_string_lossy()
+        .replace('\'', "'\\''");
+    repo.set_config(
+        &format!("merge.{MERGE_DRIVER_NAME}.name"),
+        "Merge debug flows node by node",
+    )?;
+    repo.set_config(
+        &format!("merge.{MERGE_DRIVER_NAME}.driver"),
+        &format!("'{exe}' merge-driver %O %A %B"),
+    )?;
+
+    let attributes_path = workdir.join(".gitattributes");
+    let attribute = format!(
+        "{}/*.json merge={MERGE_DRIVER_NAME}",
+        flow::FlowsDir::DIR_NAME
+    );
+    let mut attributes = match std::fs::read_to_string(&attributes_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    if !attributes.lines().any(|line| line.trim() == attribute) {
+        if !attributes.is_empty() && !attributes.ends_with('\n') {
+            attributes.push('\n');
+        }
+        attributes.push_str(&attribute);
+        attributes.push('\n');
+        std::fs::write(&attributes_path, attributes)?;
+    }
+    println!(
+        "Installed merge driver '{MERGE_DRIVER_NAME}' for {}",
+        attributes_path.display()
+    );
+    Ok(())
+}
+
+/// Print the metadata of a single debug flow
+fn print_flow(flow: flow::Flow, json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = flow::FlowMetadata::try_from(flow)?;
+    if json {
+        return print_json(&metadata);
+    }
+    println!("{} ({})", metadata.name(), metadata.id());
+    Ok(())
+}
+
+fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Describe a node in a single line
+fn describe_node(node: &flow::Node) -> String {
+    let kind = match node.kind {
+        flow::NodeKind::Action(_) => "action",
+        flow::NodeKind::Status(_) => "status",
+    };
+    format!("node {} ({kind}) \"{}\"", node.id, node.title())
+}
+
+/// Render a diff of a debug flow similar to a unified diff, one change per line
+fn format_diff(diff: &flow::diff::FlowDiff) -> String {
+    if diff.is_empty() {
+        return "No changes\n".to_string();
+    }
+
+    let mut out = String::new();
+    let push_changes = |out: &mut String, changes: &[flow::diff::FieldChange]| {
+        for change in changes {
+            out.push_str(&format!(
+                "    {}: {} -> {}\n",
+                change.field, change.old, change.new
+            ));
+        }
+    };
+
+    if let Some(name) = &diff.name {
+        out.push_str(&format!("~ name: {} -> {}\n", name.old, name.new));
+    }
+    for node in &diff.removed_nodes {
+        out.push_str(&format!("- {}\n", describe_node(node)));
+    }
+    for node in &diff.added_nodes {
+        out.push_str(&format!("+ {}\n", describe_node(node)));
+    }
+    for change in &diff.changed_nodes {
+        out.push_str(&format!("~ node {}\n", change.id));
+        push_changes(&mut out, &change.changes);
+    }
+    for edge in &diff.removed_edges {
+        out.push_str(&format!(
+            "- edge {}: {} -> {}\n",
+            edge.id, edge.source, edge.target
+        ));
+    }
+    for edge in &diff.added_edges {
+        out.push_str(&format!(
+            "+ edge {}: {} -> {}\n",
+            edge.id, edge.source, edge.target
+        ));
+    }
+    for change in &diff.changed_edges {
+        out.push_str(&format!("~ edge {}\n", change.id));
+        push_changes(&mut out, &change.changes);
+    }
+    out
+}
+
+/// Print rows as a table with left-aligned columns
+fn print_table<I>(headers: &[&str], rows: I)
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let rows: Vec<Vec<String>> = rows.into_iter().collect();
+    print!("{}", format_table(headers, &rows));
+}
+
+fn format_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| {
+        let line = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        format!("{}\n", line.trim_end())
+    };
+
+    let mut out = format_row(headers.to_vec());
+    for row in rows {
+        out.push_str(&format_row(row.iter().map(String::as_str).collect()));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_diff() {
+        let old: flow::FlowData = serde_json::from_value(serde_json::json!({
+            "name": "Flow",
+            "reactflow": {
+                "nodes": [{"id": "1", "type": "statusNode", "position": {"x": 0.0, "y": 0.0},
+                           "data": {"title": "Root", "description": "", "git": null, "state": "progress", "isRootNode": true}}],
+                "edges": []
+            }
+        }))
+        .unwrap();
+        let mut new_json = serde_json::to_value(&old).unwrap();
+        new_json["reactflow"]["nodes"][0]["data"]["state"] = "success".into();
+        let new: flow::FlowData = serde_json::from_value(new_json).unwrap();
+
+        assert_eq!(
+            format_diff(&flow::diff::diff(&old, &new)),
+            "~ node 1\n    data.state: \"progress\" -> \"success\"\n"
+        );
+        assert_eq!(format_diff(&flow::diff::diff(&old, &old)), "No changes\n");
+    }
+
+    #[test]
+    fn test_format_table() {
+        let rows = vec![
+            vec!["crash".to_string(), "Crash on start".to_string()],
+            vec!["perf-regression".to_string(), "Perf".to_string()],
+        ];
+        assert_eq!(
+            format_table(&["ID", "NAME"], &rows),
+            "ID               NAME\ncrash            Crash on start\nperf-regression  Perf\n"
+        );
+    }
+}
@@ -1,20 +1,68 @@
 use crate::utils;
 use serde::{Deserialize, Serialize};
-use std::{ffi, fmt, fs, io, path};
+use std::{
+    ffi, fmt, fs, io, path,
+    sync::{Arc, Mutex},
+};
 use utoipa::ToSchema;
 
+pub mod bisect;
+pub mod diff;
+pub mod events;
+pub mod export;
+pub mod history;
+pub mod merge;
+pub mod migration;
+pub mod model;
+pub mod pins;
+pub mod search;
+pub mod template;
+pub mod validation;
+
+pub use events::{FlowEvent, FlowRevisions};
+pub use model::{
+    Attachment, Author, CommandResult, Edge, Node, NodeKind, StashRecord, StatusNodeState,
+};
+pub use search::SearchIndex;
+
 #[derive(thiserror::Error)]
 pub enum Error {
     #[error("Path '{0}' is not a directory.")]
     NotADirectory(path::PathBuf),
-    #[error("Debug flow '{0}' exists already in directory '{1}'.")]
-    DebugFlowExistsAlready(String, path::PathBuf),
-    #[error("I/O error")]
+    #[error("Debug flow '{id}' for the name '{name}' exists already in directory '{path}'.", path = .path.display())]
+    DebugFlowExistsAlready {
+        name: String,
+        id: String,
+        path: path::PathBuf,
+    },
+    #[error("Invalid debug flow ID '{0}'")]
+    InvalidFlowId(String),
+    #[error("I/O error on '{}': {}", .0.display(), .1)]
     Io(path::PathBuf, #[source] io::Error),
     #[error("Failed to read directory entry: {0}")]
     EntryError(#[from] io::Error),
-    #[error("JSON error")]
+    #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("Invalid debug flow: {0}")]
+    InvalidFlow(String),
+    #[error("Unsupported debug flow schema version {0}, the latest supported version is {latest}", latest = migration::CURRENT_SCHEMA_VERSION)]
+    UnsupportedSchemaVersion(u32),
+    #[error("Node '{0}' not found")]
+    NodeNotFound(String),
+    #[error("Node '{0}' is not linked to a Git revision")]
+    NoRevision(String),
+    #[error("Debug flow '{id}' was modified in the meantime, the current revision is '{revision}'")]
+    RevisionConflict { id: String, revision: String },
+    #[error("No bisection of debug flow '{0}' is running")]
+    NoBisection(String),
+    #[error("The bisection of debug flow '{0}' is finished")]
+    BisectionFinished(String),
+    #[error("A command is testing the commits of the bisection of debug flow '{0}'")]
+    BisectionRunning(String),
+    #[error("Node '{node_id}' is not the commit to test of the bisection of debug flow '{id}'")]
+    NotBisectionNode { id: String, node_id: String },
+    #[error("Git error: {0}")]
+    Git(#[from] git2_ox::error::Error),
 }
 
 impl fmt::Debug for Error {
@@ -30,19 +78,33 @@ pub struct FlowsDir {
     git_repo: path::PathBuf,
     /// Path of the debug flow directory
     path: path::PathBuf,
+    /// Lock serializing the check of the revision and the write of a flow
+    write_lock: Arc<Mutex<()>>,
 }
 
 impl FlowsDir {
-    const DIR_NAME: &str = ".debug-flow";
+    /// Name of the debug flow directory in the root of the repository
+    pub const DIR_NAME: &str = ".debug-flow";
+    /// Name of the directory in the debug flow directory containing the worktrees of nodes
+    pub const WORKTREES_DIR_NAME: &str = "worktrees";
+    /// Prefix of the names of the linked worktrees of nodes in the repository
+    pub const WORKTREE_NAME_PREFIX: &str = "debug-flow-";
+
     pub fn try_new<P>(git_repo: P) -> Result<FlowsDir, Error>
     where
         P: AsRef<std::path::Path>,
     {
-        let git_repo = git_repo.as_ref();
+        // File system events are reported with absolute paths, they can only be matched against the
+        // paths of the debug flows and worktrees if the repository path is absolute as well
+        let git_repo = git_repo
+            .as_ref()
+            .canonicalize()
+            .map_err(|e| Error::Io(git_repo.as_ref().to_path_buf(), e))?;
         let path = git_repo.join(Self::DIR_NAME);
         let p = FlowsDir {
-            git_repo: git_repo.to_path_buf(),
+            git_repo,
             path,
+            write_lock: Arc::new(Mutex::new(())),
         };
         match p.create_if_not_exists() {
             Ok(()) => Ok(p),
@@ -88,21 +150,154 @@ impl FlowsDir {
     }
 
     /// Create a debug flow with a name and store it in the debug flow directory
-    pub fn create_flow(&self, name: &str, force: bool) -> Result<Flow, Error> {
+    ///
+    /// * `name` - Name of the debug flow
+    /// * `template` - ID of the template to seed the nodes and edges from, see [`template`]
+    /// * `force` - Overwrite an existing debug flow with the same ID
+    pub fn create_flow(
+        &self,
+        name: &str,
+        template: Option<&str>,
+        force: bool,
+    ) -> Result<Flow, Error> {
         let mut flow_path = self.path.clone();
         flow_path.push(FlowData::file_name_from_flow_name(name));
 
+        // Concurrent creations of the same debug flow must not both pass the existence check
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
         match !flow_path.is_file() || force {
             true => {
-                let p = Flow::new(&self.path, name);
+                let mut p = match template {
+                    Some(template) => Flow::from_flows_dir_and_data(
+                        &self.path,
+                        template::instantiate(
+                            &self.get_template(template)?,
+                            name,
+                            &self.placeholders(),
+                        )?,
+                    ),
+                    None => Flow::new(&self.path, name),
+                };
+                p.data.touch(self.author());
                 p.to_file()?;
+                self.sync_pins(&p.id(), Some(&p.data));
                 Ok(p)
             }
-            false => Err(Error::DebugFlowExistsAlready(
-                name.to_string(),
-                self.path.clone(),
-            )),
+            false => Err(Error::DebugFlowExistsAlready {
+                name: name.to_string(),
+                id: utils::slugify(name),
+                path: self.path.clone(),
+            }),
+        }
+    }
+
+    /// Get the path of the linked worktree the revision of a node of a debug flow is checked out in
+    ///
+    /// The directory containing the worktrees is created and ignored by Git if it does not exist yet.
+    pub fn worktree_path(&self, flow_id: &str, node_id: &str) -> Result<path::PathBuf, Error> {
+        let dir = self.worktrees_path();
+        if !dir.is_dir() {
+            fs::create_dir_all(&dir).map_err(|e| Error::Io(dir.clone(), e))?;
+            let ignore = dir.join(".gitignore");
+            fs::write(&ignore, "*\n").map_err(|e| Error::Io(ignore, e))?;
+        }
+        Ok(dir
+            .join(utils::encode_component(flow_id))
+            .join(utils::encode_component(node_id)))
+    }
+
+    /// Get the path of the directory containing the linked worktrees of nodes
+    pub fn worktrees_path(&self) -> path::PathBuf {
+        self.path.join(Self::WORKTREES_DIR_NAME)
+    }
+
+    /// Check if `path` is in the directory containing the linked worktrees of nodes
+    ///
+    /// Commands run in the worktrees can change lots of files, these changes concern neither the debug
+    /// flows nor the working directory of the repository.
+    pub fn is_in_worktrees(&self, path: &path::Path) -> bool {
+        path.starts_with(self.worktrees_path())
+    }
+
+    /// Get the name of the linked worktree of a node of a debug flow in the repository
+    ///
+    /// Node IDs are only unique within a debug flow, the name contains both IDs separated by a `.` which is
+    /// never part of an encoded ID.
+    pub fn worktree_name(flow_id: &str, node_id: &str) -> String {
+        format!(
+            "{}{}.{}",
+            Self::WORKTREE_NAME_PREFIX,
+            utils::encode_component(flow_id),
+            utils::encode_component(node_id)
+        )
+    }
+
+    /// Get the IDs of the debug flow and the node a linked worktree belongs to, `None` if it is not the
+    /// worktree of a node
+    pub fn worktree_node(name: &str) -> Option<(String, String)> {
+        let (flow_id, node_id) = name
+            .strip_prefix(Self::WORKTREE_NAME_PREFIX)?
+            .split_once('.')?;
+        Some((
+            utils::decode_component(flow_id)?,
+            utils::decode_component(node_id)?,
+        ))
+    }
+
+    /// Get the path of the directory containing the templates
+    pub fn templates_path(&self) -> path::PathBuf {
+        self.path.join(template::TEMPLATES_DIR_NAME)
+    }
+
+    /// Get the metadata of all templates ordered by their ID, the list is empty if there is no template
+    /// directory
+    pub fn templates(&self) -> Result<Vec<template::TemplateMetadata>, Error> {
+        let path = self.templates_path();
+        let entries = match fs::read_dir(&path) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(Error::Io(path, e)),
+        };
+        let mut templates: Vec<template::TemplateMetadata> = entries
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if !path.is_file() || path.extension() != Some(ffi::OsStr::new("json")) {
+                    return None;
+                }
+                let id = path.file_stem()?.to_str()?.to_string();
+                match FlowData::from_file(&path) {
+                    Ok(data) => Some(template::TemplateMetadata::new(&id, &data)),
+                    Err(e) => {
+                        log::warn!("Error reading template '{}': {e}", path.display());
+                        None
+                    }
+                }
+            })
+            .collect();
+        templates.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(templates)
+    }
+
+    /// Load a template from the template directory
+    pub fn get_template(&self, id: &str) -> Result<FlowData, Error> {
+        if !utils::is_safe_file_stem(id) {
+            return Err(Error::InvalidFlowId(id.to_string()));
         }
+        let path = self.templates_path().join(FlowData::file_name_from_id(id));
+        let content = fs::read_to_string(&path).map_err(|e| Error::Io(path.clone(), e))?;
+        FlowData::from_json(&content)
+    }
+
+    /// Get the values of the template placeholders from the current status of the repository
+    fn placeholders(&self) -> template::Placeholders {
+        git2_ox::Repository::try_open(&self.git_repo)
+            .and_then(|repo| repo.status())
+            .map(|status| template::Placeholders::from_status(&status))
+            .inspect_err(|e| log::debug!("Template placeholders are not resolved: {e}"))
+            .unwrap_or_default()
     }
 
     /// Load a debug flow from the storage directory
@@ -115,10 +310,20 @@ impl FlowsDir {
         Flow::from_flows_dir_and_id(&self.path, id)
     }
 
+    /// Get the path of the file of a debug flow
+    ///
+    /// Returns [`Error::InvalidFlowId`] if the ID would refer to a file outside of the debug flow directory.
+    pub fn flow_path(&self, id: &str) -> Result<path::PathBuf, Error> {
+        if !utils::is_safe_file_stem(id) {
+            return Err(Error::InvalidFlowId(id.to_string()));
+        }
+        Ok(self.path.join(FlowData::file_name_from_id(id)))
+    }
+
     pub fn delete_flow_by_id(&self, id: &str) -> Result<(), Error> {
-        let mut p = self.path.clone();
-        p.push(FlowData::file_name_from_id(id));
-        fs::remove_file(p)?;
+        let p = self.flow_path(id)?;
+        fs::remove_file(&p).map_err(|e| Error::Io(p, e))?;
+        self.sync_pins(id, None);
         Ok(())
     }
 
@@ -157,22 +362,261 @@ impl FlowsDir {
             }))
     }
 
-    /// Save a debug flow to the debug flow directory
-    pub fn save_flow(&self, debug_flow: &FlowData) -> Result<(), Error> {
-        let mut p = self.path.clone();
-        p.push(FlowData::file_name_from_flow_name(&debug_flow.name));
-        debug_flow.to_file(&p)?;
+    /// Render a debug flow into a Markdown report
+    ///
+    /// The Git revisions of the nodes are resolved in the repository to include the summary, author and
+    /// hash of the commits.
+    pub fn export_markdown(&self, id: &str) -> Result<String, Error> {
+        let flow = self.get_flow_by_id(id)?;
+        let repo = git2_ox::Repository::try_open(&self.git_repo)?;
+        Ok(export::to_markdown(flow.data(), |git| {
+            repo.get_commit_for_revision(git.rev())
+                .ok()
+                .map(|c| c.commit().clone())
+        }))
+    }
+
+    /// Render a debug flow into a text format
+    pub fn export(&self, id: &str, format: export::Format) -> Result<String, Error> {
+        match format {
+            export::Format::Markdown => self.export_markdown(id),
+            export::Format::Mermaid => Ok(export::to_mermaid(self.get_flow_by_id(id)?.data())),
+            export::Format::Dot => Ok(export::to_dot(self.get_flow_by_id(id)?.data())),
+        }
+    }
+
+    /// Load a debug flow as it is stored in a Git revision of the repository
+    ///
+    /// * `id` - ID of the debug flow
+    /// * `rev` - Revision of the repository, e.g. a commit hash, a branch or `HEAD`
+    pub fn get_flow_at_revision(&self, id: &str, rev: &str) -> Result<FlowData, Error> {
+        self.flow_path(id)?;
+        let path = path::Path::new(Self::DIR_NAME).join(FlowData::file_name_from_id(id));
+        let repo = git2_ox::Repository::try_open(&self.git_repo)?;
+        let content = repo.read_file(rev, &path.to_string_lossy())?;
+        FlowData::from_json(&String::from_utf8_lossy(&content))
+    }
+
+    /// Compare a debug flow as it is stored in a Git revision with the stored debug flow
+    ///
+    /// * `id` - ID of the debug flow
+    /// * `rev` - Revision of the repository the stored debug flow is compared to
+    pub fn diff(&self, id: &str, rev: &str) -> Result<diff::FlowDiff, Error> {
+        let old = self.get_flow_at_revision(id, rev)?;
+        let new = self.get_flow_by_id(id)?;
+        Ok(diff::diff(&old, new.data()))
+    }
+
+    /// Validate the Git revisions linked to the nodes of a debug flow, see [`validation::validate`]
+    pub fn validate(&self, id: &str) -> Result<validation::FlowValidation, Error> {
+        let flow = self.get_flow_by_id(id)?;
+        let repo = git2_ox::Repository::try_open(&self.git_repo)?;
+        Ok(validation::validate(flow.data(), &repo))
+    }
+
+    /// Pin the Git revisions of all nodes of a debug flow to full commit hashes
+    ///
+    /// Returns the revision of the debug flow and the number of pinned nodes, the debug flow is only stored
+    /// if a node was pinned.
+    pub fn pin_revisions(&self, id: &str) -> Result<(String, usize), Error> {
+        let flow = self.get_flow_by_id(id)?;
+        let repo = git2_ox::Repository::try_open(&self.git_repo)?;
+        let mut data = flow.data().clone();
+        let pinned = validation::pin_revisions(&mut data, &repo);
+        let revision = match (pinned, flow.revision()) {
+            (0, Some(revision)) => revision.to_string(),
+            _ => self.save_flow(id, &data, flow.revision())?,
+        };
+        Ok((revision, pinned))
+    }
+
+    /// Rename a debug flow, this changes the ID of the debug flow and moves its file
+    ///
+    /// * `id` - ID of the debug flow to rename
+    /// * `new_name` - New name of the debug flow
+    ///
+    /// Returns [`Error::DebugFlowExistsAlready`] if another debug flow with the ID of the new name exists.
+    pub fn rename_flow(&self, id: &str, new_name: &str) -> Result<Flow, Error> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let old = self.get_flow_by_id(id)?;
+        let mut data = old.data().clone();
+        data.name = new_name.to_string();
+        if data.id() != id {
+            self.ensure_flow_does_not_exist(new_name)?;
+        }
+        data.touch(self.author());
+
+        let mut renamed = Flow::from_flows_dir_and_data(&self.path, data);
+        renamed.to_file()?;
+        self.sync_pins(&renamed.id(), Some(&renamed.data));
+        if renamed.path != old.path {
+            fs::remove_file(&old.path).map_err(|e| Error::Io(old.path.clone(), e))?;
+            self.sync_pins(id, None);
+        }
+        Ok(renamed)
+    }
+
+    /// Store a copy of a debug flow under a new name
+    ///
+    /// * `id` - ID of the debug flow to copy
+    /// * `new_name` - Name of the copy, the ID of the copy is derived from it and must not exist yet
+    pub fn duplicate_flow(&self, id: &str, new_name: &str) -> Result<Flow, Error> {
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut data = self.get_flow_by_id(id)?.data().clone();
+        data.name = new_name.to_string();
+        self.ensure_flow_does_not_exist(new_name)?;
+        // The copy is a new debug flow
+        data.created_at = None;
+        data.touch(self.author());
+
+        let mut duplicate = Flow::from_flows_dir_and_data(&self.path, data);
+        duplicate.to_file()?;
+        self.sync_pins(&duplicate.id(), Some(&duplicate.data));
+        Ok(duplicate)
+    }
+
+    /// Fail with [`Error::DebugFlowExistsAlready`] if a debug flow with the ID derived from `name` exists
+    fn ensure_flow_does_not_exist(&self, name: &str) -> Result<(), Error> {
+        if self
+            .path
+            .join(FlowData::file_name_from_flow_name(name))
+            .exists()
+        {
+            return Err(Error::DebugFlowExistsAlready {
+                name: name.to_string(),
+                id: utils::slugify(name),
+                path: self.path.clone(),
+            });
+        }
         Ok(())
     }
+
+    /// Get the revision of the file at `path`, `None` if the file does not exist
+    fn file_revision(path: &path::Path) -> Result<Option<String>, Error> {
+        match fs::read(path) {
+            Ok(content) => Ok(Some(git2_ox::utils::hash_blob(&content)?)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(Error::Io(path.to_path_buf(), e)),
+        }
+    }
+
+    /// Get the revision of a stored debug flow without parsing it, `None` if the debug flow does not exist
+    pub fn revision_by_id(&self, id: &str) -> Result<Option<String>, Error> {
+        Self::file_revision(&self.flow_path(id)?)
+    }
+
+    /// Get the ID of the debug flow stored at `path`, `None` if `path` is not a debug flow file in this directory
+    ///
+    /// The file at `path` does not need to exist anymore, this allows to map events of deleted files.
+    pub fn flow_id_from_path(&self, path: &path::Path) -> Option<String> {
+        if path.extension() != Some(ffi::OsStr::new("json")) || self.is_in_worktrees(path) {
+            return None;
+        }
+        let dir = fs::canonicalize(path.parent()?).ok()?;
+        if dir != fs::canonicalize(&self.path).ok()? {
+            return None;
+        }
+        path.file_stem()?.to_str().map(str::to_string)
+    }
+
+    /// Get the current revisions of all debug flows in the debug flow directory
+    pub fn revisions(&self) -> Result<FlowRevisions, Error> {
+        Ok(FlowRevisions::from_revisions(self.flows()?.filter_map(
+            |flow| Some((flow.id(), flow.revision()?.to_string())),
+        )))
+    }
+
+    /// Save a debug flow to the debug flow directory and return the new revision of the flow
+    ///
+    /// * `id` - ID of the debug flow, the flow is stored under this ID even if its name maps to a different
+    ///   ID. Renaming a debug flow is done with [`FlowsDir::rename_flow`].
+    /// * `debug_flow` - Debug flow to save
+    /// * `base_revision` - Revision the changes in `debug_flow` are based on. If set and the stored flow has a
+    ///   different revision, the flow is not saved and [`Error::RevisionConflict`] is returned.
+    pub fn save_flow(
+        &self,
+        id: &str,
+        debug_flow: &FlowData,
+        base_revision: Option<&str>,
+    ) -> Result<String, Error> {
+        let p = self.flow_path(id)?;
+
+        let _guard = self
+            .write_lock
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(base_revision) = base_revision
+            && let Some(current_revision) = Self::file_revision(&p)?
+            && current_revision != base_revision
+        {
+            return Err(Error::RevisionConflict {
+                id: id.to_string(),
+                revision: current_revision,
+            });
+        }
+
+        // The creation is taken from the stored debug flow, clients can not change it
+        let mut debug_flow = debug_flow.clone();
+        let stored = p.exists().then(|| FlowData::from_file(&p).ok()).flatten();
+        if let Some(stored) = &stored {
+            debug_flow.keep_created(stored);
+            debug_flow.updated_at = stored.updated_at;
+            debug_flow.updated_by = stored.updated_by.clone();
+        }
+        // Storing an unchanged debug flow keeps the last change and thereby the revision
+        if stored.as_ref() != Some(&debug_flow) {
+            debug_flow.touch(self.author());
+        }
+        let revision = debug_flow.to_file(&p)?;
+        self.sync_pins(id, Some(&debug_flow));
+        Ok(revision)
+    }
+
+    /// Update the references pinning the commits linked in a debug flow, see [`pins`]
+    ///
+    /// Failing to pin the commits does not fail storing the debug flow, the pins are updated with the next
+    /// change.
+    fn sync_pins(&self, id: &str, data: Option<&FlowData>) {
+        let repo = match git2_ox::Repository::try_open(&self.git_repo) {
+            Ok(repo) => repo,
+            Err(e) => {
+                log::debug!("Commits of debug flow '{id}' are not pinned: {e}");
+                return;
+            }
+        };
+        if let Err(e) = pins::sync(&repo, id, data) {
+            log::warn!("Failed to pin commits of debug flow '{id}': {e}");
+        }
+    }
+
+    /// Get the author of changes, this is the user configured in the git repository
+    ///
+    /// Returns `None` if the directory is not a git repository or no user is configured.
+    fn author(&self) -> Option<Author> {
+        git2_ox::Repository::try_open(&self.git_repo)
+            .and_then(|repo| repo.signature())
+            .inspect_err(|e| log::debug!("No author for debug flow changes: {e}"))
+            .ok()
+            .map(Author::from)
+    }
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Default, Clone)]
+#[derive(Serialize, Deserialize, ToSchema, Default, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ReactFlowState {
-    /// Nodes of the reactflow state, the types of the nodes are managed on the frontend
-    nodes: Vec<serde_json::Value>,
-    /// Edges of the reactflow state, the types of the nodes are managed on the frontend
-    edges: Vec<serde_json::Value>,
+    /// Nodes of the reactflow state
+    nodes: Vec<Node>,
+    /// Edges of the reactflow state
+    edges: Vec<Edge>,
 }
 
 impl ReactFlowState {
@@ -182,13 +626,74 @@ impl ReactFlowState {
             edges: Vec::new(),
         }
     }
+
+    /// Get the nodes of the reactflow state
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    /// Get the edges of the reactflow state
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    /// Get a node by its ID
+    pub fn node(&self, id: &str) -> Option<&Node> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// Get the nodes the flow starts at
+    ///
+    /// These are the root status nodes, if there are none, all nodes without incoming edges.
+    pub fn root_nodes(&self) -> Vec<&Node> {
+        let roots: Vec<&Node> = self.nodes.iter().filter(|n| n.is_root_node()).collect();
+        if !roots.is_empty() {
+            return roots;
+        }
+        self.nodes
+            .iter()
+            .filter(|n| !self.edges.iter().any(|e| e.target == n.id))
+            .collect()
+    }
+
+    /// Get the nodes connected to the outgoing edges of a node, ordered from left to right on the canvas
+    pub fn successors(&self, id: &str) -> Vec<&Node> {
+        let mut successors: Vec<&Node> = self
+            .edges
+            .iter()
+            .filter(|e| e.source == id)
+            .filter_map(|e| self.node(&e.target))
+            .collect();
+        successors.sort_by(|a, b| a.position.x.total_cmp(&b.position.x));
+        successors.dedup_by(|a, b| a.id == b.id);
+        successors
+    }
 }
 
-#[derive(Serialize, Deserialize, ToSchema, Clone)]
+fn current_schema_version() -> u32 {
+    migration::CURRENT_SCHEMA_VERSION
+}
+
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct FlowData {
+    /// Version of the schema the debug flow is stored in, defaults to the current version
+    #[serde(default = "current_schema_version")]
+    schema_version: u32,
     /// Name of the debug flow
     name: String,
+    /// Date the debug flow was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Date of the last change of the debug flow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Author who created the debug flow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    created_by: Option<Author>,
+    /// Author of the last change of the debug flow
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    updated_by: Option<Author>,
     /// Representation of the reactflow state
     reactflow: ReactFlowState,
 }
@@ -196,14 +701,68 @@ pub struct FlowData {
 impl FlowData {
     pub fn new(name: &str) -> Self {
         FlowData {
+            schema_version: migration::CURRENT_SCHEMA_VERSION,
             name: name.to_string(),
+            created_at: None,
+            updated_at: None,
+            created_by: None,
+            updated_by: None,
             reactflow: ReactFlowState::new(),
         }
     }
 
-    /// Get the ID of the debug flow
+    /// Get the date the debug flow was created, `None` for debug flows created by older releases
+    pub fn created_at(&self) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.created_at.as_ref()
+    }
+
+    /// Get the date of the last change of the debug flow
+    pub fn updated_at(&self) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.updated_at.as_ref()
+    }
+
+    /// Get the author who created the debug flow
+    pub fn created_by(&self) -> Option<&Author> {
+        self.created_by.as_ref()
+    }
+
+    /// Get the author of the last change of the debug flow
+    pub fn updated_by(&self) -> Option<&Author> {
+        self.updated_by.as_ref()
+    }
+
+    /// Record a change of the debug flow by `author`, this also records the creation if it is not known yet
+    pub fn touch(&mut self, author: Option<Author>) {
+        let now = chrono::Utc::now();
+        if self.created_at.is_none() {
+            self.created_at = Some(now);
+            self.created_by = author.clone();
+        }
+        self.updated_at = Some(now);
+        self.updated_by = author;
+    }
+
+    /// Take the creation date and author from `other`, e.g. the stored version of the debug flow
+    fn keep_created(&mut self, other: &FlowData) {
+        if other.created_at.is_some() {
+            self.created_at = other.created_at;
+            self.created_by = other.created_by.clone();
+        }
+    }
+
+    /// Get the schema version of the debug flow
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Get the reactflow state of the debug flow
+    pub fn reactflow(&self) -> &ReactFlowState {
+        &self.reactflow
+    }
+
+    /// Get the ID of the debug flow derived from its name, see [`utils::slugify`]
     pub fn id(&self) -> String {
-        utils::to_kebab_case(self.name.as_str())
+        utils::slugify(self.name.as_str())
     }
 
     /// Get the name of the debug flow
@@ -211,29 +770,59 @@ impl FlowData {
         self.name.clone()
     }
 
-    /// Store the debug flow data to a file
-    pub fn to_file(&self, path: &path::PathBuf) -> std::result::Result<(), Error> {
+    /// Store the debug flow data to a file and return the revision of the written content
+    ///
+    /// The file is replaced atomically, a crash while writing leaves the previous content in place.
+    pub fn to_file(&self, path: &path::Path) -> std::result::Result<String, Error> {
         let json_content = serde_json::to_string(self)?;
-        std::fs::write(path, json_content)?;
-        Ok(())
+        utils::write_file_atomic(path, json_content.as_bytes())
+            .map_err(|e| Error::Io(path.to_path_buf(), e))?;
+        Ok(git2_ox::utils::hash_blob(json_content.as_bytes())?)
     }
 
     /// Get the file name from the debug flow name
     pub fn file_name_from_flow_name(name: &str) -> path::PathBuf {
-        FlowData::file_name_from_id(utils::to_kebab_case(name).as_str())
+        FlowData::file_name_from_id(utils::slugify(name).as_str())
     }
 
     /// Get the file name from the debug flow ID
     pub fn file_name_from_id(id: &str) -> path::PathBuf {
-        let mut p = path::PathBuf::from(id);
-        p.set_extension("json");
-        p
+        // The extension is appended, IDs of older debug flows may contain dots
+        path::PathBuf::from(format!("{id}.json"))
     }
 
     /// Create debug flow data from a file
+    ///
+    /// Flows stored with an older schema version are migrated to the current version, the file
+    /// itself is only updated the next time the flow is stored.
     pub fn from_file(path: &path::PathBuf) -> Result<FlowData, Error> {
         let file_content = std::fs::read_to_string(path)?;
-        serde_json::from_str(&file_content).map_err(Error::Json)
+        FlowData::from_json(&file_content)
+    }
+
+    /// Create debug flow data from its JSON representation, migrating it to the current schema version
+    pub fn from_json(json: &str) -> Result<FlowData, Error> {
+        let value = serde_json::from_str(json)?;
+        serde_json::from_value(migration::migrate(value)?).map_err(Error::Json)
+    }
+
+    /// Get a node of the diagram for modification
+    pub fn node_mut(&mut self, id: &str) -> Option<&mut Node> {
+        self.reactflow.nodes.iter_mut().find(|node| node.id == id)
+    }
+
+    /// Get a node of the diagram, fails with [`Error::NodeNotFound`] if there is none with the ID
+    pub fn find_node(&self, id: &str) -> Result<&Node, Error> {
+        self.reactflow
+            .node(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))
+    }
+
+    /// Get a node of the diagram for modification, fails with [`Error::NodeNotFound`] if there is none with
+    /// the ID
+    pub fn find_node_mut(&mut self, id: &str) -> Result<&mut Node, Error> {
+        self.node_mut(id)
+            .ok_or_else(|| Error::NodeNotFound(id.to_string()))
     }
 
     /// Get the number of nodes in the diagram
@@ -252,15 +841,23 @@ pub struct Flow {
     path: path::PathBuf,
     /// Project data
     data: FlowData,
+    /// Revision of the stored file, `None` if the flow was not stored yet
+    revision: Option<String>,
 }
 
 impl Flow {
     pub fn new(flows_dir: &path::Path, name: &str) -> Self {
+        Flow::from_flows_dir_and_data(flows_dir, FlowData::new(name))
+    }
+
+    /// Create a new, not yet stored, debug flow in a debug flow directory from existing debug flow data
+    pub fn from_flows_dir_and_data(flows_dir: &path::Path, data: FlowData) -> Self {
         let mut path = flows_dir.to_path_buf();
-        path.push(FlowData::file_name_from_flow_name(name));
+        path.push(FlowData::file_name_from_id(&data.id()));
         Flow {
             path,
-            data: FlowData::new(name),
+            data,
+            revision: None,
         }
     }
 
@@ -269,39 +866,47 @@ impl Flow {
     pub fn from_flows_dir_and_name(flows_dir: &path::Path, name: &str) -> Result<Self, Error> {
         let mut path = flows_dir.to_path_buf();
         path.push(FlowData::file_name_from_flow_name(name));
-
-        Ok(Flow {
-            path: path.clone(),
-            data: FlowData::from_file(&path)?,
-        })
+        Flow::from_file(&path)
     }
 
     pub fn from_flows_dir_and_id(flows_dir: &path::Path, id: &str) -> Result<Self, Error> {
+        if !utils::is_safe_file_stem(id) {
+            return Err(Error::InvalidFlowId(id.to_string()));
+        }
         let mut path = flows_dir.to_path_buf();
         path.push(FlowData::file_name_from_id(id));
-
-        Ok(Flow {
-            path: path.clone(),
-            data: FlowData::from_file(&path)?,
-        })
+        Flow::from_file(&path)
     }
 
     pub fn from_file(path: &path::PathBuf) -> Result<Flow, Error> {
+        let content = fs::read(path).map_err(|e| Error::Io(path.clone(), e))?;
         Ok(Flow {
             path: path.clone(),
-            data: FlowData::from_file(path)?,
+            data: FlowData::from_json(&String::from_utf8_lossy(&content))?,
+            revision: Some(git2_ox::utils::hash_blob(&content)?),
         })
     }
 
+    /// Get the ID of the debug flow, this is the stem of the file it is stored in
     pub fn id(&self) -> String {
-        self.data.id()
+        self.path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| self.data.id())
     }
 
     pub fn name(&self) -> String {
         self.data.name()
     }
-    pub fn to_file(&self) -> Result<(), Error> {
-        self.data.to_file(&self.path)
+    pub fn to_file(&mut self) -> Result<(), Error> {
+        self.revision = Some(self.data.to_file(&self.path)?);
+        Ok(())
+    }
+
+    /// Get the revision of the stored flow, this is the Git blob ID of the file content
+    pub fn revision(&self) -> Option<&str> {
+        self.revision.as_deref()
     }
 
     pub fn data(&self) -> &FlowData {
@@ -328,25 +933,87 @@ pub struct FlowMetadata {
     id: String,
     /// Name of the debug flow
     name: String,
-    /// Last modified date
+    /// Date of the last change, the modification time of the file for debug flows without an update date
     last_modified_date: chrono::DateTime<chrono::Utc>,
+    /// Date the debug flow was created
+    created_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Date of the last change of the debug flow
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Author who created the debug flow
+    created_by: Option<Author>,
+    /// Author of the last change of the debug flow
+    updated_by: Option<Author>,
     /// Number of nodes in the debug flow
     num_nodes: usize,
     /// Number of edges in the debug flow
     num_edges: usize,
 }
 
+impl FlowMetadata {
+    /// Get the ID of the debug flow
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the name of the debug flow
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the last modified date
+    pub fn last_modified_date(&self) -> &chrono::DateTime<chrono::Utc> {
+        &self.last_modified_date
+    }
+
+    /// Get the date the debug flow was created
+    pub fn created_at(&self) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.created_at.as_ref()
+    }
+
+    /// Get the date of the last change of the debug flow
+    pub fn updated_at(&self) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.updated_at.as_ref()
+    }
+
+    /// Get the author who created the debug flow
+    pub fn created_by(&self) -> Option<&Author> {
+        self.created_by.as_ref()
+    }
+
+    /// Get the author of the last change of the debug flow
+    pub fn updated_by(&self) -> Option<&Author> {
+        self.updated_by.as_ref()
+    }
+
+    /// Get the number of nodes in the debug flow
+    pub fn num_nodes(&self) -> usize {
+        self.num_nodes
+    }
+
+    /// Get the number of edges in the debug flow
+    pub fn num_edges(&self) -> usize {
+        self.num_edges
+    }
+}
+
 impl TryFrom<Flow> for FlowMetadata {
     type Error = Error;
 
     fn try_from(p: Flow) -> Result<Self, Error> {
-        let accessed = p.file_metadata()?.accessed()?;
+        let last_modified_date = match p.data.updated_at {
+            Some(updated_at) => updated_at,
+            None => p.file_metadata()?.modified()?.into(),
+        };
         Ok(FlowMetadata {
             id: p.id(),
             name: p.name(),
-            last_modified_date: accessed.into(),
+            last_modified_date,
+            created_at: p.data.created_at,
+            updated_at: p.data.updated_at,
             num_nodes: p.num_nodes(),
             num_edges: p.num_edges(),
+            created_by: p.data.created_by,
+            updated_by: p.data.updated_by,
         })
     }
 }
@@ -355,11 +1022,447 @@ impl TryFrom<Flow> for FlowMetadata {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_from_json_typed_nodes() {
+        let flow = FlowData::from_json(
+            r#"{
+                "name": "Crash",
+                "reactflow": {
+                    "nodes": [
+                        {
+                            "id": "root",
+                            "type": "statusNode",
+                            "position": {"x": 0, "y": 0},
+                            "measured": {"width": 100, "height": 50},
+                            "data": {
+                                "title": "Crash on start",
+                                "description": "",
+                                "state": "fail",
+                                "git": {"rev": "abc1234", "summary": "Fix it", "type": "commit"},
+                                "isRootNode": true
+                            }
+                        },
+                        {
+                            "id": "action",
+                            "type": "actionNode",
+                            "position": {"x": 0, "y": 100},
+                            "data": {"title": "Revert", "description": "", "git": null}
+                        }
+                    ],
+                    "edges": [{"id": "e1", "source": "root", "target": "action", "type": "default"}]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(flow.schema_version(), migration::CURRENT_SCHEMA_VERSION);
+        let root = flow.reactflow().node("root").unwrap();
+        assert!(root.is_root_node());
+        assert_eq!(root.state(), Some(StatusNodeState::Fail));
+        assert_eq!(root.git().unwrap().rev(), "abc1234");
+        assert!(root.extra.contains_key("measured"));
+        assert_eq!(flow.reactflow().node("action").unwrap().state(), None);
+        assert_eq!(flow.reactflow().edges()[0].extra["type"], "default");
+
+        // Frontend specific properties survive a round trip
+        let json = serde_json::to_value(&flow).unwrap();
+        assert_eq!(json["reactflow"]["nodes"][0]["measured"]["width"], 100);
+        assert_eq!(json["reactflow"]["nodes"][0]["type"], "statusNode");
+        assert_eq!(FlowData::from_json(&json.to_string()).unwrap(), flow);
+    }
+
+    #[test]
+    fn test_from_json_unknown_node_type() {
+        let result = FlowData::from_json(
+            r#"{"schemaVersion": 1, "name": "Flow", "reactflow": {"nodes": [{"id": "1", "type": "fooNode", "position": {"x": 0, "y": 0}, "data": {}}], "edges": []}}"#,
+        );
+        assert!(matches!(result, Err(Error::Json(_))));
+    }
+
+    #[test]
+    fn test_save_flow_revision_conflict() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        let flow = flows_dir.create_flow("Concurrent", None, false).unwrap();
+        let base_revision = flow.revision().unwrap().to_string();
+
+        let mut first = flow.data().clone();
+        first.reactflow.edges.push(Edge {
+            id: "e1".to_string(),
+            source: "a".to_string(),
+            target: "b".to_string(),
+            extra: Default::default(),
+        });
+        let first_revision = flows_dir
+            .save_flow("concurrent", &first, Some(&base_revision))
+            .unwrap();
+        assert_ne!(first_revision, base_revision);
+        assert_eq!(
+            flows_dir.get_flow_by_id("concurrent").unwrap().revision(),
+            Some(first_revision.as_str())
+        );
+
+        // A second client still based on the initial revision must not overwrite the first change
+        let result = flows_dir.save_flow("concurrent", flow.data(), Some(&base_revision));
+        assert!(matches!(
+            result,
+            Err(Error::RevisionConflict { revision, .. }) if revision == first_revision
+        ));
+
+        // Saving without a base revision always overwrites
+        flows_dir
+            .save_flow("concurrent", flow.data(), None)
+            .unwrap();
+        assert_eq!(
+            flows_dir.get_flow_by_id("concurrent").unwrap().num_edges(),
+            0
+        );
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(flows_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_is_in_worktrees_relative_repo() {
+        // The repository is passed as `./` by default
+        let repo = tempfile::tempdir_in(".").unwrap();
+        let current_dir = std::env::current_dir().unwrap();
+        let relative_repo =
+            path::Path::new(".").join(repo.path().strip_prefix(&current_dir).unwrap());
+        let flows_dir = FlowsDir::try_new(&relative_repo).unwrap();
+
+        let repo_path = repo.path();
+        let worktrees = repo_path
+            .join(FlowsDir::DIR_NAME)
+            .join(FlowsDir::WORKTREES_DIR_NAME);
+        assert!(flows_dir.is_in_worktrees(&worktrees.join("flow").join("1").join("main.rs")));
+        assert!(!flows_dir.is_in_worktrees(&repo_path.join("main.rs")));
+        assert_eq!(
+            flows_dir.flow_id_from_path(&repo_path.join(FlowsDir::DIR_NAME).join("flow.json")),
+            Some("flow".to_string())
+        );
+    }
+
+    #[test]
+    fn test_flow_id_from_path() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+
+        assert_eq!(
+            flows_dir.flow_id_from_path(&flows_dir.path().join("deleted-flow.json")),
+            Some("deleted-flow".to_string())
+        );
+        assert_eq!(
+            flows_dir.flow_id_from_path(&flows_dir.path().join(".flow.json.123.tmp")),
+            None
+        );
+        assert_eq!(
+            flows_dir.flow_id_from_path(&repo.path().join("package.json")),
+            None
+        );
+        let worktree = flows_dir.worktree_path("flow", "1").unwrap();
+        assert!(flows_dir.is_in_worktrees(&worktree.join("src").join("main.rs")));
+        assert!(!flows_dir.is_in_worktrees(&flows_dir.path().join("flow.json")));
+        assert_eq!(
+            flows_dir.flow_id_from_path(
+                &worktree
+                    .join(FlowsDir::DIR_NAME)
+                    .join("checked-out-flow.json")
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_worktree_node() {
+        for (flow_id, node_id) in [
+            ("flow", "1"),
+            ("my.flow", "dndnode_2"),
+            ("a", "status node/3"),
+        ] {
+            let name = FlowsDir::worktree_name(flow_id, node_id);
+            assert!(name.starts_with(FlowsDir::WORKTREE_NAME_PREFIX));
+            assert_eq!(
+                FlowsDir::worktree_node(&name),
+                Some((flow_id.to_string(), node_id.to_string()))
+            );
+        }
+        // Nodes with the same ID in different debug flows have different worktrees
+        assert_ne!(
+            FlowsDir::worktree_name("a", "1"),
+            FlowsDir::worktree_name("b", "1")
+        );
+        assert_eq!(FlowsDir::worktree_node("feature-x"), None);
+        assert_eq!(FlowsDir::worktree_node("debug-flow-a"), None);
+        assert_eq!(FlowsDir::worktree_node("debug-flow-a.b.c"), None);
+    }
+
+    #[test]
+    fn test_rename_flow() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        flows_dir.create_flow("Old name", None, false).unwrap();
+        flows_dir.create_flow("Taken", None, false).unwrap();
+
+        assert!(matches!(
+            flows_dir.rename_flow("old-name", "taken"),
+            Err(Error::DebugFlowExistsAlready { .. })
+        ));
+
+        let renamed = flows_dir.rename_flow("old-name", "New name").unwrap();
+        assert_eq!(renamed.id(), "new-name");
+        assert_eq!(
+            flows_dir.get_flow_by_id("new-name").unwrap().name(),
+            "New name"
+        );
+        assert!(flows_dir.get_flow_by_id("old-name").is_err());
+
+        // Changing only the case of the name keeps the ID
+        let renamed = flows_dir.rename_flow("new-name", "NEW NAME").unwrap();
+        assert_eq!(renamed.id(), "new-name");
+        assert_eq!(
+            flows_dir.get_flow_by_id("new-name").unwrap().name(),
+            "NEW NAME"
+        );
+    }
+
+    #[test]
+    fn test_save_flow_keeps_id() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        let flow = flows_dir.create_flow("Original", None, false).unwrap();
+
+        // Changing the name while storing must not fork the debug flow into a second file
+        let mut data = flow.data().clone();
+        data.name = "Changed".to_string();
+        flows_dir.save_flow("original", &data, None).unwrap();
+
+        let stored = flows_dir.get_flow_by_id("original").unwrap();
+        assert_eq!(stored.id(), "original");
+        assert_eq!(stored.name(), "Changed");
+        assert_eq!(fs::read_dir(flows_dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_save_flow_timestamps() {
+        let repo = tempfile::tempdir().unwrap();
+        let git_repo = git2_ox::Repository::try_init(repo.path()).unwrap();
+        let mut config = git_repo.repo().config().unwrap();
+        config.set_str("user.name", "Jane Doe").unwrap();
+        config.set_str("user.email", "jane@example.com").unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+
+        let flow = flows_dir.create_flow("Timestamps", None, false).unwrap();
+        let created_at = *flow.data().created_at().unwrap();
+        assert_eq!(flow.data().updated_at(), Some(&created_at));
+        assert_eq!(flow.data().created_by().unwrap().name, "Jane Doe");
+        assert_eq!(flow.data().updated_by().unwrap().email, "jane@example.com");
+
+        // Storing an unchanged debug flow does not change the revision
+        let revision = flows_dir
+            .save_flow("timestamps", flow.data(), None)
+            .unwrap();
+        assert_eq!(Some(revision.as_str()), flow.revision());
+
+        // Clients can not change the creation
+        config.set_str("user.name", "John Doe").unwrap();
+        let mut data = flow.data().clone();
+        data.created_at = None;
+        data.created_by = None;
+        data.reactflow.edges.push(Edge {
+            id: "e1".to_string(),
+            source: "a".to_string(),
+            target: "b".to_string(),
+            extra: Default::default(),
+        });
+        flows_dir.save_flow("timestamps", &data, None).unwrap();
+
+        let stored = flows_dir.get_flow_by_id("timestamps").unwrap();
+        assert_eq!(stored.data().created_at(), Some(&created_at));
+        assert_eq!(stored.data().created_by().unwrap().name, "Jane Doe");
+        assert!(stored.data().updated_at().unwrap() >= &created_at);
+        assert_eq!(stored.data().updated_by().unwrap().name, "John Doe");
+
+        let metadata = FlowMetadata::try_from(stored).unwrap();
+        assert_eq!(Some(metadata.last_modified_date()), metadata.updated_at());
+    }
+
+    #[test]
+    fn test_duplicate_flow() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        let flow = flows_dir.create_flow("Original", None, false).unwrap();
+        let mut data = flow.data().clone();
+        data.reactflow.edges.push(Edge {
+            id: "e1".to_string(),
+            source: "a".to_string(),
+            target: "b".to_string(),
+            extra: Default::default(),
+        });
+        flows_dir.save_flow("original", &data, None).unwrap();
+
+        let copy = flows_dir.duplicate_flow("original", "Copy").unwrap();
+        assert_eq!(copy.id(), "copy");
+        assert_eq!(copy.num_edges(), 1);
+        assert_eq!(flows_dir.get_flow_by_id("original").unwrap().num_edges(), 1);
+
+        assert!(matches!(
+            flows_dir.duplicate_flow("original", "copy"),
+            Err(Error::DebugFlowExistsAlready { .. })
+        ));
+        assert!(matches!(
+            flows_dir.duplicate_flow("missing", "Other"),
+            Err(Error::Io(..))
+        ));
+    }
+
+    #[test]
+    fn test_diff_to_revision() {
+        let repo = tempfile::tempdir().unwrap();
+        let git_repo = git2_ox::Repository::try_init(repo.path()).unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        let flow = flows_dir.create_flow("Reviewed", None, false).unwrap();
+
+        // Commit the debug flow to HEAD
+        git_repo.add_all([".debug-flow"]).unwrap();
+        let git = git_repo.repo();
+        let mut config = git.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let signature = git.signature().unwrap();
+        git.commit(Some("HEAD"), &signature, &signature, "Add flow", &tree, &[])
+            .unwrap();
+
+        let mut data = flow.data().clone();
+        data.name = "Reviewed again".to_string();
+        flows_dir.save_flow("reviewed", &data, None).unwrap();
+
+        let diff = flows_dir.diff("reviewed", "HEAD").unwrap();
+        assert_eq!(diff.name.unwrap().old, "Reviewed");
+        assert!(matches!(
+            flows_dir.diff("reviewed", "not-a-revision"),
+            Err(Error::Git(_))
+        ));
+    }
+
     #[test]
     fn test_file_name() {
         assert_eq!(
             FlowData::file_name_from_flow_name("HELLO WORLD"),
             path::Path::new("hello-world.json")
         );
+        assert_eq!(
+            FlowData::file_name_from_id("v1.2-legacy"),
+            path::Path::new("v1.2-legacy.json")
+        );
+    }
+
+    #[test]
+    fn test_create_flow_from_template() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        assert!(flows_dir.templates().unwrap().is_empty());
+
+        fs::create_dir(flows_dir.templates_path()).unwrap();
+        fs::write(
+            flows_dir.templates_path().join("flaky-test.json"),
+            r#"{"name": "Flaky test", "reactflow": {"nodes": [
+                {"id": "1", "type": "statusNode", "position": {"x": 0, "y": 0},
+                 "data": {"title": "Flaky on {{branch}}", "description": "", "git": null, "state": "fail", "isRootNode": true}}
+            ], "edges": []}}"#,
+        )
+        .unwrap();
+        let templates = flows_dir.templates().unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].id, "flaky-test");
+        assert_eq!(templates[0].num_nodes, 1);
+
+        // Without a Git repository the placeholders are kept
+        let flow = flows_dir
+            .create_flow("Flaky login", Some("flaky-test"), false)
+            .unwrap();
+        assert_eq!(flow.id(), "flaky-login");
+        assert_eq!(
+            flow.data().reactflow().nodes()[0].title(),
+            "Flaky on {{branch}}"
+        );
+        assert!(flow.data().created_at().is_some());
+        // Templates are not debug flows
+        assert_eq!(flows_dir.flows().unwrap().count(), 1);
+
+        assert!(matches!(
+            flows_dir.create_flow("Other", Some("missing"), false),
+            Err(Error::Io(_, ref e)) if e.kind() == io::ErrorKind::NotFound
+        ));
+        assert!(matches!(
+            flows_dir.create_flow("Other", Some("../flaky-login"), false),
+            Err(Error::InvalidFlowId(_))
+        ));
+    }
+
+    #[test]
+    fn test_create_flow_collision() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        flows_dir.create_flow("Crash A", None, false).unwrap();
+
+        let result = flows_dir.create_flow("crash_a", None, false);
+        assert!(matches!(
+            result,
+            Err(Error::DebugFlowExistsAlready { ref id, .. }) if id == "crash-a"
+        ));
+        assert_eq!(
+            flows_dir.get_flow_by_id("crash-a").unwrap().name(),
+            "Crash A"
+        );
+    }
+
+    #[test]
+    fn test_create_flow_concurrently() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+
+        let created = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| flows_dir.create_flow("Crash", None, false)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|result| result.is_ok())
+                .count()
+        });
+        assert_eq!(created, 1);
+    }
+
+    #[test]
+    fn test_path_traversal() {
+        let repo = tempfile::tempdir().unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+
+        let flow = flows_dir.create_flow("../../etc", None, false).unwrap();
+        assert_eq!(flow.id(), "etc");
+        assert_eq!(
+            fs::canonicalize(flows_dir.path().join("etc.json")).unwrap(),
+            fs::canonicalize(&flow.path).unwrap()
+        );
+
+        for id in ["../../etc", "../escape", "..", "a/b", ""] {
+            assert!(
+                matches!(flows_dir.get_flow_by_id(id), Err(Error::InvalidFlowId(_))),
+                "{id}"
+            );
+            assert!(matches!(
+                flows_dir.delete_flow_by_id(id),
+                Err(Error::InvalidFlowId(_))
+            ));
+            assert!(matches!(
+                flows_dir.save_flow(id, flow.data(), None),
+                Err(Error::InvalidFlowId(_))
+            ));
+        }
+        assert!(!repo.path().join("escape.json").exists());
     }
 }
@@ -0,0 +1,536 @@
+//! Guided bisections building a debug flow
+//!
+//! A bisection searches the first commit with a problem between a bad revision and good revisions. Every
+//! commit to test becomes a status node of the debug flow, the nodes are chained by edges starting at the root
+//! node. Marking the commit sets the state of its node: good commits succeed, bad commits fail and skipped
+//! commits stay unknown. The result of the bisection is added as a final status node.
+//!
+//! Bisections are kept in memory, restarting the application ends them.
+
+use super::{
+    CommandResult, Edge, Error, FlowData, FlowsDir, Node, NodeKind, StatusNodeState,
+    model::{Extra, GitMetadata, GitMetadataKind, Position, StatusNodeData},
+};
+use git2_ox::{BisectStep, BisectVerdict, CommitProperties};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use utoipa::ToSchema;
+
+/// Vertical distance between the nodes added by a bisection
+const NODE_SPACING: f64 = 150.0;
+
+/// Exit code of test commands for commits which cannot be tested, like for `git bisect run`
+pub const SKIP_EXIT_CODE: i32 = 125;
+
+/// Get the verdict on a commit from the result of a test command
+///
+/// Like for `git bisect run` the exit code 0 means good, 125 skip and the other codes up to 127 bad. `None`
+/// for commands which timed out or were terminated, the bisection should not continue in this case.
+pub fn verdict(result: &CommandResult) -> Option<BisectVerdict> {
+    match result.exit_code {
+        _ if result.timed_out => None,
+        Some(0) => Some(BisectVerdict::Good),
+        Some(SKIP_EXIT_CODE) => Some(BisectVerdict::Skip),
+        Some(1..=127) => Some(BisectVerdict::Bad),
+        _ => None,
+    }
+}
+
+/// Bisection of a debug flow
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BisectSession {
+    /// Marked commits
+    pub bisect: git2_ox::Bisect,
+    /// Next step of the bisection
+    pub step: BisectStep,
+    /// ID of the node of the commit to test, `None` once the bisection is finished
+    pub node_id: Option<String>,
+    /// ID of the last node added by the bisection, the next node is connected to it
+    #[serde(skip)]
+    last_node_id: Option<String>,
+    /// ID of the run testing the commits with a command, see [`BisectSessions::start_run`]
+    #[serde(skip)]
+    run: Option<u64>,
+}
+
+impl BisectSession {
+    /// Add the node of the current step to the debug flow
+    fn add_step_node(&mut self, data: &mut FlowData) {
+        let previous = self
+            .last_node_id
+            .as_deref()
+            .and_then(|id| data.reactflow().node(id))
+            .or_else(|| data.reactflow().root_nodes().first().copied());
+        let source = previous.map(|node| node.id.clone());
+        let position = previous
+            .map(|node| Position {
+                x: node.position.x,
+                y: node.position.y + NODE_SPACING,
+            })
+            .unwrap_or_default();
+
+        let (commit, title, description, state) = match &self.step {
+            BisectStep::Test {
+                commit,
+                candidates,
+                steps,
+            } => (
+                Some(commit),
+                format!("Bisect: test {}", commit.short_id()),
+                format!(
+                    "Does \"{}\" have the problem? {candidates} commits left, about {steps} more tests.",
+                    commit.summary()
+                ),
+                StatusNodeState::Progress,
+            ),
+            BisectStep::FirstBad { commit } => (
+                Some(commit),
+                format!("First bad commit {}", commit.short_id()),
+                format!("\"{}\" introduced the problem.", commit.summary()),
+                StatusNodeState::Fail,
+            ),
+            BisectStep::Inconclusive { candidates } => (
+                None,
+                "Bisect: inconclusive".to_string(),
+                candidates.iter().fold(
+                    "Only skipped commits are left, the first bad commit is one of:\n".to_string(),
+                    |text, commit| {
+                        format!("{text}\n* {} \"{}\"", commit.short_id(), commit.summary())
+                    },
+                ),
+                StatusNodeState::Unknown,
+            ),
+        };
+        let id = unique_id(
+            data,
+            &format!(
+                "status-node-bisect-{}",
+                commit.map(|c| c.short_id()).unwrap_or("result")
+            ),
+        );
+        data.reactflow.nodes.push(Node {
+            id: id.clone(),
+            position,
+            kind: NodeKind::Status(StatusNodeData {
+                title,
+                state,
+                description,
+                git: commit.map(|c| GitMetadata::new(c.id(), c.summary(), GitMetadataKind::Commit)),
+                is_root_node: false,
+                attachments: Vec::new(),
+                extra: Extra::new(),
+            }),
+            extra: Extra::new(),
+        });
+        if let Some(source) = source {
+            data.reactflow.edges.push(Edge {
+                id: unique_id(data, &format!("edge-{source}-{id}")),
+                source,
+                target: id.clone(),
+                extra: Extra::new(),
+            });
+        }
+
+        self.node_id = matches!(self.step, BisectStep::Test { .. }).then(|| id.clone());
+        self.last_node_id = Some(id);
+    }
+}
+
+/// Get `base` or `base` with the smallest numeric suffix which is not the ID of a node or edge
+fn unique_id(data: &FlowData, base: &str) -> String {
+    let exists = |id: &str| {
+        data.reactflow().nodes().iter().any(|n| n.id == id)
+            || data.reactflow().edges().iter().any(|e| e.id == id)
+    };
+    std::iter::once(base.to_string())
+        .chain((2..).map(|i| format!("{base}-{i}")))
+        .find(|id| !exists(id))
+        .unwrap_or_default()
+}
+
+/// Bisections of the debug flows, by debug flow ID
+#[derive(Clone, Default)]
+pub struct BisectSessions {
+    sessions: Arc<Mutex<HashMap<String, BisectSession>>>,
+}
+
+impl BisectSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, BisectSession>> {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Get the bisection of a debug flow
+    pub fn get(&self, id: &str) -> Option<BisectSession> {
+        self.lock().get(id).cloned()
+    }
+
+    /// End the bisection of a debug flow, the nodes it added are kept
+    pub fn remove(&self, id: &str) -> Option<BisectSession> {
+        self.lock().remove(id)
+    }
+
+    /// Start a bisection of a debug flow and add the node of the first commit to test
+    ///
+    /// A running bisection of the debug flow is replaced. Returns the bisection and the new revision of the
+    /// debug flow.
+    ///
+    /// * `bad` - Revision which has the problem
+    /// * `good` - Revisions without the problem
+    pub fn start(
+        &self,
+        flows_dir: &FlowsDir,
+        id: &str,
+        bad: &str,
+        good: &[String],
+    ) -> Result<(BisectSession, String), Error> {
+        let repo = git2_ox::Repository::try_open(flows_dir.git_repo())?;
+        let bisect = repo.bisect_start(bad, good)?;
+        let step = repo.bisect_step(&bisect)?;
+        let mut session = BisectSession {
+            bisect,
+            step,
+            node_id: None,
+            last_node_id: None,
+            run: None,
+        };
+
+        let mut sessions = self.lock();
+        let flow = flows_dir.get_flow_by_id(id)?;
+        let mut data = flow.data().clone();
+        session.add_step_node(&mut data);
+        let revision = flows_dir.save_flow(id, &data, flow.revision())?;
+        sessions.insert(id.to_string(), session.clone());
+        Ok((session, revision))
+    }
+
+    /// Mark the commit to test in the bisection of a debug flow and add the node of the next step
+    ///
+    /// Fails with [`Error::BisectionRunning`] while a command tests the commits. Returns the bisection and
+    /// the new revision of the debug flow.
+    pub fn mark(
+        &self,
+        flows_dir: &FlowsDir,
+        id: &str,
+        verdict: BisectVerdict,
+    ) -> Result<(BisectSession, String), Error> {
+        self.mark_node(flows_dir, id, verdict, None)
+    }
+
+    /// Start testing the commits of the bisection of a debug flow with a command
+    ///
+    /// There is only one run per bisection and commits cannot be marked manually until the run is finished
+    /// with [`BisectSessions::finish_run`]. Returns the ID of the run.
+    pub fn start_run(&self, id: &str) -> Result<u64, Error> {
+        static NEXT_RUN: AtomicU64 = AtomicU64::new(1);
+        let mut sessions = self.lock();
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| Error::NoBisection(id.to_string()))?;
+        if session.node_id.is_none() {
+            return Err(Error::BisectionFinished(id.to_string()));
+        }
+        if session.run.is_some() {
+            return Err(Error::BisectionRunning(id.to_string()));
+        }
+        let run = NEXT_RUN.fetch_add(1, Ordering::Relaxed);
+        session.run = Some(run);
+        Ok(run)
+    }
+
+    /// Get the node of the commit the run should test next, `None` if the bisection is finished or was ended
+    /// or replaced
+    pub fn next_run_node(&self, id: &str, run: u64) -> Option<String> {
+        self.lock()
+            .get(id)
+            .filter(|session| session.run == Some(run))
+            .and_then(|session| session.node_id.clone())
+    }
+
+    /// Mark the commit tested by a run in the bisection of a debug flow and add the node of the next step
+    ///
+    /// Fails with [`Error::NotBisectionNode`] if `node_id` is not the node of the commit to test, e.g. because
+    /// the bisection was replaced while the command was running.
+    pub fn mark_run(
+        &self,
+        flows_dir: &FlowsDir,
+        id: &str,
+        run: u64,
+        node_id: &str,
+        verdict: BisectVerdict,
+    ) -> Result<(BisectSession, String), Error> {
+        self.mark_node(flows_dir, id, verdict, Some((run, node_id)))
+    }
+
+    /// End a run of the bisection of a debug flow, commits can be marked manually again
+    pub fn finish_run(&self, id: &str, run: u64) {
+        if let Some(session) = self
+            .lock()
+            .get_mut(id)
+            .filter(|session| session.run == Some(run))
+        {
+            session.run = None;
+        }
+    }
+
+    /// Mark the commit to test, manually if `run` is `None` or by the run with the ID and the tested node
+    fn mark_node(
+        &self,
+        flows_dir: &FlowsDir,
+        id: &str,
+        verdict: BisectVerdict,
+        run: Option<(u64, &str)>,
+    ) -> Result<(BisectSession, String), Error> {
+        let mut sessions = self.lock();
+        let session = sessions
+            .get_mut(id)
+            .ok_or_else(|| Error::NoBisection(id.to_string()))?;
+        match run {
+            None if session.run.is_some() => {
+                return Err(Error::BisectionRunning(id.to_string()));
+            }
+            Some((run, node_id))
+                if session.run != Some(run) || session.node_id.as_deref() != Some(node_id) =>
+            {
+                return Err(Error::NotBisectionNode {
+                    id: id.to_string(),
+                    node_id: node_id.to_string(),
+                });
+            }
+            _ => {}
+        }
+        let BisectStep::Test { commit, .. } = &session.step else {
+            return Err(Error::BisectionFinished(id.to_string()));
+        };
+
+        let repo = git2_ox::Repository::try_open(flows_dir.git_repo())?;
+        let mut next = session.clone();
+        repo.bisect_mark(&mut next.bisect, commit.id(), verdict)?;
+        next.step = repo.bisect_step(&next.bisect)?;
+
+        let flow = flows_dir.get_flow_by_id(id)?;
+        let mut data = flow.data().clone();
+        if let Some(node) = next.node_id.as_deref().and_then(|n| data.node_mut(n)) {
+            node.set_state(match verdict {
+                BisectVerdict::Good => StatusNodeState::Success,
+                BisectVerdict::Bad => StatusNodeState::Fail,
+                BisectVerdict::Skip => StatusNodeState::Unknown,
+            });
+        }
+        next.add_step_node(&mut data);
+        let revision = flows_dir.save_flow(id, &data, flow.revision())?;
+        *session = next;
+        Ok((session.clone(), revision))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a repository with a linear history of 8 commits and the debug flow `regression`, returns the
+    /// commits oldest first
+    fn setup() -> (tempfile::TempDir, FlowsDir, Vec<String>) {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2_ox::Repository::try_init(dir.path()).unwrap();
+        let git = repo.repo();
+        let mut config = git.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = git.signature().unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let mut commits: Vec<String> = Vec::new();
+        for i in 0..8 {
+            let parent = commits
+                .last()
+                .map(|c| git.find_commit(c.parse().unwrap()).unwrap());
+            let parents: Vec<_> = parent.iter().collect();
+            let commit = git
+                .commit(
+                    Some("HEAD"),
+                    &signature,
+                    &signature,
+                    &format!("Commit {i}"),
+                    &tree,
+                    &parents,
+                )
+                .unwrap();
+            commits.push(commit.to_string());
+        }
+
+        let flows_dir = FlowsDir::try_new(dir.path()).unwrap();
+        flows_dir.create_flow("Regression", None, false).unwrap();
+        let data: FlowData = serde_json::from_value(serde_json::json!({
+            "name": "Regression",
+            "reactflow": {"nodes": [
+                {"id": "root", "type": "statusNode", "position": {"x": 10, "y": 20},
+                 "data": {"title": "Slow", "description": "", "state": "fail", "isRootNode": true, "git": null}}
+            ], "edges": []}
+        }))
+        .unwrap();
+        flows_dir.save_flow("regression", &data, None).unwrap();
+        (dir, flows_dir, commits)
+    }
+
+    #[test]
+    fn test_bisect_builds_flow() {
+        let (_dir, flows_dir, commits) = setup();
+        let sessions = BisectSessions::new();
+        assert!(matches!(
+            sessions.mark(&flows_dir, "regression", BisectVerdict::Good),
+            Err(Error::NoBisection(_))
+        ));
+        let (mut session, _) = sessions
+            .start(&flows_dir, "regression", "HEAD", &[commits[0].clone()])
+            .unwrap();
+        let first_bad = 5;
+        let mut tested = Vec::new();
+        while let Some(node_id) = session.node_id.clone() {
+            let data = flows_dir
+                .get_flow_by_id("regression")
+                .unwrap()
+                .data()
+                .clone();
+            let node = data.reactflow().node(&node_id).unwrap();
+            assert_eq!(node.state(), Some(StatusNodeState::Progress));
+            let rev = node.git().unwrap().rev().to_string();
+            let i = commits.iter().position(|c| *c == rev).unwrap();
+            tested.push(node_id);
+            let verdict = match i < first_bad {
+                true => BisectVerdict::Good,
+                false => BisectVerdict::Bad,
+            };
+            session = sessions.mark(&flows_dir, "regression", verdict).unwrap().0;
+        }
+        assert!(matches!(
+            &session.step,
+            BisectStep::FirstBad { commit } if commit.id() == commits[first_bad]
+        ));
+        assert!(matches!(
+            sessions.mark(&flows_dir, "regression", BisectVerdict::Good),
+            Err(Error::BisectionFinished(_))
+        ));
+
+        // The tested commits and the result are chained to the root node
+        let data = flows_dir
+            .get_flow_by_id("regression")
+            .unwrap()
+            .data()
+            .clone();
+        assert_eq!(data.num_nodes(), tested.len() + 2);
+        let mut chain = Vec::new();
+        let mut node = data.reactflow().node("root").unwrap();
+        while let Some(next) = data.reactflow().successors(&node.id).first() {
+            assert_eq!(next.position.y, node.position.y + NODE_SPACING);
+            chain.push(next.id.clone());
+            node = next;
+        }
+        assert_eq!(chain[..tested.len()], tested);
+        assert_eq!(node.state(), Some(StatusNodeState::Fail));
+        assert_eq!(node.git().unwrap().rev(), commits[first_bad]);
+        assert!(tested.iter().all(
+            |id| data.reactflow().node(id).unwrap().state() != Some(StatusNodeState::Progress)
+        ));
+    }
+
+    #[test]
+    fn test_bisect_run() {
+        let (_dir, flows_dir, commits) = setup();
+        let sessions = BisectSessions::new();
+        assert!(matches!(
+            sessions.start_run("regression"),
+            Err(Error::NoBisection(_))
+        ));
+        let (session, _) = sessions
+            .start(&flows_dir, "regression", "HEAD", &[commits[0].clone()])
+            .unwrap();
+        let node_id = session.node_id.unwrap();
+
+        let run = sessions.start_run("regression").unwrap();
+        assert_eq!(
+            sessions.next_run_node("regression", run).as_ref(),
+            Some(&node_id)
+        );
+        // Only one run at a time and no manual marks during the run
+        assert!(matches!(
+            sessions.start_run("regression"),
+            Err(Error::BisectionRunning(_))
+        ));
+        assert!(matches!(
+            sessions.mark(&flows_dir, "regression", BisectVerdict::Good),
+            Err(Error::BisectionRunning(_))
+        ));
+        // Only the tested node is marked by the run
+        assert!(matches!(
+            sessions.mark_run(&flows_dir, "regression", run, "root", BisectVerdict::Good),
+            Err(Error::NotBisectionNode { .. })
+        ));
+        let (session, _) = sessions
+            .mark_run(&flows_dir, "regression", run, &node_id, BisectVerdict::Good)
+            .unwrap();
+        assert_ne!(session.node_id, Some(node_id.clone()));
+
+        // The run of a replaced bisection does not mark the new one
+        let (session, _) = sessions
+            .start(&flows_dir, "regression", "HEAD", &[commits[0].clone()])
+            .unwrap();
+        assert_eq!(sessions.next_run_node("regression", run), None);
+        assert!(matches!(
+            sessions.mark_run(
+                &flows_dir,
+                "regression",
+                run,
+                session.node_id.as_deref().unwrap(),
+                BisectVerdict::Bad
+            ),
+            Err(Error::NotBisectionNode { .. })
+        ));
+        let new_run = sessions.start_run("regression").unwrap();
+        sessions.finish_run("regression", run);
+        assert!(matches!(
+            sessions.mark(&flows_dir, "regression", BisectVerdict::Bad),
+            Err(Error::BisectionRunning(_))
+        ));
+        sessions.finish_run("regression", new_run);
+        sessions
+            .mark(&flows_dir, "regression", BisectVerdict::Bad)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verdict() {
+        let result = |exit_code, timed_out| CommandResult {
+            command: String::new(),
+            commit: String::new(),
+            exit_code,
+            timed_out,
+            stdout: String::new(),
+            stderr: String::new(),
+            started_at: chrono::Utc::now(),
+            finished_at: chrono::Utc::now(),
+        };
+        assert_eq!(verdict(&result(Some(0), false)), Some(BisectVerdict::Good));
+        assert_eq!(verdict(&result(Some(1), false)), Some(BisectVerdict::Bad));
+        assert_eq!(
+            verdict(&result(Some(125), false)),
+            Some(BisectVerdict::Skip)
+        );
+        assert_eq!(verdict(&result(Some(128), false)), None);
+        assert_eq!(verdict(&result(None, false)), None);
+        assert_eq!(verdict(&result(None, true)), None);
+    }
+}
@@ -0,0 +1,269 @@
+//! Structural comparison of two versions of a debug flow
+//!
+//! Nodes and edges are matched by their IDs, changed nodes and edges report every changed field by its
+//! path in the JSON representation, e.g. `data.state`.
+
+use super::{Edge, FlowData, Node};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+
+/// Fields reactflow uses for transient UI state, changes of them are not reported
+const IGNORED_FIELDS: [&str; 4] = ["selected", "dragging", "measured", "resizing"];
+
+/// Change of a single field
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    /// Path of the field, nested fields are separated by dots
+    pub field: String,
+    /// Old value, `null` if the field was added
+    pub old: Value,
+    /// New value, `null` if the field was removed
+    pub new: Value,
+}
+
+/// Changes of a node or an edge which exists in both versions
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ElementChange {
+    /// ID of the node or edge
+    pub id: String,
+    /// Changed fields
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two versions of a debug flow
+#[derive(Serialize, ToSchema, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowDiff {
+    /// Change of the name of the debug flow
+    pub name: Option<FieldChange>,
+    /// Nodes only in the new version
+    pub added_nodes: Vec<Node>,
+    /// Nodes only in the old version
+    pub removed_nodes: Vec<Node>,
+    /// Nodes in both versions with different fields
+    pub changed_nodes: Vec<ElementChange>,
+    /// Edges only in the new version
+    pub added_edges: Vec<Edge>,
+    /// Edges only in the old version
+    pub removed_edges: Vec<Edge>,
+    /// Edges in both versions with different fields
+    pub changed_edges: Vec<ElementChange>,
+}
+
+impl FlowDiff {
+    /// Check if both versions are equal apart from ignored fields
+    pub fn is_empty(&self) -> bool {
+        self == &FlowDiff::default()
+    }
+}
+
+/// Compare two versions of a debug flow
+///
+/// * `old` - Old version of the debug flow, e.g. the version in a Git revision
+/// * `new` - New version of the debug flow, e.g. the stored version
+pub fn diff(old: &FlowData, new: &FlowData) -> FlowDiff {
+    let (added_nodes, removed_nodes, changed_nodes) =
+        diff_elements(old.reactflow().nodes(), new.reactflow().nodes(), |n| &n.id);
+    let (added_edges, removed_edges, changed_edges) =
+        diff_elements(old.reactflow().edges(), new.reactflow().edges(), |e| &e.id);
+
+    FlowDiff {
+        name: (old.name != new.name).then(|| FieldChange {
+            field: "name".to_string(),
+            old: Value::String(old.name.clone()),
+            new: Value::String(new.name.clone()),
+        }),
+        added_nodes,
+        removed_nodes,
+        changed_nodes,
+        added_edges,
+        removed_edges,
+        changed_edges,
+    }
+}
+
+/// Compare two lists of elements matched by their ID, the order of the elements of the new version is kept
+fn diff_elements<T, F>(old: &[T], new: &[T], id: F) -> (Vec<T>, Vec<T>, Vec<ElementChange>)
+where
+    T: Serialize + Clone,
+    F: Fn(&T) -> &String,
+{
+    let old_by_id: HashMap<&String, &T> = old.iter().map(|e| (id(e), e)).collect();
+    let new_by_id: HashMap<&String, &T> = new.iter().map(|e| (id(e), e)).collect();
+
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for element in new {
+        match old_by_id.get(id(element)) {
+            None => added.push(element.clone()),
+            Some(old_element) => {
+                let changes = diff_fields(*old_element, element);
+                if !changes.is_empty() {
+                    changed.push(ElementChange {
+                        id: id(element).clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    let removed = old
+        .iter()
+        .filter(|e| !new_by_id.contains_key(id(e)))
+        .cloned()
+        .collect();
+    (added, removed, changed)
+}
+
+/// Get the changed fields of two serializable values
+fn diff_fields<T: Serialize>(old: &T, new: &T) -> Vec<FieldChange> {
+    let mut old_fields = BTreeMap::new();
+    flatten(
+        "",
+        serde_json::to_value(old).unwrap_or_default(),
+        &mut old_fields,
+    );
+    let mut new_fields = BTreeMap::new();
+    flatten(
+        "",
+        serde_json::to_value(new).unwrap_or_default(),
+        &mut new_fields,
+    );
+
+    let mut changes = Vec::new();
+    for (field, old_value) in &old_fields {
+        let new_value = new_fields.get(field).unwrap_or(&Value::Null);
+        if old_value != new_value {
+            changes.push(FieldChange {
+                field: field.clone(),
+                old: old_value.clone(),
+                new: new_value.clone(),
+            });
+        }
+    }
+    for (field, new_value) in new_fields {
+        if !old_fields.contains_key(&field) && !new_value.is_null() {
+            changes.push(FieldChange {
+                field,
+                old: Value::Null,
+                new: new_value,
+            });
+        }
+    }
+    changes.sort_by(|a, b| a.field.cmp(&b.field));
+    changes
+}
+
+/// Collect the leaf values of nested objects by their dot separated path, arrays are treated as values
+fn flatten(prefix: &str, value: Value, fields: &mut BTreeMap<String, Value>) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                if prefix.is_empty() && IGNORED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+                let path = match prefix {
+                    "" => key,
+                    _ => format!("{prefix}.{key}"),
+                };
+                flatten(&path, value, fields);
+            }
+        }
+        value => {
+            fields.insert(prefix.to_string(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn flow(nodes: Value, edges: Value) -> FlowData {
+        serde_json::from_value(json!({
+            "name": "Flow",
+            "reactflow": {"nodes": nodes, "edges": edges}
+        }))
+        .unwrap()
+    }
+
+    fn status_node(id: &str, state: &str) -> Value {
+        json!({
+            "id": id,
+            "type": "statusNode",
+            "position": {"x": 0.0, "y": 0.0},
+            "data": {"title": id, "description": "", "git": null, "state": state, "isRootNode": false}
+        })
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = flow(
+            json!([status_node("1", "progress"), status_node("2", "unknown")]),
+            json!([{"id": "e1", "source": "1", "target": "2"}]),
+        );
+        let mut new = flow(
+            json!([status_node("1", "success"), status_node("3", "fail")]),
+            json!([{"id": "e2", "source": "1", "target": "3"}]),
+        );
+        new.name = "Renamed".to_string();
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.name.unwrap().new, json!("Renamed"));
+        assert_eq!(
+            diff.added_nodes.iter().map(|n| &n.id).collect::<Vec<_>>(),
+            ["3"]
+        );
+        assert_eq!(
+            diff.removed_nodes.iter().map(|n| &n.id).collect::<Vec<_>>(),
+            ["2"]
+        );
+        assert_eq!(
+            diff.changed_nodes,
+            [ElementChange {
+                id: "1".to_string(),
+                changes: vec![FieldChange {
+                    field: "data.state".to_string(),
+                    old: json!("progress"),
+                    new: json!("success"),
+                }]
+            }]
+        );
+        assert_eq!(diff.added_edges[0].id, "e2");
+        assert_eq!(diff.removed_edges[0].id, "e1");
+        assert!(diff.changed_edges.is_empty());
+    }
+
+    #[test]
+    fn test_diff_ignores_transient_fields() {
+        let old = flow(json!([status_node("1", "fail")]), json!([]));
+        let mut node = status_node("1", "fail");
+        node["selected"] = json!(true);
+        node["measured"] = json!({"width": 10, "height": 10});
+        let new = flow(json!([node]), json!([]));
+
+        assert!(diff(&old, &new).is_empty());
+        assert!(diff(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_diff_changed_edge() {
+        let old = flow(
+            json!([]),
+            json!([{"id": "e1", "source": "1", "target": "2"}]),
+        );
+        let new = flow(
+            json!([]),
+            json!([{"id": "e1", "source": "1", "target": "3"}]),
+        );
+
+        let diff = diff(&old, &new);
+        assert_eq!(diff.changed_edges[0].changes[0].field, "target");
+        assert_eq!(diff.changed_edges[0].changes[0].new, json!("3"));
+    }
+}
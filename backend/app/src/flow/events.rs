@@ -0,0 +1,130 @@
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use utoipa::ToSchema;
+
+/// Change of a debug flow in the debug flow directory
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum FlowEvent {
+    /// A debug flow was created
+    #[serde(rename_all = "camelCase")]
+    Created { id: String, revision: String },
+    /// A debug flow was updated
+    #[serde(rename_all = "camelCase")]
+    Updated { id: String, revision: String },
+    /// A debug flow was deleted
+    #[serde(rename_all = "camelCase")]
+    Deleted { id: String },
+}
+
+impl FlowEvent {
+    /// Get the ID of the debug flow the event belongs to
+    pub fn id(&self) -> &str {
+        match self {
+            FlowEvent::Created { id, .. }
+            | FlowEvent::Updated { id, .. }
+            | FlowEvent::Deleted { id } => id,
+        }
+    }
+
+    /// Get the revision of the debug flow after the change, `None` if it was deleted
+    pub fn revision(&self) -> Option<&str> {
+        match self {
+            FlowEvent::Created { revision, .. } | FlowEvent::Updated { revision, .. } => {
+                Some(revision)
+            }
+            FlowEvent::Deleted { .. } => None,
+        }
+    }
+}
+
+/// Last known revisions of the debug flows in a debug flow directory
+///
+/// Changes are reported from the API handlers and from the file system watcher, tracking the revisions
+/// allows to emit exactly one event per change, no matter which of the two noticed it first.
+#[derive(Clone, Default)]
+pub struct FlowRevisions {
+    revisions: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl FlowRevisions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the revisions from an iterator of debug flow IDs and revisions
+    pub fn from_revisions<I>(revisions: I) -> Self
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        Self {
+            revisions: Arc::new(Mutex::new(revisions.into_iter().collect())),
+        }
+    }
+
+    /// Record the current revision of a debug flow and get the event describing the change
+    ///
+    /// * `id` - ID of the debug flow
+    /// * `revision` - Current revision of the debug flow, `None` if the debug flow does not exist
+    ///
+    /// Returns `None` if the revision did not change since the last update.
+    pub fn update(&self, id: &str, revision: Option<String>) -> Option<FlowEvent> {
+        let mut revisions = self
+            .revisions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match revision {
+            Some(revision) => match revisions.insert(id.to_string(), revision.clone()) {
+                None => Some(FlowEvent::Created {
+                    id: id.to_string(),
+                    revision,
+                }),
+                Some(previous) if previous != revision => Some(FlowEvent::Updated {
+                    id: id.to_string(),
+                    revision,
+                }),
+                Some(_) => None,
+            },
+            None => revisions
+                .remove(id)
+                .map(|_| FlowEvent::Deleted { id: id.to_string() }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update() {
+        let revisions = FlowRevisions::from_revisions([("a".to_string(), "1".to_string())]);
+
+        assert_eq!(revisions.update("a", Some("1".to_string())), None);
+        assert_eq!(
+            revisions.update("a", Some("2".to_string())),
+            Some(FlowEvent::Updated {
+                id: "a".to_string(),
+                revision: "2".to_string()
+            })
+        );
+        assert_eq!(
+            revisions.update("b", Some("3".to_string())),
+            Some(FlowEvent::Created {
+                id: "b".to_string(),
+                revision: "3".to_string()
+            })
+        );
+        assert_eq!(
+            revisions.update("a", None),
+            Some(FlowEvent::Deleted {
+                id: "a".to_string()
+            })
+        );
+        assert_eq!(revisions.update("a", None), None);
+    }
+}
@@ -0,0 +1,417 @@
+//! Rendering of debug flows into text formats
+
+use super::{FlowData, Node, StatusNodeState, model::GitMetadata};
+use git2_ox::CommitProperties;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt::Write;
+use utoipa::ToSchema;
+
+/// Text formats a debug flow can be exported to
+#[derive(Deserialize, ToSchema, clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Markdown debugging report
+    Markdown,
+    /// Mermaid flowchart
+    Mermaid,
+    /// Graphviz DOT graph
+    Dot,
+}
+
+impl Format {
+    /// Get the MIME type of the format
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Format::Markdown => "text/markdown",
+            Format::Mermaid => "text/vnd.mermaid",
+            Format::Dot => "text/vnd.graphviz",
+        }
+    }
+}
+
+/// Maximum heading level supported by Markdown
+const MAX_HEADING_LEVEL: usize = 6;
+
+/// Render a debug flow into a Markdown debugging report
+///
+/// The report starts at the root status node and follows the edges of the flow. A linear sequence of
+/// nodes is rendered as consecutive sections, each branch of the graph becomes a nested section
+/// containing the nodes of the branch.
+/// Nodes which are not reachable from the root node are listed at the end of the report.
+///
+/// * `flow` - Debug flow to render
+/// * `resolve_commit` - Resolves the Git revision of a node to a commit, `None` if it cannot be resolved
+pub fn to_markdown<F>(flow: &FlowData, resolve_commit: F) -> String
+where
+    F: Fn(&GitMetadata) -> Option<git2_ox::Commit>,
+{
+    let mut renderer = MarkdownRenderer {
+        flow,
+        resolve_commit,
+        visited: HashSet::new(),
+        out: String::new(),
+    };
+    renderer.render()
+}
+
+struct MarkdownRenderer<'a, F> {
+    flow: &'a FlowData,
+    resolve_commit: F,
+    visited: HashSet<&'a str>,
+    out: String,
+}
+
+impl<'a, F> MarkdownRenderer<'a, F>
+where
+    F: Fn(&GitMetadata) -> Option<git2_ox::Commit>,
+{
+    fn render(&mut self) -> String {
+        let reactflow = self.flow.reactflow();
+        let _ = writeln!(self.out, "# {}", self.flow.name());
+
+        for root in reactflow.root_nodes() {
+            self.render_node(root, 2);
+        }
+
+        let unconnected: Vec<&Node> = reactflow
+            .nodes()
+            .iter()
+            .filter(|node| !self.visited.contains(node.id.as_str()))
+            .collect();
+        if !unconnected.is_empty() {
+            let _ = write!(self.out, "\n## Unconnected nodes\n");
+            for node in unconnected {
+                self.render_node(node, 3);
+            }
+        }
+        std::mem::take(&mut self.out)
+    }
+
+    fn render_node(&mut self, node: &'a Node, level: usize) {
+        let heading = "#".repeat(level.min(MAX_HEADING_LEVEL));
+        if !self.visited.insert(node.id.as_str()) {
+            // Nodes reachable on multiple paths are only rendered once
+            let _ = write!(self.out, "\n{heading} {} (see above)\n", node.title());
+            return;
+        }
+
+        let _ = write!(self.out, "\n{heading} {}\n\n", node.title());
+        match node.state() {
+            Some(state) => {
+                let _ = writeln!(self.out, "- **Type:** Status");
+                let _ = writeln!(self.out, "- **State:** {}", state.as_str());
+            }
+            None => {
+                let _ = writeln!(self.out, "- **Type:** Action");
+            }
+        }
+        if let Some(git) = node.git() {
+            let _ = writeln!(self.out, "- **Revision:** {}", self.format_revision(git));
+        }
+
+        let description = node.description().trim();
+        if !description.is_empty() {
+            let _ = write!(self.out, "\n{description}\n");
+        }
+
+        let successors = self.flow.reactflow().successors(&node.id);
+        match successors.as_slice() {
+            [] => {}
+            // A sequence of nodes continues on the same level
+            [next] => self.render_node(next, level),
+            branches => {
+                let branch_heading = "#".repeat((level + 1).min(MAX_HEADING_LEVEL));
+                for (i, branch) in branches.iter().enumerate() {
+                    let _ = write!(
+                        self.out,
+                        "\n{branch_heading} Branch {}: {}\n",
+                        i + 1,
+                        branch.title()
+                    );
+                    self.render_node(branch, level + 2);
+                }
+            }
+        }
+    }
+
+    fn format_revision(&self, git: &GitMetadata) -> String {
+        match (self.resolve_commit)(git) {
+            Some(commit) => {
+                let author = commit.author().name();
+                let short_id = commit.short_id();
+                if git.rev().starts_with(short_id) {
+                    format!("`{short_id}` {} ({author})", commit.summary())
+                } else {
+                    format!(
+                        "`{}` at `{short_id}` {} ({author})",
+                        git.rev(),
+                        commit.summary()
+                    )
+                }
+            }
+            None => format!("`{}` (not found in repository)", git.rev()),
+        }
+    }
+}
+
+/// Fill color of status nodes in a state, matching the colors of the state icons in the frontend
+fn state_color(state: StatusNodeState) -> &'static str {
+    match state {
+        StatusNodeState::Unknown => "#a1a1aa",
+        StatusNodeState::Progress => "#fbbf24",
+        StatusNodeState::Fail => "#f87171",
+        StatusNodeState::Success => "#34d399",
+    }
+}
+
+/// Lines of the label of a node: its title and the Git revision it is linked to
+fn label_lines(node: &Node) -> Vec<&str> {
+    let mut lines = vec![node.title()];
+    if let Some(git) = node.git() {
+        lines.push(git.display_rev());
+    }
+    lines
+}
+
+/// Escape text for use in a quoted Mermaid label
+fn escape_mermaid(text: &str) -> String {
+    text.replace('#', "#35;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+}
+
+/// Render a debug flow into a Mermaid `flowchart`
+///
+/// Status nodes are rendered as rounded boxes filled with the color of their state, the root node as a
+/// stadium and action nodes as rectangles. Each node is labelled with its title and Git revision.
+pub fn to_mermaid(flow: &FlowData) -> String {
+    let reactflow = flow.reactflow();
+    let mut out = String::from("flowchart TD\n");
+
+    // Node IDs of reactflow may contain characters Mermaid does not accept, use the index instead
+    let mermaid_id = |id: &str| {
+        reactflow
+            .nodes()
+            .iter()
+            .position(|n| n.id == id)
+            .map(|i| format!("n{i}"))
+    };
+
+    for (i, node) in reactflow.nodes().iter().enumerate() {
+        let label = label_lines(node)
+            .into_iter()
+            .map(escape_mermaid)
+            .collect::<Vec<_>>()
+            .join("<br/>");
+        let (open, close) = match node.state() {
+            _ if node.is_root_node() => ("([", "])"),
+            Some(_) => ("(", ")"),
+            None => ("[", "]"),
+        };
+        let _ = writeln!(out, "    n{i}{open}\"{label}\"{close}");
+        if let Some(state) = node.state() {
+            let _ = writeln!(out, "    class n{i} {}", state.as_str());
+        }
+    }
+
+    for edge in reactflow.edges() {
+        if let (Some(source), Some(target)) = (mermaid_id(&edge.source), mermaid_id(&edge.target)) {
+            let _ = writeln!(out, "    {source} --> {target}");
+        }
+    }
+
+    for state in [
+        StatusNodeState::Unknown,
+        StatusNodeState::Progress,
+        StatusNodeState::Fail,
+        StatusNodeState::Success,
+    ] {
+        let _ = writeln!(
+            out,
+            "    classDef {} fill:{},stroke:#3f3f46",
+            state.as_str(),
+            state_color(state)
+        );
+    }
+    out
+}
+
+/// Escape text for use in a quoted DOT string
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Render a debug flow into a Graphviz DOT `digraph`
+///
+/// Status nodes are rendered as rounded boxes filled with the color of their state, the root node with a
+/// double border and action nodes as plain boxes. Each node is labelled with its title and Git revision.
+pub fn to_dot(flow: &FlowData) -> String {
+    let reactflow = flow.reactflow();
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph \"{}\" {{", escape_dot(&flow.name()));
+    let _ = writeln!(out, "    node [shape=box];");
+
+    for node in reactflow.nodes() {
+        let label = label_lines(node)
+            .into_iter()
+            .map(escape_dot)
+            .collect::<Vec<_>>()
+            .join("\\n");
+        let mut attributes = vec![format!("label=\"{label}\"")];
+        if let Some(state) = node.state() {
+            attributes.push("style=\"rounded,filled\"".to_string());
+            attributes.push(format!("fillcolor=\"{}\"", state_color(state)));
+        }
+        if node.is_root_node() {
+            attributes.push("peripheries=2".to_string());
+        }
+        let _ = writeln!(
+            out,
+            "    \"{}\" [{}];",
+            escape_dot(&node.id),
+            attributes.join(", ")
+        );
+    }
+
+    for edge in reactflow.edges() {
+        let _ = writeln!(
+            out,
+            "    \"{}\" -> \"{}\";",
+            escape_dot(&edge.source),
+            escape_dot(&edge.target)
+        );
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow() -> FlowData {
+        FlowData::from_json(
+            r#"{
+                "name": "Crash on start",
+                "reactflow": {
+                    "nodes": [
+                        {"id": "fix", "type": "statusNode", "position": {"x": 0, "y": 200},
+                         "data": {"title": "Fixed", "description": "", "state": "success", "git": null, "isRootNode": false}},
+                        {"id": "root", "type": "statusNode", "position": {"x": 0, "y": 0},
+                         "data": {"title": "App crashes", "description": "Segfault in parser", "state": "fail",
+                                  "git": {"rev": "v1.0", "summary": "Release", "type": "tag"}, "isRootNode": true}},
+                        {"id": "a", "type": "actionNode", "position": {"x": -100, "y": 100},
+                         "data": {"title": "Revert parser", "description": "", "git": null}},
+                        {"id": "b", "type": "actionNode", "position": {"x": 100, "y": 100},
+                         "data": {"title": "Update dependency", "description": "", "git": {"rev": "deadbeef", "summary": "", "type": "commit"}}},
+                        {"id": "lonely", "type": "actionNode", "position": {"x": 500, "y": 0},
+                         "data": {"title": "Unrelated", "description": "", "git": null}}
+                    ],
+                    "edges": [
+                        {"id": "e1", "source": "root", "target": "b"},
+                        {"id": "e2", "source": "root", "target": "a"},
+                        {"id": "e3", "source": "a", "target": "fix"}
+                    ]
+                }
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        let markdown = to_markdown(&flow(), |_| None);
+        assert_eq!(
+            markdown,
+            "# Crash on start
+
+## App crashes
+
+- **Type:** Status
+- **State:** fail
+- **Revision:** `v1.0` (not found in repository)
+
+Segfault in parser
+
+### Branch 1: Revert parser
+
+#### Revert parser
+
+- **Type:** Action
+
+#### Fixed
+
+- **Type:** Status
+- **State:** success
+
+### Branch 2: Update dependency
+
+#### Update dependency
+
+- **Type:** Action
+- **Revision:** `deadbeef` (not found in repository)
+
+## Unconnected nodes
+
+### Unrelated
+
+- **Type:** Action
+"
+        );
+    }
+
+    #[test]
+    fn test_to_mermaid() {
+        let mermaid = to_mermaid(&flow());
+        assert_eq!(
+            mermaid,
+            r##"flowchart TD
+    n0("Fixed")
+    class n0 success
+    n1(["App crashes<br/>v1.0"])
+    class n1 fail
+    n2["Revert parser"]
+    n3["Update dependency<br/>deadbee"]
+    n4["Unrelated"]
+    n1 --> n3
+    n1 --> n2
+    n2 --> n0
+    classDef unknown fill:#a1a1aa,stroke:#3f3f46
+    classDef progress fill:#fbbf24,stroke:#3f3f46
+    classDef fail fill:#f87171,stroke:#3f3f46
+    classDef success fill:#34d399,stroke:#3f3f46
+"##
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let dot = to_dot(&flow());
+        assert_eq!(
+            dot,
+            r##"digraph "Crash on start" {
+    node [shape=box];
+    "fix" [label="Fixed", style="rounded,filled", fillcolor="#34d399"];
+    "root" [label="App crashes\nv1.0", style="rounded,filled", fillcolor="#f87171", peripheries=2];
+    "a" [label="Revert parser"];
+    "b" [label="Update dependency\ndeadbee"];
+    "lonely" [label="Unrelated"];
+    "root" -> "b";
+    "root" -> "a";
+    "a" -> "fix";
+}
+"##
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape_mermaid(r#"a "b" <c> #1"#),
+            "a #quot;b#quot; #lt;c#gt; #35;1"
+        );
+        assert_eq!(escape_dot(r#"a "b" \c"#), r#"a \"b\" \\c"#);
+    }
+}
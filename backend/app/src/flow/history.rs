@@ -0,0 +1,186 @@
+//! Versions of debug flows recorded as commits on a dedicated Git reference
+//!
+//! Every debug flow file is committed to [`HISTORY_REF`] by its file name. The reference is not a branch,
+//! recording versions neither touches the index, the working directory nor the branch of the user.
+
+use super::{Error, FlowData, FlowsDir};
+use serde::Serialize;
+use std::{collections::BTreeSet, time};
+use tokio::sync::mpsc;
+use utoipa::ToSchema;
+
+/// Reference the versions of the debug flows are committed to
+pub const HISTORY_REF: &str = "refs/debug-flow/history";
+
+/// Recorded version of a debug flow
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowVersion {
+    /// ID of the commit on the history reference, use this to fetch the version
+    pub version: String,
+    /// Revision of the debug flow in this version, see [`super::Flow::revision`]
+    pub revision: String,
+    /// Date the version was recorded
+    pub date: chrono::DateTime<chrono::Utc>,
+    /// Author configured in the repository when the version was recorded
+    pub author: super::Author,
+}
+
+impl From<git2_ox::FileVersion> for FlowVersion {
+    fn from(version: git2_ox::FileVersion) -> Self {
+        let commit = version.commit();
+        Self {
+            version: git2_ox::CommitProperties::id(commit).to_string(),
+            revision: version.blob_id().to_string(),
+            date: *commit.time(),
+            author: commit.author().clone().into(),
+        }
+    }
+}
+
+/// Get the file name of a debug flow on the history reference
+fn file_name(id: &str) -> String {
+    FlowData::file_name_from_id(id)
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Get the recorded versions of a debug flow, newest first
+///
+/// Returns an empty list if no version has been recorded in the repository yet.
+pub fn versions(flows_dir: &FlowsDir, id: &str) -> Result<Vec<FlowVersion>, Error> {
+    flows_dir.flow_path(id)?;
+    let repo = git2_ox::Repository::try_open(flows_dir.git_repo())?;
+    match repo.file_versions(HISTORY_REF, &file_name(id)) {
+        Ok(versions) => Ok(versions.into_iter().map(FlowVersion::from).collect()),
+        Err(git2_ox::error::Error::NotFound(_)) => Ok(Vec::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get a debug flow as it was recorded in `version` together with its revision
+pub fn flow_at_version(
+    flows_dir: &FlowsDir,
+    id: &str,
+    version: &str,
+) -> Result<(FlowData, String), Error> {
+    flows_dir.flow_path(id)?;
+    let repo = git2_ox::Repository::try_open(flows_dir.git_repo())?;
+    let content = repo.read_file(version, &file_name(id))?;
+    let data = FlowData::from_json(&String::from_utf8_lossy(&content))?;
+    Ok((data, git2_ox::utils::hash_blob(&content)?))
+}
+
+/// Commit the current state of a debug flow to the history reference
+fn record(flows_dir: &FlowsDir, id: &str) -> Result<(), Error> {
+    let path = flows_dir.flow_path(id)?;
+    let content = match std::fs::read(&path) {
+        Ok(content) => content,
+        // The debug flow was deleted or renamed in the meantime
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(Error::Io(path, e)),
+    };
+    let repo = git2_ox::Repository::try_open(flows_dir.git_repo())?;
+    let commit = repo.commit_file_to_reference(
+        HISTORY_REF,
+        &file_name(id),
+        &content,
+        &format!("Update debug flow '{id}'"),
+    )?;
+    if let Some(commit) = commit {
+        log::debug!(
+            "Recorded debug flow '{id}' in {}",
+            git2_ox::CommitProperties::id(&commit)
+        );
+    }
+    Ok(())
+}
+
+/// Records versions of debug flows in the background
+///
+/// Changes are debounced, a version of a debug flow is only recorded once it was not stored for the
+/// debounce duration. This keeps the history readable while the debug flow is edited.
+#[derive(Clone)]
+pub struct FlowHistory {
+    tx: mpsc::UnboundedSender<String>,
+}
+
+impl FlowHistory {
+    /// Start recording versions of the debug flows in `flows_dir`, this requires a Tokio runtime
+    pub fn spawn(flows_dir: FlowsDir, debounce: time::Duration) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run(flows_dir, debounce, rx));
+        Self { tx }
+    }
+
+    /// Schedule recording the current version of a debug flow
+    pub fn record(&self, id: &str) {
+        if self.tx.send(id.to_string()).is_err() {
+            log::error!("Flow history is not running, version of '{id}' is not recorded");
+        }
+    }
+
+    async fn run(
+        flows_dir: FlowsDir,
+        debounce: time::Duration,
+        mut rx: mpsc::UnboundedReceiver<String>,
+    ) {
+        while let Some(id) = rx.recv().await {
+            let mut pending = BTreeSet::from([id]);
+            // Collect changes until the quiet period is over or all senders are gone
+            while let Ok(Some(id)) = tokio::time::timeout(debounce, rx.recv()).await {
+                pending.insert(id);
+            }
+
+            let flows_dir = flows_dir.clone();
+            let result = tokio::task::spawn_blocking(move || {
+                for id in pending {
+                    if let Err(e) = record(&flows_dir, &id) {
+                        log::error!("Failed to record version of debug flow '{id}': {e}");
+                    }
+                }
+            })
+            .await;
+            if let Err(e) = result {
+                log::error!("Recording debug flow versions failed: {e}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_fetch_versions() {
+        let repo = tempfile::tempdir().unwrap();
+        git2_ox::Repository::try_init(repo.path()).unwrap();
+        let flows_dir = FlowsDir::try_new(repo.path()).unwrap();
+        assert!(versions(&flows_dir, "history").unwrap().is_empty());
+
+        let flow = flows_dir.create_flow("History", None, false).unwrap();
+        record(&flows_dir, "history").unwrap();
+        let mut data = flow.data().clone();
+        data.name = "History v2".to_string();
+        let revision = flows_dir.save_flow("history", &data, None).unwrap();
+        record(&flows_dir, "history").unwrap();
+        // Unchanged debug flows do not create new versions
+        record(&flows_dir, "history").unwrap();
+
+        let versions = versions(&flows_dir, "history").unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].revision, revision);
+        assert_eq!(Some(versions[1].revision.as_str()), flow.revision());
+
+        let (old, old_revision) =
+            flow_at_version(&flows_dir, "history", &versions[1].version).unwrap();
+        assert_eq!(old.name(), "History");
+        assert_eq!(Some(old_revision.as_str()), flow.revision());
+        assert!(flow_at_version(&flows_dir, "history", "not-a-version").is_err());
+
+        // The debug flow files are not committed to the branch of the user
+        let git_repo = git2_ox::Repository::try_open(repo.path()).unwrap();
+        assert!(git_repo.get_commit_for_revision("HEAD").is_err());
+    }
+}
@@ -0,0 +1,340 @@
+//! Three-way merge of debug flows
+//!
+//! Nodes and edges are matched by their IDs and merged field by field. Changes to different fields of the
+//! same node merge cleanly, conflicting changes keep our version and add the version of the other branch
+//! as an explicit conflict node next to it, so the conflict can be resolved in the frontend.
+
+use super::{Edge, Error, FlowData, Node, NodeKind};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+/// Suffix of the IDs of nodes and edges holding the conflicting version of the other branch
+const CONFLICT_ID_SUFFIX: &str = "-conflict";
+
+/// Result of a three-way merge
+#[derive(Debug)]
+pub struct MergeOutcome {
+    /// Merged debug flow
+    pub flow: FlowData,
+    /// Descriptions of the conflicts, the merge is clean if this is empty
+    pub conflicts: Vec<String>,
+}
+
+/// Merge two versions of a debug flow which are based on a common version
+///
+/// * `base` - Common ancestor, `None` if both branches added the debug flow
+/// * `ours` - Version of the current branch
+/// * `theirs` - Version of the branch being merged
+pub fn merge(
+    base: Option<&FlowData>,
+    ours: &FlowData,
+    theirs: &FlowData,
+) -> Result<MergeOutcome, Error> {
+    let mut conflicts = Vec::new();
+    let empty = FlowData::new(&ours.name);
+    let base = base.unwrap_or(&empty);
+
+    let mut flow = ours.clone();
+    flow.name = match merge_value(
+        Some(&Value::String(base.name.clone())),
+        Some(&Value::String(ours.name.clone())),
+        Some(&Value::String(theirs.name.clone())),
+    ) {
+        Ok(Some(Value::String(name))) => name,
+        _ => {
+            conflicts.push(format!(
+                "Name changed to '{}' and '{}', keeping '{}'",
+                ours.name, theirs.name, ours.name
+            ));
+            ours.name.clone()
+        }
+    };
+    if theirs.updated_at > ours.updated_at {
+        flow.updated_at = theirs.updated_at;
+        flow.updated_by = theirs.updated_by.clone();
+    }
+
+    flow.reactflow.nodes = merge_elements(
+        &base.reactflow.nodes,
+        &ours.reactflow.nodes,
+        &theirs.reactflow.nodes,
+        |n| &n.id,
+        conflict_node,
+        &mut conflicts,
+    )?;
+    let edges = merge_elements(
+        &base.reactflow.edges,
+        &ours.reactflow.edges,
+        &theirs.reactflow.edges,
+        |e| &e.id,
+        |_, theirs| conflict_edge(theirs),
+        &mut conflicts,
+    )?;
+
+    // Edges added on one branch may connect nodes deleted on the other branch
+    let node_ids: HashSet<&String> = flow.reactflow.nodes.iter().map(|n| &n.id).collect();
+    let (edges, dangling): (Vec<Edge>, Vec<Edge>) = edges
+        .into_iter()
+        .partition(|e| node_ids.contains(&e.source) && node_ids.contains(&e.target));
+    for edge in dangling {
+        conflicts.push(format!(
+            "Edge '{}' connects a deleted node, it was removed",
+            edge.id
+        ));
+    }
+    flow.reactflow.edges = edges;
+
+    Ok(MergeOutcome { flow, conflicts })
+}
+
+/// Merge lists of elements matched by their ID
+///
+/// The order of our elements is kept, elements only added by the other branch are appended.
+fn merge_elements<T, I, C>(
+    base: &[T],
+    ours: &[T],
+    theirs: &[T],
+    id: I,
+    conflict: C,
+    conflicts: &mut Vec<String>,
+) -> Result<Vec<T>, Error>
+where
+    T: Serialize + DeserializeOwned + Clone,
+    I: Fn(&T) -> &String,
+    C: Fn(&T, &T) -> T,
+{
+    let find = |elements: &[T], element_id: &String| -> Result<Option<Value>, Error> {
+        elements
+            .iter()
+            .find(|e| id(e) == element_id)
+            .map(serde_json::to_value)
+            .transpose()
+            .map_err(Error::Json)
+    };
+
+    let mut ids: Vec<&String> = ours.iter().map(&id).collect();
+    for element in theirs {
+        if !ids.contains(&id(element)) {
+            ids.push(id(element));
+        }
+    }
+
+    let mut merged = Vec::new();
+    for element_id in ids {
+        let b = find(base, element_id)?;
+        let o = find(ours, element_id)?;
+        let t = find(theirs, element_id)?;
+        match merge_value(b.as_ref(), o.as_ref(), t.as_ref()) {
+            Ok(None) => {}
+            Ok(Some(value)) => match serde_json::from_value(value) {
+                Ok(element) => merged.push(element),
+                // Fields were merged into an invalid combination, e.g. the type of a node changed
+                Err(_) => {
+                    let ours: T = serde_json::from_value(o.unwrap_or_default())?;
+                    let theirs: T = serde_json::from_value(t.unwrap_or_default())?;
+                    conflicts.push(format!("'{element_id}' was changed on both branches"));
+                    merged.push(ours.clone());
+                    merged.push(conflict(&ours, &theirs));
+                }
+            },
+            Err(()) => match (o, t) {
+                (Some(o), Some(t)) => {
+                    let ours: T = serde_json::from_value(o)?;
+                    let theirs: T = serde_json::from_value(t)?;
+                    conflicts.push(format!("'{element_id}' was changed on both branches"));
+                    merged.push(ours.clone());
+                    merged.push(conflict(&ours, &theirs));
+                }
+                // Deleted on one branch and changed on the other, the change is kept
+                (Some(changed), None) | (None, Some(changed)) => {
+                    conflicts.push(format!(
+                        "'{element_id}' was deleted on one branch and changed on the other, it was kept"
+                    ));
+                    merged.push(serde_json::from_value(changed)?);
+                }
+                (None, None) => {}
+            },
+        }
+    }
+    Ok(merged)
+}
+
+/// Three-way merge of JSON values, `None` is an absent value
+///
+/// Objects are merged key by key, all other values conflict if both sides changed them differently.
+fn merge_value(
+    base: Option<&Value>,
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+) -> Result<Option<Value>, ()> {
+    if ours == theirs || theirs == base {
+        return Ok(ours.cloned());
+    }
+    if ours == base {
+        return Ok(theirs.cloned());
+    }
+    match (base, ours, theirs) {
+        (Some(Value::Object(b)), Some(Value::Object(o)), Some(Value::Object(t))) => {
+            let mut merged = Map::new();
+            let keys = o.keys().chain(t.keys().filter(|k| !o.contains_key(*k)));
+            for key in keys {
+                if let Some(value) = merge_value(b.get(key), o.get(key), t.get(key))? {
+                    merged.insert(key.clone(), value);
+                }
+            }
+            Ok(Some(Value::Object(merged)))
+        }
+        _ => Err(()),
+    }
+}
+
+/// Create the conflict node holding their version of a node next to our version
+fn conflict_node(ours: &Node, theirs: &Node) -> Node {
+    let mut node = theirs.clone();
+    node.id = format!("{}{CONFLICT_ID_SUFFIX}", theirs.id);
+    node.position.x = ours.position.x;
+    node.position.y = ours.position.y + 120.0;
+    let note = format!(
+        "Merge conflict: this is the version of node '{}' from the merged branch, resolve the conflict and \
+        delete one of the nodes.",
+        theirs.id
+    );
+    match &mut node.kind {
+        NodeKind::Action(data) => {
+            data.title = format!("Conflict: {}", data.title);
+            data.description = format!("{note}\n\n{}", data.description);
+        }
+        NodeKind::Status(data) => {
+            data.title = format!("Conflict: {}", data.title);
+            data.description = format!("{note}\n\n{}", data.description);
+            data.is_root_node = false;
+        }
+    }
+    node
+}
+
+/// Create the conflict edge holding their version of an edge
+fn conflict_edge(theirs: &Edge) -> Edge {
+    let mut edge = theirs.clone();
+    edge.id = format!("{}{CONFLICT_ID_SUFFIX}", theirs.id);
+    edge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn node(id: &str, title: &str, state: &str) -> Value {
+        json!({
+            "id": id,
+            "type": "statusNode",
+            "position": {"x": 0.0, "y": 0.0},
+            "data": {"title": title, "description": "", "git": null, "state": state, "isRootNode": false}
+        })
+    }
+
+    fn flow(nodes: Value, edges: Value) -> FlowData {
+        serde_json::from_value(json!({
+            "name": "Flow",
+            "reactflow": {"nodes": nodes, "edges": edges}
+        }))
+        .unwrap()
+    }
+
+    fn node_ids(flow: &FlowData) -> Vec<&str> {
+        flow.reactflow.nodes.iter().map(|n| n.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_merge_clean() {
+        let base = flow(json!([node("1", "Root", "unknown")]), json!([]));
+        // Ours changes the state and adds a node, theirs changes the title and adds a node with an edge
+        let ours = flow(
+            json!([node("1", "Root", "fail"), node("2", "Ours", "unknown")]),
+            json!([]),
+        );
+        let theirs = flow(
+            json!([
+                node("1", "Crash", "unknown"),
+                node("3", "Theirs", "unknown")
+            ]),
+            json!([{"id": "e1", "source": "1", "target": "3"}]),
+        );
+
+        let outcome = merge(Some(&base), &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty(), "{:?}", outcome.conflicts);
+        assert_eq!(node_ids(&outcome.flow), ["1", "2", "3"]);
+        let root = &outcome.flow.reactflow.nodes[0];
+        assert_eq!(root.title(), "Crash");
+        assert_eq!(root.state(), Some(crate::flow::StatusNodeState::Fail));
+        assert_eq!(outcome.flow.reactflow.edges.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_deletion() {
+        let base = flow(
+            json!([node("1", "Root", "unknown"), node("2", "Gone", "unknown")]),
+            json!([]),
+        );
+        let ours = flow(json!([node("1", "Root", "unknown")]), json!([]));
+        let theirs = base.clone();
+
+        let outcome = merge(Some(&base), &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(node_ids(&outcome.flow), ["1"]);
+    }
+
+    #[test]
+    fn test_merge_conflict() {
+        let base = flow(json!([node("1", "Root", "unknown")]), json!([]));
+        let ours = flow(json!([node("1", "Root", "success")]), json!([]));
+        let theirs = flow(
+            json!([node("1", "Root", "fail"), node("2", "New", "unknown")]),
+            json!([{"id": "e1", "source": "1", "target": "2"}]),
+        );
+
+        let outcome = merge(Some(&base), &ours, &theirs).unwrap();
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert_eq!(node_ids(&outcome.flow), ["1", "1-conflict", "2"]);
+        let conflict = &outcome.flow.reactflow.nodes[1];
+        assert_eq!(conflict.title(), "Conflict: Root");
+        assert_eq!(conflict.state(), Some(crate::flow::StatusNodeState::Fail));
+        assert_eq!(
+            outcome.flow.reactflow.nodes[0].state(),
+            Some(crate::flow::StatusNodeState::Success)
+        );
+    }
+
+    #[test]
+    fn test_merge_dangling_edge() {
+        let base = flow(
+            json!([node("1", "Root", "unknown"), node("2", "Child", "unknown")]),
+            json!([]),
+        );
+        let ours = flow(json!([node("1", "Root", "unknown")]), json!([]));
+        let theirs = flow(
+            base.reactflow
+                .nodes
+                .iter()
+                .map(|n| serde_json::to_value(n).unwrap())
+                .collect(),
+            json!([{"id": "e1", "source": "1", "target": "2"}]),
+        );
+
+        let outcome = merge(Some(&base), &ours, &theirs).unwrap();
+        assert_eq!(outcome.conflicts.len(), 1);
+        assert!(outcome.flow.reactflow.edges.is_empty());
+    }
+
+    #[test]
+    fn test_merge_without_base() {
+        let ours = flow(json!([node("1", "Ours", "unknown")]), json!([]));
+        let theirs = flow(json!([node("2", "Theirs", "unknown")]), json!([]));
+
+        let outcome = merge(None, &ours, &theirs).unwrap();
+        assert!(outcome.conflicts.is_empty());
+        assert_eq!(node_ids(&outcome.flow), ["1", "2"]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Migrations of stored debug flows to the current schema version
+//!
+//! Debug flows are stored as JSON files in the repository and may have been written by older releases.
+//! Each migration upgrades the raw JSON of a flow by exactly one schema version, [`migrate`] applies all
+//! migrations required to get to [`CURRENT_SCHEMA_VERSION`] before the flow is deserialized.
+
+use super::Error;
+use serde_json::{Map, Value};
+
+/// Schema version of flows written by this version of the application
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Key of the schema version in the flow data
+const SCHEMA_VERSION_KEY: &str = "schemaVersion";
+
+/// Migrates the JSON value of a flow from one schema version to the next
+type Migration = fn(&mut Map<String, Value>) -> Result<(), Error>;
+
+/// Migrations ordered by the schema version they migrate from
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Get the schema version of the raw JSON of a flow, flows without a version are version 0
+fn schema_version(flow: &Map<String, Value>) -> Result<u32, Error> {
+    match flow.get(SCHEMA_VERSION_KEY) {
+        None => Ok(0),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| Error::InvalidFlow(format!("Invalid schema version '{version}'"))),
+    }
+}
+
+/// Migrate the raw JSON of a flow to the current schema version
+///
+/// * `value` - JSON value of the flow as read from the file
+pub fn migrate(mut value: Value) -> Result<Value, Error> {
+    let flow = value
+        .as_object_mut()
+        .ok_or_else(|| Error::InvalidFlow("Flow is not a JSON object".to_string()))?;
+
+    let version = schema_version(flow)?;
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(Error::UnsupportedSchemaVersion(version));
+    }
+
+    for (from_version, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        log::debug!(
+            "Migrating flow from schema version {from_version} to {}",
+            from_version + 1
+        );
+        migration(flow)?;
+        flow.insert(SCHEMA_VERSION_KEY.to_string(), (from_version + 1).into());
+    }
+    Ok(value)
+}
+
+/// Get the array stored at `key` in the reactflow state of a flow
+fn reactflow_array<'a>(
+    flow: &'a mut Map<String, Value>,
+    key: &str,
+) -> Result<Option<&'a mut Vec<Value>>, Error> {
+    let Some(reactflow) = flow.get_mut("reactflow") else {
+        return Ok(None);
+    };
+    match reactflow.get_mut(key) {
+        None => Ok(None),
+        Some(Value::Array(values)) => Ok(Some(values)),
+        Some(_) => Err(Error::InvalidFlow(format!(
+            "'reactflow.{key}' is not an array"
+        ))),
+    }
+}
+
+/// Insert `value` at `key` if it does not exist yet
+fn insert_default(object: &mut Map<String, Value>, key: &str, value: Value) {
+    object.entry(key).or_insert(value);
+}
+
+/// Flows without a schema version were written before the backend knew about the node types.
+///
+/// Node data created by older frontend releases may lack fields which were added later, these are
+/// filled with the defaults the frontend uses for new nodes.
+fn migrate_v0_to_v1(flow: &mut Map<String, Value>) -> Result<(), Error> {
+    for node in reactflow_array(flow, "nodes")?.into_iter().flatten() {
+        let Some(node) = node.as_object_mut() else {
+            return Err(Error::InvalidFlow("Node is not a JSON object".to_string()));
+        };
+        insert_default(node, "position", serde_json::json!({"x": 0.0, "y": 0.0}));
+        let is_status_node = node.get("type").and_then(Value::as_str) == Some("statusNode");
+
+        let data = node
+            .entry("data")
+            .or_insert_with(|| Value::Object(Map::new()));
+        let Some(data) = data.as_object_mut() else {
+            return Err(Error::InvalidFlow(
+                "Node data is not a JSON object".to_string(),
+            ));
+        };
+        insert_default(data, "title", Value::String(String::new()));
+        insert_default(data, "description", Value::String(String::new()));
+        insert_default(data, "git", Value::Null);
+        if is_status_node {
+            insert_default(data, "state", Value::String("unknown".to_string()));
+            insert_default(data, "isRootNode", Value::Bool(false));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_v0() {
+        let migrated = migrate(json!({
+            "name": "Old flow",
+            "reactflow": {
+                "nodes": [
+                    {"id": "1", "type": "statusNode", "position": {"x": 1.0, "y": 2.0}, "data": {"title": "Root"}},
+                    {"id": "2", "type": "actionNode", "data": {"title": "Action", "description": "Do it"}}
+                ],
+                "edges": []
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(migrated["schemaVersion"], json!(CURRENT_SCHEMA_VERSION));
+        assert_eq!(
+            migrated["reactflow"]["nodes"][0]["data"],
+            json!({"title": "Root", "description": "", "git": null, "state": "unknown", "isRootNode": false})
+        );
+        assert_eq!(
+            migrated["reactflow"]["nodes"][1]["data"],
+            json!({"title": "Action", "description": "Do it", "git": null})
+        );
+        assert_eq!(
+            migrated["reactflow"]["nodes"][1]["position"],
+            json!({"x": 0.0, "y": 0.0})
+        );
+    }
+
+    #[test]
+    fn test_migrate_current_is_noop() {
+        let flow = json!({
+            "schemaVersion": CURRENT_SCHEMA_VERSION,
+            "name": "Flow",
+            "reactflow": {"nodes": [{"id": "1", "type": "actionNode", "data": {}}], "edges": []}
+        });
+        assert_eq!(migrate(flow.clone()).unwrap(), flow);
+    }
+
+    #[test]
+    fn test_migrate_unsupported_version() {
+        let flow = json!({"schemaVersion": CURRENT_SCHEMA_VERSION + 1, "name": "Flow"});
+        assert!(matches!(
+            migrate(flow),
+            Err(Error::UnsupportedSchemaVersion(v)) if v == CURRENT_SCHEMA_VERSION + 1
+        ));
+    }
+}
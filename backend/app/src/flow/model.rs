@@ -0,0 +1,469 @@
+use super::Error;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Additional properties of nodes, node data and edges which are only relevant for the frontend.
+///
+/// Reactflow adds fields like `measured`, `selected` or `dragging` to its objects, these are kept
+/// as-is so storing a flow does not drop anything the frontend relies on.
+pub type Extra = serde_json::Map<String, serde_json::Value>;
+
+/// Person who created or changed a debug flow, taken from the Git configuration of the repository
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Author {
+    /// Value of `user.name`
+    pub name: String,
+    /// Value of `user.email`
+    pub email: String,
+}
+
+impl From<git2_ox::Signature> for Author {
+    fn from(signature: git2_ox::Signature) -> Self {
+        Author {
+            name: signature.name().to_string(),
+            email: signature.email().to_string(),
+        }
+    }
+}
+
+/// Kind of the Git revision a node is linked to
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum GitMetadataKind {
+    Commit,
+    Tag,
+    Branch,
+}
+
+/// Git revision linked to a node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GitMetadata {
+    /// Commit ID, tag name or branch name
+    rev: String,
+    /// Summary of the referred commit, for branches the summary of the branch head
+    summary: String,
+    /// Kind of the revision
+    #[serde(rename = "type")]
+    kind: GitMetadataKind,
+}
+
+impl GitMetadata {
+    pub fn new(rev: &str, summary: &str, kind: GitMetadataKind) -> Self {
+        Self {
+            rev: rev.to_string(),
+            summary: summary.to_string(),
+            kind,
+        }
+    }
+
+    /// Get the revision
+    pub fn rev(&self) -> &str {
+        &self.rev
+    }
+
+    /// Get the summary of the referred commit
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// Get the kind of the revision
+    pub fn kind(&self) -> GitMetadataKind {
+        self.kind
+    }
+
+    /// Get the revision for display, commit IDs are abbreviated while references are kept as-is
+    pub fn display_rev(&self) -> &str {
+        match self.kind {
+            // The revision is read from flow files, it is not necessarily an ASCII commit ID
+            GitMetadataKind::Commit => self
+                .rev
+                .char_indices()
+                .nth(7)
+                .map_or(&self.rev[..], |(i, _)| &self.rev[..i]),
+            GitMetadataKind::Tag | GitMetadataKind::Branch => &self.rev,
+        }
+    }
+}
+
+/// State of a status node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatusNodeState {
+    #[default]
+    Unknown,
+    Fail,
+    Progress,
+    Success,
+}
+
+impl StatusNodeState {
+    /// Get the serialized name of the state
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusNodeState::Unknown => "unknown",
+            StatusNodeState::Fail => "fail",
+            StatusNodeState::Progress => "progress",
+            StatusNodeState::Success => "success",
+        }
+    }
+}
+
+/// Maximum number of command results attached to a node
+///
+/// Each result keeps the end of the output of the command, without a limit the file of a debug flow would grow
+/// with every run.
+pub const MAX_COMMAND_RESULTS: usize = 5;
+
+/// Result of a command run in a checkout of the revision of a node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandResult {
+    /// Shell command
+    pub command: String,
+    /// ID of the commit the command was run on
+    pub commit: String,
+    /// Exit code of the command, `None` if it was terminated by a signal or timed out
+    pub exit_code: Option<i32>,
+    /// Whether the command was killed after the timeout
+    pub timed_out: bool,
+    /// End of the standard output
+    pub stdout: String,
+    /// End of the standard error output
+    pub stderr: String,
+    /// Date the command was started
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Date the command finished
+    pub finished_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CommandResult {
+    /// Whether the command exited successfully within the timeout
+    pub fn is_success(&self) -> bool {
+        self.exit_code == Some(0) && !self.timed_out
+    }
+}
+
+/// Uncommitted changes stashed to check out the revision of a node in the working directory
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StashRecord {
+    /// ID of the stash commit
+    pub id: String,
+    /// Message of the stash
+    pub message: String,
+    /// ID of the commit which was checked out
+    pub commit: String,
+    /// Date the changes were stashed
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result attached to a node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum Attachment {
+    /// Result of a command run at the revision of the node
+    CommandResult(CommandResult),
+    /// Changes stashed to check out the revision of the node, they can be restored
+    Stash(StashRecord),
+}
+
+/// Data of an action node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionNodeData {
+    /// Title of the node
+    pub title: String,
+    /// Markdown description of the node
+    pub description: String,
+    /// Git revision the action was performed on
+    pub git: Option<GitMetadata>,
+    /// Results attached to the node, e.g. of commands run at the revision
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Frontend specific properties of the node data
+    #[serde(flatten)]
+    #[schema(value_type = HashMap<String, serde_json::Value>)]
+    pub extra: Extra,
+}
+
+/// Data of a status node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusNodeData {
+    /// Title of the node
+    pub title: String,
+    /// State of the status
+    pub state: StatusNodeState,
+    /// Markdown description of the node
+    pub description: String,
+    /// Git revision the status was observed on
+    pub git: Option<GitMetadata>,
+    /// Whether this is the root node of the flow, root nodes have no target handle and cannot be deleted
+    pub is_root_node: bool,
+    /// Results attached to the node, e.g. of commands run at the revision
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<Attachment>,
+    /// Frontend specific properties of the node data
+    #[serde(flatten)]
+    #[schema(value_type = HashMap<String, serde_json::Value>)]
+    pub extra: Extra,
+}
+
+/// Type and data of a node
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum NodeKind {
+    #[serde(rename = "actionNode")]
+    Action(ActionNodeData),
+    #[serde(rename = "statusNode")]
+    Status(StatusNodeData),
+}
+
+/// Position of a node on the canvas
+#[derive(Serialize, Deserialize, ToSchema, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Position {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Node of a debug flow
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Node {
+    /// Unique ID of the node in the flow
+    pub id: String,
+    /// Position of the node on the canvas
+    pub position: Position,
+    #[serde(flatten)]
+    pub kind: NodeKind,
+    /// Frontend specific properties of the node
+    #[serde(flatten)]
+    #[schema(value_type = HashMap<String, serde_json::Value>)]
+    pub extra: Extra,
+}
+
+impl Node {
+    /// Get the title of the node
+    pub fn title(&self) -> &str {
+        match &self.kind {
+            NodeKind::Action(data) => &data.title,
+            NodeKind::Status(data) => &data.title,
+        }
+    }
+
+    /// Get the description of the node
+    pub fn description(&self) -> &str {
+        match &self.kind {
+            NodeKind::Action(data) => &data.description,
+            NodeKind::Status(data) => &data.description,
+        }
+    }
+
+    /// Get the Git revision linked to the node
+    pub fn git(&self) -> Option<&GitMetadata> {
+        match &self.kind {
+            NodeKind::Action(data) => data.git.as_ref(),
+            NodeKind::Status(data) => data.git.as_ref(),
+        }
+    }
+
+    /// Get the commit ID, tag name or branch name linked to the node, fails with [`Error::NoRevision`] if the
+    /// node is not linked to a revision
+    pub fn revision(&self) -> Result<&str, Error> {
+        self.git()
+            .map(GitMetadata::rev)
+            .ok_or_else(|| Error::NoRevision(self.id.clone()))
+    }
+
+    /// Get the Git revision linked to the node for modification
+    pub fn git_mut(&mut self) -> Option<&mut GitMetadata> {
+        match &mut self.kind {
+            NodeKind::Action(data) => data.git.as_mut(),
+            NodeKind::Status(data) => data.git.as_mut(),
+        }
+    }
+
+    /// Get the state of the node, `None` for action nodes
+    pub fn state(&self) -> Option<StatusNodeState> {
+        match &self.kind {
+            NodeKind::Action(_) => None,
+            NodeKind::Status(data) => Some(data.state),
+        }
+    }
+
+    /// Get the results attached to the node
+    pub fn attachments(&self) -> &[Attachment] {
+        match &self.kind {
+            NodeKind::Action(data) => &data.attachments,
+            NodeKind::Status(data) => &data.attachments,
+        }
+    }
+
+    fn attachments_mut(&mut self) -> &mut Vec<Attachment> {
+        match &mut self.kind {
+            NodeKind::Action(data) => &mut data.attachments,
+            NodeKind::Status(data) => &mut data.attachments,
+        }
+    }
+
+    /// Attach a result to the node
+    ///
+    /// Only the last [`MAX_COMMAND_RESULTS`] command results are kept, older ones are removed.
+    pub fn add_attachment(&mut self, attachment: Attachment) {
+        let attachments = self.attachments_mut();
+        attachments.push(attachment);
+        let is_result =
+            |attachment: &Attachment| matches!(attachment, Attachment::CommandResult(_));
+        let mut excess = attachments
+            .iter()
+            .filter(|attachment| is_result(attachment))
+            .count()
+            .saturating_sub(MAX_COMMAND_RESULTS);
+        attachments.retain(|attachment| {
+            if excess > 0 && is_result(attachment) {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Remove and get the last stash attached to the node
+    pub fn take_stash(&mut self) -> Option<StashRecord> {
+        let attachments = self.attachments_mut();
+        let index = attachments
+            .iter()
+            .rposition(|attachment| matches!(attachment, Attachment::Stash(_)))?;
+        match attachments.remove(index) {
+            Attachment::Stash(stash) => Some(stash),
+            _ => None,
+        }
+    }
+
+    /// Set the state of a status node, action nodes have no state and are not changed
+    pub fn set_state(&mut self, state: StatusNodeState) {
+        if let NodeKind::Status(data) = &mut self.kind {
+            data.state = state;
+        }
+    }
+
+    /// Whether the node is the root node of the flow
+    pub fn is_root_node(&self) -> bool {
+        matches!(&self.kind, NodeKind::Status(data) if data.is_root_node)
+    }
+}
+
+/// Edge between two nodes of a debug flow
+#[derive(Serialize, Deserialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Edge {
+    /// Unique ID of the edge in the flow
+    pub id: String,
+    /// ID of the source node
+    pub source: String,
+    /// ID of the target node
+    pub target: String,
+    /// Frontend specific properties of the edge
+    #[serde(flatten)]
+    #[schema(value_type = HashMap<String, serde_json::Value>)]
+    pub extra: Extra,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_rev() {
+        let display_rev =
+            |rev: &str, kind| GitMetadata::new(rev, "", kind).display_rev().to_string();
+        assert_eq!(
+            display_rev("0123456789abcdef", GitMetadataKind::Commit),
+            "0123456"
+        );
+        assert_eq!(display_rev("0123", GitMetadataKind::Commit), "0123");
+        assert_eq!(display_rev("äöüäöüäöü", GitMetadataKind::Commit), "äöüäöüä");
+        assert_eq!(
+            display_rev("feature/branch", GitMetadataKind::Branch),
+            "feature/branch"
+        );
+    }
+
+    #[test]
+    fn test_node_data_extra_round_trip() {
+        let json = serde_json::json!({
+            "id": "1",
+            "type": "statusNode",
+            "position": {"x": 0.0, "y": 0.0},
+            "data": {
+                "title": "Crash",
+                "description": "",
+                "state": "fail",
+                "git": null,
+                "isRootNode": true,
+                "collapsed": true
+            }
+        });
+
+        let node: Node = serde_json::from_value(json.clone()).unwrap();
+        let NodeKind::Status(data) = &node.kind else {
+            panic!("expected a status node");
+        };
+        assert_eq!(data.extra["collapsed"], true);
+        assert_eq!(serde_json::to_value(&node).unwrap(), json);
+
+        let json = serde_json::json!({
+            "id": "2",
+            "type": "actionNode",
+            "position": {"x": 0.0, "y": 0.0},
+            "data": {"title": "Revert", "description": "", "git": null, "color": "red"}
+        });
+        let node: Node = serde_json::from_value(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&node).unwrap(), json);
+    }
+
+    #[test]
+    fn test_add_attachment_keeps_last_results() {
+        let mut node: Node = serde_json::from_value(serde_json::json!({
+            "id": "1",
+            "type": "actionNode",
+            "position": {"x": 0.0, "y": 0.0},
+            "data": {"title": "Build", "description": "", "git": null}
+        }))
+        .unwrap();
+        let stash = StashRecord {
+            id: "0123".to_string(),
+            message: "Stash".to_string(),
+            commit: "4567".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        node.add_attachment(Attachment::Stash(stash.clone()));
+        let result = |command: usize| CommandResult {
+            command: command.to_string(),
+            commit: "4567".to_string(),
+            exit_code: Some(0),
+            timed_out: false,
+            stdout: String::new(),
+            stderr: String::new(),
+            started_at: chrono::Utc::now(),
+            finished_at: chrono::Utc::now(),
+        };
+        for command in 0..MAX_COMMAND_RESULTS + 2 {
+            node.add_attachment(Attachment::CommandResult(result(command)));
+        }
+
+        let commands: Vec<_> = node
+            .attachments()
+            .iter()
+            .filter_map(|attachment| match attachment {
+                Attachment::CommandResult(result) => Some(result.command.as_str()),
+                Attachment::Stash(_) => None,
+            })
+            .collect();
+        assert_eq!(commands, ["2", "3", "4", "5", "6"]);
+        // Stashes are never removed, they can still be restored
+        assert_eq!(node.take_stash(), Some(stash));
+    }
+}
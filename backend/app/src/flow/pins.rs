@@ -0,0 +1,92 @@
+//! Hidden references protecting the commits linked in debug flows from garbage collection
+//!
+//! Every commit a node links to is referenced by `refs/debug-flow/pins/<flow-id>/<node-id>`. Commits which
+//! are no longer on any branch, e.g. after an amend or a rebase, thereby stay available and a saved debugging
+//! session can always check out what it recorded.
+
+use super::FlowData;
+use crate::utils;
+use git2_ox::CommitProperties;
+
+/// Namespace of the references pinning the linked commits
+pub const PINS_REF_PREFIX: &str = "refs/debug-flow/pins";
+
+/// Get the namespace of the references of a debug flow
+pub fn flow_ref_prefix(id: &str) -> String {
+    format!("{PINS_REF_PREFIX}/{}", utils::encode_component(id))
+}
+
+/// Make the pins of a debug flow reference exactly the commits linked in `data`
+///
+/// Revisions which do not resolve are skipped, all pins of the debug flow are removed if `data` is `None`.
+pub fn sync(
+    repo: &git2_ox::Repository,
+    id: &str,
+    data: Option<&FlowData>,
+) -> Result<(), git2_ox::error::Error> {
+    let nodes = data.map(|d| d.reactflow().nodes()).unwrap_or_default();
+    let targets: Vec<(String, String)> = nodes
+        .iter()
+        .filter_map(|node| {
+            let commit = repo.get_commit_for_revision(node.git()?.rev()).ok()?;
+            Some((
+                utils::encode_component(&node.id),
+                commit.commit().id().to_string(),
+            ))
+        })
+        .collect();
+    repo.sync_references(
+        &flow_ref_prefix(id),
+        targets,
+        &format!("debug-flow: pin commits of '{id}'"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_pins_follow_debug_flow() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2_ox::Repository::try_init(dir.path()).unwrap();
+        let git = repo.repo();
+        let mut config = git.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = git.signature().unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let commit = git
+            .commit(Some("HEAD"), &signature, &signature, "First", &tree, &[])
+            .unwrap()
+            .to_string();
+        let pin = |name: &str| git.refname_to_id(name).ok().map(|oid| oid.to_string());
+
+        let flows_dir = crate::flow::FlowsDir::try_new(dir.path()).unwrap();
+        let flow = flows_dir.create_flow("Amended", None, false).unwrap();
+        let mut data = flow.data().clone();
+        data.reactflow = serde_json::from_value(serde_json::json!({
+            "nodes": [
+                {"id": "n.1", "type": "actionNode", "position": {"x": 0, "y": 0},
+                 "data": {"title": "", "description": "", "git": {"rev": "HEAD", "summary": "", "type": "branch"}}},
+                {"id": "2", "type": "actionNode", "position": {"x": 0, "y": 0},
+                 "data": {"title": "", "description": "", "git": {"rev": "gone", "summary": "", "type": "tag"}}}
+            ],
+            "edges": []
+        }))
+        .unwrap();
+        flows_dir.save_flow("amended", &data, None).unwrap();
+        assert_eq!(
+            pin("refs/debug-flow/pins/amended/n%2E1"),
+            Some(commit.clone())
+        );
+        assert_eq!(pin("refs/debug-flow/pins/amended/2"), None);
+
+        flows_dir.rename_flow("amended", "Rebased").unwrap();
+        assert_eq!(pin("refs/debug-flow/pins/amended/n%2E1"), None);
+        assert_eq!(pin("refs/debug-flow/pins/rebased/n%2E1"), Some(commit));
+
+        flows_dir.delete_flow_by_id("rebased").unwrap();
+        assert_eq!(pin("refs/debug-flow/pins/rebased/n%2E1"), None);
+    }
+}
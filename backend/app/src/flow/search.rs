@@ -0,0 +1,349 @@
+//! Full-text search across all debug flows of a debug flow directory
+//!
+//! The searchable texts of all debug flows are kept in memory, the index is updated whenever a debug flow
+//! changes so searching never has to read the flow files.
+
+use super::{Flow, Node};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+use utoipa::ToSchema;
+
+/// Number of characters shown before and after the first match in a snippet
+const SNIPPET_CONTEXT: usize = 40;
+
+/// Part of a debug flow a search matched
+#[derive(Serialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    /// Name of the debug flow
+    Name,
+    /// Title of a node
+    Title,
+    /// Description of a node
+    Description,
+    /// Git revision a node is linked to
+    Revision,
+    /// Summary of the commit a node is linked to
+    Summary,
+}
+
+/// Range of characters in a snippet which matched a search term
+#[derive(Serialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Highlight {
+    /// Index of the first matching character
+    pub start: usize,
+    /// Index after the last matching character
+    pub end: usize,
+}
+
+/// Match of a search in a debug flow
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    /// ID of the debug flow
+    pub flow_id: String,
+    /// Name of the debug flow
+    pub flow_name: String,
+    /// ID of the matching node, `None` if the name of the debug flow matched
+    pub node_id: Option<String>,
+    /// Matching part of the debug flow
+    pub field: SearchField,
+    /// Excerpt of the matching text
+    pub snippet: String,
+    /// Character ranges of the search terms in the snippet
+    pub highlights: Vec<Highlight>,
+}
+
+/// Searchable text of a debug flow
+#[derive(Debug)]
+struct Document {
+    node_id: Option<String>,
+    field: SearchField,
+    text: Vec<char>,
+    /// Case folded `text`, each character maps to the character at the same index in `text`
+    folded: Vec<char>,
+}
+
+impl Document {
+    fn new(node_id: Option<&str>, field: SearchField, text: &str) -> Self {
+        let text: Vec<char> = text.chars().collect();
+        Self {
+            node_id: node_id.map(str::to_string),
+            field,
+            folded: text.iter().map(|c| fold(*c)).collect(),
+            text,
+        }
+    }
+
+    /// Get the character ranges of all occurrences of all terms, `None` if any term does not occur
+    fn find(&self, terms: &[Vec<char>]) -> Option<Vec<Highlight>> {
+        let mut highlights = Vec::new();
+        for term in terms {
+            let found: Vec<Highlight> = (0..self.folded.len().saturating_sub(term.len() - 1))
+                .filter(|&start| self.folded[start..].starts_with(term))
+                .map(|start| Highlight {
+                    start,
+                    end: start + term.len(),
+                })
+                .collect();
+            if found.is_empty() {
+                return None;
+            }
+            highlights.extend(found);
+        }
+        highlights.sort_by_key(|h| h.start);
+        Some(highlights)
+    }
+
+    /// Cut the text around the first highlight and move the highlights into the snippet
+    fn snippet(&self, highlights: Vec<Highlight>) -> (String, Vec<Highlight>) {
+        let (first_start, first_end) = highlights.first().map_or((0, 0), |h| (h.start, h.end));
+        let start = first_start.saturating_sub(SNIPPET_CONTEXT);
+        let end = (first_end + SNIPPET_CONTEXT).min(self.text.len());
+
+        let mut snippet = String::new();
+        let mut offset = start;
+        if start > 0 {
+            snippet.push('…');
+            offset -= 1;
+        }
+        snippet.extend(&self.text[start..end]);
+        if end < self.text.len() {
+            snippet.push('…');
+        }
+
+        let highlights = highlights
+            .into_iter()
+            .filter(|h| h.start >= start && h.end <= end)
+            .map(|h| Highlight {
+                start: h.start - offset,
+                end: h.end - offset,
+            })
+            .collect();
+        (snippet, highlights)
+    }
+}
+
+/// Fold a character for case insensitive comparisons, this never changes the number of characters
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Indexed debug flow
+#[derive(Debug)]
+struct IndexedFlow {
+    name: String,
+    documents: Vec<Document>,
+}
+
+impl IndexedFlow {
+    fn new(flow: &Flow) -> Self {
+        let name = flow.name();
+        let mut documents = vec![Document::new(None, SearchField::Name, &name)];
+        for node in flow.data().reactflow().nodes() {
+            documents.extend(Self::node_documents(node));
+        }
+        Self { name, documents }
+    }
+
+    fn node_documents(node: &Node) -> impl Iterator<Item = Document> + '_ {
+        let id = Some(node.id.as_str());
+        let git = node.git();
+        [
+            Some((SearchField::Title, node.title())),
+            Some((SearchField::Description, node.description())),
+            git.map(|g| (SearchField::Revision, g.rev())),
+            git.map(|g| (SearchField::Summary, g.summary())),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|(_, text)| !text.is_empty())
+        .map(move |(field, text)| Document::new(id, field, text))
+    }
+}
+
+/// In-memory search index over all debug flows of a debug flow directory
+#[derive(Clone, Default)]
+pub struct SearchIndex {
+    flows: Arc<RwLock<HashMap<String, IndexedFlow>>>,
+}
+
+impl SearchIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create the index from all debug flows of a debug flow directory
+    pub fn from_flows<I>(flows: I) -> Self
+    where
+        I: IntoIterator<Item = Flow>,
+    {
+        let index = Self::new();
+        for flow in flows {
+            index.update(&flow);
+        }
+        index
+    }
+
+    /// Add a debug flow to the index or replace the indexed version of it
+    pub fn update(&self, flow: &Flow) {
+        let indexed = IndexedFlow::new(flow);
+        self.flows
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(flow.id(), indexed);
+    }
+
+    /// Remove a debug flow from the index
+    pub fn remove(&self, id: &str) {
+        self.flows
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .remove(id);
+    }
+
+    /// Search all debug flows
+    ///
+    /// The query is split into terms at whitespace, a text matches if it contains all terms ignoring case.
+    /// Matches are ordered by debug flow ID and the order of the nodes in the debug flow.
+    ///
+    /// * `query` - Search query
+    /// * `limit` - Maximum number of matches to return
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchMatch> {
+        let terms: Vec<Vec<char>> = query
+            .split_whitespace()
+            .map(|term| term.chars().map(fold).collect())
+            .collect();
+        if terms.is_empty() {
+            return Vec::new();
+        }
+
+        let flows = self
+            .flows
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut ids: Vec<&String> = flows.keys().collect();
+        ids.sort();
+
+        ids.into_iter()
+            .flat_map(|id| {
+                let flow = &flows[id];
+                flow.documents.iter().filter_map(|document| {
+                    let highlights = document.find(&terms)?;
+                    let (snippet, highlights) = document.snippet(highlights);
+                    Some(SearchMatch {
+                        flow_id: id.clone(),
+                        flow_name: flow.name.clone(),
+                        node_id: document.node_id.clone(),
+                        field: document.field,
+                        snippet,
+                        highlights,
+                    })
+                })
+            })
+            .take(limit)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::FlowData;
+
+    fn flow(id: &str, json: &str) -> Flow {
+        Flow {
+            path: std::path::PathBuf::from(format!(".debug-flow/{id}.json")),
+            data: FlowData::from_json(json).unwrap(),
+            revision: None,
+        }
+    }
+
+    fn tls_flow() -> Flow {
+        flow(
+            "tls",
+            r#"{
+                "name": "TLS handshake",
+                "reactflow": {
+                    "nodes": [
+                        {"id": "1", "type": "statusNode", "position": {"x": 0, "y": 0},
+                         "data": {"title": "Handshake fails", "description": "", "git": null, "state": "fail", "isRootNode": true}},
+                        {"id": "2", "type": "actionNode", "position": {"x": 0, "y": 0},
+                         "data": {"title": "Bisect", "description": "We bisected the TLS regression down to the cipher update",
+                                  "git": {"rev": "abc1234def", "summary": "Update cipher suites", "type": "commit"}}}
+                    ],
+                    "edges": []
+                }
+            }"#,
+        )
+    }
+
+    #[test]
+    fn test_search() {
+        let index = SearchIndex::from_flows([tls_flow()]);
+
+        let matches = index.search("tls REGRESSION", 10);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].flow_id, "tls");
+        assert_eq!(matches[0].node_id.as_deref(), Some("2"));
+        assert_eq!(matches[0].field, SearchField::Description);
+        let snippet: Vec<char> = matches[0].snippet.chars().collect();
+        let highlighted: Vec<String> = matches[0]
+            .highlights
+            .iter()
+            .map(|h| snippet[h.start..h.end].iter().collect())
+            .collect();
+        assert_eq!(highlighted, ["TLS", "regression"]);
+
+        let matches = index.search("handshake", 10);
+        assert_eq!(
+            matches
+                .iter()
+                .map(|m| (m.node_id.as_deref(), m.field))
+                .collect::<Vec<_>>(),
+            [(None, SearchField::Name), (Some("1"), SearchField::Title)]
+        );
+
+        assert_eq!(index.search("abc1234", 10)[0].field, SearchField::Revision);
+        assert_eq!(
+            index.search("cipher suites", 10)[0].field,
+            SearchField::Summary
+        );
+        assert_eq!(index.search("handshake", 1).len(), 1);
+        assert!(index.search("   ", 10).is_empty());
+        assert!(index.search("tls kernel", 10).is_empty());
+    }
+
+    #[test]
+    fn test_update_and_remove() {
+        let index = SearchIndex::from_flows([tls_flow()]);
+        index.update(&flow(
+            "tls",
+            r#"{"name": "Renamed", "reactflow": {"nodes": [], "edges": []}}"#,
+        ));
+        assert!(index.search("handshake", 10).is_empty());
+        assert_eq!(index.search("renamed", 10).len(), 1);
+
+        index.remove("tls");
+        assert!(index.search("renamed", 10).is_empty());
+    }
+
+    #[test]
+    fn test_snippet() {
+        let text = format!("{}needle{}", "a".repeat(100), "b".repeat(100));
+        let document = Document::new(None, SearchField::Description, &text);
+        let highlights = document.find(&["needle".chars().collect()]).unwrap();
+        let (snippet, highlights) = document.snippet(highlights);
+
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        let snippet: Vec<char> = snippet.chars().collect();
+        let highlighted: String = snippet[highlights[0].start..highlights[0].end]
+            .iter()
+            .collect();
+        assert_eq!(highlighted, "needle");
+    }
+}
@@ -0,0 +1,132 @@
+//! Templates for recurring kinds of investigations
+//!
+//! Templates are debug flow files in the `templates` directory of the debug flow directory. Creating a debug
+//! flow from a template copies its nodes and edges and resolves placeholders in all texts of them:
+//!
+//! * `{{HEAD}}` - Hash of the commit `HEAD` points to
+//! * `{{branch}}` - Name of the current branch, `HEAD` if the head is detached
+//!
+//! Unknown placeholders and placeholders which cannot be resolved, e.g. in a repository without commits,
+//! are kept as they are.
+
+use super::{FlowData, ReactFlowState};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Name of the directory in the debug flow directory containing the templates
+pub const TEMPLATES_DIR_NAME: &str = "templates";
+
+/// Metadata of a template
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateMetadata {
+    /// ID of the template, this is the stem of the file it is stored in
+    pub id: String,
+    /// Name of the template
+    pub name: String,
+    /// Number of nodes the template seeds
+    pub num_nodes: usize,
+    /// Number of edges the template seeds
+    pub num_edges: usize,
+}
+
+impl TemplateMetadata {
+    pub fn new(id: &str, data: &FlowData) -> Self {
+        Self {
+            id: id.to_string(),
+            name: data.name(),
+            num_nodes: data.num_nodes(),
+            num_edges: data.num_edges(),
+        }
+    }
+}
+
+/// Values of the placeholders in a template
+#[derive(Clone, Debug, Default)]
+pub struct Placeholders {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Placeholders {
+    /// Take the values of the placeholders from the status of the repository
+    pub fn from_status(status: &git2_ox::Status) -> Self {
+        let head = git2_ox::CommitProperties::id(status.head().commit()).to_string();
+        let branch = status.current_branch().unwrap_or("HEAD").to_string();
+        Self {
+            values: vec![("{{HEAD}}", head), ("{{branch}}", branch)],
+        }
+    }
+
+    /// Replace all known placeholders in `text`
+    pub fn resolve(&self, text: &str) -> String {
+        self.values
+            .iter()
+            .fold(text.to_string(), |text, (placeholder, value)| {
+                text.replace(placeholder, value)
+            })
+    }
+
+    fn resolve_value(&self, value: &mut Value) {
+        match value {
+            Value::String(text) => *text = self.resolve(text),
+            Value::Array(values) => values.iter_mut().for_each(|v| self.resolve_value(v)),
+            Value::Object(object) => object.values_mut().for_each(|v| self.resolve_value(v)),
+            _ => {}
+        }
+    }
+}
+
+/// Create the data of a new debug flow named `name` with the nodes and edges of `template`
+pub fn instantiate(
+    template: &FlowData,
+    name: &str,
+    placeholders: &Placeholders,
+) -> Result<FlowData, super::Error> {
+    let mut reactflow = serde_json::to_value(&template.reactflow)?;
+    placeholders.resolve_value(&mut reactflow);
+
+    let mut data = FlowData::new(name);
+    data.reactflow = serde_json::from_value::<ReactFlowState>(reactflow)?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_instantiate() {
+        let template = FlowData::from_json(
+            r#"{
+                "name": "Performance regression",
+                "reactflow": {
+                    "nodes": [
+                        {"id": "1", "type": "statusNode", "position": {"x": 0, "y": 0},
+                         "data": {"title": "Slow on {{branch}}", "description": "Measured at {{HEAD}}, {{unknown}}",
+                                  "git": {"rev": "{{HEAD}}", "summary": "", "type": "commit"},
+                                  "state": "fail", "isRootNode": true}}
+                    ],
+                    "edges": []
+                }
+            }"#,
+        )
+        .unwrap();
+        let placeholders = Placeholders {
+            values: vec![
+                ("{{HEAD}}", "abc123".to_string()),
+                ("{{branch}}", "main".to_string()),
+            ],
+        };
+
+        let data = instantiate(&template, "Slow startup", &placeholders).unwrap();
+        assert_eq!(data.name(), "Slow startup");
+        let node = &data.reactflow().nodes()[0];
+        assert_eq!(node.title(), "Slow on main");
+        assert_eq!(node.description(), "Measured at abc123, {{unknown}}");
+        assert_eq!(node.git().unwrap().rev(), "abc123");
+
+        let data = instantiate(&template, "Unresolved", &Placeholders::default()).unwrap();
+        assert_eq!(data.reactflow().nodes()[0].title(), "Slow on {{branch}}");
+    }
+}
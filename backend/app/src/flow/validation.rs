@@ -0,0 +1,261 @@
+//! Validation of the Git revisions linked to the nodes of a debug flow
+//!
+//! Revisions recorded in a debug flow may stop resolving after a rebase, the deletion of a branch or a
+//! garbage collection. Branches and tags can also move to another commit after they were linked. The
+//! summary recorded with a revision is used to detect moved branches and tags.
+
+use super::{
+    FlowData,
+    model::{GitMetadata, GitMetadataKind},
+};
+use git2_ox::CommitProperties;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Problem of a revision linked to a node
+#[derive(Serialize, ToSchema, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RevisionProblem {
+    /// The revision does not resolve anymore, e.g. the branch or tag was deleted or the commit was removed
+    Missing,
+    /// The commit exists but is not reachable from any branch or tag, only the pins of debug flows keep it
+    /// from being removed by a garbage collection, see [`super::pins`]
+    Unreachable,
+    /// The branch or tag points to a different commit than when it was linked
+    Moved,
+}
+
+/// Revision of a node which needs attention
+#[derive(Serialize, ToSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionIssue {
+    /// ID of the node
+    pub node_id: String,
+    /// Linked revision
+    pub rev: String,
+    /// Kind of the linked revision
+    pub kind: GitMetadataKind,
+    /// Problem of the revision
+    pub problem: RevisionProblem,
+    /// Commit the revision resolves to now, `None` if it is missing
+    pub commit: Option<String>,
+    /// Human readable description of the problem
+    pub message: String,
+}
+
+/// Result of validating the revisions of a debug flow
+#[derive(Serialize, ToSchema, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowValidation {
+    /// Number of nodes linked to a revision
+    pub checked: usize,
+    /// Revisions which need attention, in the order of the nodes
+    pub issues: Vec<RevisionIssue>,
+}
+
+impl FlowValidation {
+    /// Check if all revisions resolve to the commits they were linked to
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Validate the revisions linked to the nodes of a debug flow
+pub fn validate(data: &FlowData, repo: &git2_ox::Repository) -> FlowValidation {
+    let mut validation = FlowValidation::default();
+    for node in data.reactflow().nodes() {
+        let Some(git) = node.git() else {
+            continue;
+        };
+        validation.checked += 1;
+
+        let issue = |problem, commit: Option<&str>, message: String| RevisionIssue {
+            node_id: node.id.clone(),
+            rev: git.rev().to_string(),
+            kind: git.kind(),
+            problem,
+            commit: commit.map(str::to_string),
+            message,
+        };
+        let commit = match repo.get_commit_for_revision(git.rev()) {
+            Ok(commit) => commit.commit().clone(),
+            Err(e) => {
+                let message = match git.kind() {
+                    GitMetadataKind::Commit => format!("Commit '{}' does not exist", git.rev()),
+                    GitMetadataKind::Branch => format!("Branch '{}' was deleted", git.rev()),
+                    GitMetadataKind::Tag => format!("Tag '{}' was deleted", git.rev()),
+                };
+                log::debug!("Resolving '{}': {e}", git.rev());
+                validation
+                    .issues
+                    .push(issue(RevisionProblem::Missing, None, message));
+                continue;
+            }
+        };
+        let id = commit.id();
+
+        match git.kind() {
+            GitMetadataKind::Branch | GitMetadataKind::Tag
+                if !git.summary().is_empty() && commit.summary() != git.summary() =>
+            {
+                validation.issues.push(issue(
+                    RevisionProblem::Moved,
+                    Some(id),
+                    format!(
+                        "'{}' moved to {} \"{}\"",
+                        git.rev(),
+                        commit.short_id(),
+                        commit.summary()
+                    ),
+                ));
+            }
+            GitMetadataKind::Commit if !repo.is_reachable(id).unwrap_or(false) => {
+                validation.issues.push(issue(
+                    RevisionProblem::Unreachable,
+                    Some(id),
+                    format!(
+                        "Commit '{}' is not reachable from any branch or tag",
+                        git.rev()
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+    validation
+}
+
+/// Replace the revisions of all nodes by the full hashes of the commits they resolve to
+///
+/// Nodes with revisions which do not resolve are kept as they are. Returns the number of changed nodes.
+pub fn pin_revisions(data: &mut FlowData, repo: &git2_ox::Repository) -> usize {
+    let mut pinned = 0;
+    for node in &mut data.reactflow.nodes {
+        let Some(git) = node.git_mut() else {
+            continue;
+        };
+        let Ok(commit) = repo.get_commit_for_revision(git.rev()) else {
+            continue;
+        };
+        let commit = commit.commit();
+        let id = commit.id();
+        if git.kind() != GitMetadataKind::Commit || git.rev() != id {
+            *git = GitMetadata::new(id, commit.summary(), GitMetadataKind::Commit);
+            pinned += 1;
+        }
+    }
+    pinned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flow::FlowsDir;
+
+    fn git_node(id: &str, rev: &str, summary: &str, kind: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "type": "actionNode",
+            "position": {"x": 0, "y": 0},
+            "data": {"title": id, "description": "", "git": {"rev": rev, "summary": summary, "type": kind}}
+        })
+    }
+
+    #[test]
+    fn test_validate_and_pin() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = git2_ox::Repository::try_init(dir.path()).unwrap();
+        let git = repo.repo();
+        let mut config = git.config().unwrap();
+        config.set_str("user.name", "test").unwrap();
+        config.set_str("user.email", "test@example.com").unwrap();
+        let signature = git.signature().unwrap();
+        let tree = git
+            .find_tree(git.index().unwrap().write_tree().unwrap())
+            .unwrap();
+        let first = git
+            .commit(Some("HEAD"), &signature, &signature, "First", &tree, &[])
+            .unwrap();
+        let first = git.find_commit(first).unwrap();
+        let second = git
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Second",
+                &tree,
+                &[&first],
+            )
+            .unwrap();
+        // A commit no reference points to, e.g. the old version of a rebased commit
+        let orphan = git
+            .commit(None, &signature, &signature, "Orphan", &tree, &[&first])
+            .unwrap();
+        let branch = repo.current_branch_name().unwrap();
+
+        let flows_dir = FlowsDir::try_new(dir.path()).unwrap();
+        flows_dir.create_flow("Revisions", None, false).unwrap();
+        let mut data: FlowData = serde_json::from_value(serde_json::json!({
+            "name": "Revisions",
+            "reactflow": {"nodes": [
+                git_node("valid", &second.to_string(), "Second", "commit"),
+                git_node("moved", &branch, "First", "branch"),
+                git_node("deleted", "v1.0", "First", "tag"),
+                git_node("orphan", &orphan.to_string()[..10], "Orphan", "commit"),
+            ], "edges": []}
+        }))
+        .unwrap();
+        flows_dir.save_flow("revisions", &data, None).unwrap();
+
+        let validation = flows_dir.validate("revisions").unwrap();
+        assert_eq!(validation.checked, 4);
+        assert_eq!(
+            validation
+                .issues
+                .iter()
+                .map(|i| (i.node_id.as_str(), i.problem))
+                .collect::<Vec<_>>(),
+            [
+                ("moved", RevisionProblem::Moved),
+                ("deleted", RevisionProblem::Missing),
+                ("orphan", RevisionProblem::Unreachable),
+            ]
+        );
+        assert_eq!(
+            validation.issues[0].commit.as_deref(),
+            Some(second.to_string().as_str())
+        );
+
+        let (_, pinned) = flows_dir.pin_revisions("revisions").unwrap();
+        assert_eq!(pinned, 2);
+        data = flows_dir
+            .get_flow_by_id("revisions")
+            .unwrap()
+            .data()
+            .clone();
+        let git = data.reactflow().node("moved").unwrap().git().unwrap();
+        assert_eq!(git.rev(), second.to_string());
+        assert_eq!(git.kind(), GitMetadataKind::Commit);
+        assert_eq!(git.summary(), "Second");
+        assert_eq!(
+            data.reactflow()
+                .node("orphan")
+                .unwrap()
+                .git()
+                .unwrap()
+                .rev(),
+            orphan.to_string()
+        );
+        assert_eq!(
+            data.reactflow()
+                .node("deleted")
+                .unwrap()
+                .git()
+                .unwrap()
+                .rev(),
+            "v1.0"
+        );
+        // Pinning again changes nothing
+        assert_eq!(flows_dir.pin_revisions("revisions").unwrap().1, 0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod actors;
+pub mod cli;
 pub mod flow;
 mod fswatcher;
 pub mod utils;
@@ -1,4 +1,4 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -7,9 +7,12 @@ use std::path::PathBuf;
     about = "A tool for visualizing and debugging Git repository history as an interactive tree",
     long_about = "Debug Tree provides an interactive web interface for exploring Git repository history. \
                   It serves a local web server that visualizes commits, branches, and repository structure \
-                  in an easy-to-navigate tree format, perfect for debugging complex Git workflows."
+                  in an easy-to-navigate tree format, perfect for debugging complex Git workflows.",
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Path to a git repository
     #[arg(default_value = "./")]
     repo: PathBuf,
@@ -22,6 +25,22 @@ struct Cli {
     /// Do not open the website in the default browser
     #[arg(long, default_value_t = false)]
     no_browser: bool,
+    /// Record versions of stored debug flows as commits on `refs/debug-flow/history`
+    #[arg(long, default_value_t = false)]
+    history: bool,
+    /// Seconds without changes to a debug flow before a version of it is recorded
+    #[arg(long, default_value_t = 5, requires = "history")]
+    history_debounce: u64,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage debug flows without starting the server
+    Flows(debug_flow::cli::FlowsArgs),
+    /// Merge driver for debug flows, called by git as `debug-flow merge-driver %O %A %B`
+    MergeDriver(debug_flow::cli::MergeDriverArgs),
+    /// Register the merge driver for `.debug-flow/*.json` in the git config and `.gitattributes`
+    InstallMergeDriver(debug_flow::cli::InstallMergeDriverArgs),
 }
 
 #[tokio::main]
@@ -31,6 +50,28 @@ async fn main() {
         .init();
 
     let args = Cli::parse();
+    if let Some(command) = args.command {
+        let result = match command {
+            Command::Flows(flows_args) => debug_flow::cli::run_flows(flows_args),
+            Command::MergeDriver(merge_args) => {
+                debug_flow::cli::run_merge_driver(merge_args).map(|clean| {
+                    // Git keeps the file conflicted if the driver exits with a non-zero status
+                    if !clean {
+                        std::process::exit(1);
+                    }
+                })
+            }
+            Command::InstallMergeDriver(install_args) => {
+                debug_flow::cli::run_install_merge_driver(install_args)
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let flows_dir = debug_flow::flow::FlowsDir::try_new(args.repo)
         .expect("Error creating debug flow directory");
     let server = debug_flow::web::serve(
@@ -38,6 +79,8 @@ async fn main() {
         args.port,
         args.frontend_proxy_port,
         flows_dir,
+        args.history
+            .then(|| std::time::Duration::from_secs(args.history_debounce)),
         || {
             if !args.no_browser {
                 let url = format!("http://localhost:{}", args.port);
@@ -1,5 +1,161 @@
-pub fn to_kebab_case(s: &str) -> String {
-    s.to_lowercase().replace(' ', "-").replace("_", "-")
+use std::{ffi, fs, io, io::Write, path, sync};
+
+/// Maximum length of a slug in bytes, this keeps file names well below the limits of common file systems
+pub const MAX_SLUG_LENGTH: usize = 64;
+
+/// Slug used if a name does not contain a single character that can be kept
+const EMPTY_SLUG: &str = "untitled";
+
+/// Transliteration of common lowercase non-ASCII Latin letters
+fn transliterate(c: char) -> Option<&'static str> {
+    let s = match c {
+        'à' | 'á' | 'â' | 'ã' | 'å' | 'ā' | 'ă' | 'ą' => "a",
+        'ä' | 'æ' => "ae",
+        'ç' | 'ć' | 'č' => "c",
+        'ď' | 'đ' | 'ð' => "d",
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => "e",
+        'ğ' => "g",
+        'ì' | 'í' | 'î' | 'ï' | 'ī' | 'ı' => "i",
+        'ł' | 'ľ' => "l",
+        'ñ' | 'ń' | 'ň' => "n",
+        'ò' | 'ó' | 'ô' | 'õ' | 'ø' | 'ō' | 'ő' => "o",
+        'ö' | 'œ' => "oe",
+        'ř' => "r",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'ť' | 'ţ' => "t",
+        'þ' => "th",
+        'ù' | 'ú' | 'û' | 'ū' | 'ů' | 'ű' => "u",
+        'ü' => "ue",
+        'ý' | 'ÿ' => "y",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => return None,
+    };
+    Some(s)
+}
+
+/// Convert a name to a slug which is safe to use as a file name and in URLs
+///
+/// ASCII letters and digits are lowercased, common Latin letters with diacritics are transliterated and all
+/// other letters and digits are encoded as `_` followed by their hexadecimal code point. Everything else,
+/// including path separators and dots, separates words which are joined with a single `-`. The slug is
+/// truncated to [`MAX_SLUG_LENGTH`] bytes without splitting words into invalid parts.
+pub fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    let mut separate = false;
+
+    for c in name.chars().flat_map(char::to_lowercase) {
+        let part = if c.is_ascii_alphanumeric() {
+            c.encode_utf8(&mut [0; 4]).to_string()
+        } else if let Some(s) = transliterate(c) {
+            s.to_string()
+        } else if c.is_alphanumeric() {
+            format!("_{:x}", c as u32)
+        } else {
+            separate = true;
+            continue;
+        };
+
+        let separator = if separate && !slug.is_empty() {
+            "-"
+        } else {
+            ""
+        };
+        if slug.len() + separator.len() + part.len() > MAX_SLUG_LENGTH {
+            break;
+        }
+        slug.push_str(separator);
+        slug.push_str(&part);
+        separate = false;
+    }
+
+    if slug.is_empty() {
+        return EMPTY_SLUG.to_string();
+    }
+    slug
+}
+
+/// Check if `id` can be used as a file name inside of a directory without escaping it
+///
+/// This is less strict than the output of [`slugify`] to keep files created by older releases accessible.
+pub fn is_safe_file_stem(id: &str) -> bool {
+    !id.is_empty()
+        && !id.starts_with('.')
+        && !id
+            .chars()
+            .any(|c| matches!(c, '/' | '\\' | ':' | '\0') || c.is_control())
+}
+
+/// Encode an ID as a single component of a file path or a Git reference name
+///
+/// Only ASCII alphanumerics, `-` and `_` are kept, all other bytes are percent-encoded. This rules out
+/// everything Git does not allow in reference names, e.g. `..`, `~`, `:` or a trailing `.lock`, and
+/// everything that would escape a directory.
+pub fn encode_component(id: &str) -> String {
+    let mut component = String::new();
+    for byte in id.bytes() {
+        match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => component.push(byte as char),
+            _ => component.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    component
+}
+
+/// Decode a component encoded with [`encode_component`], `None` if it is not a valid encoding
+pub fn decode_component(component: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(component.len());
+    let mut iter = component.bytes();
+    while let Some(byte) = iter.next() {
+        match byte {
+            b'%' => {
+                let hex = [iter.next()?, iter.next()?];
+                bytes.push(u8::from_str_radix(std::str::from_utf8(&hex).ok()?, 16).ok()?);
+            }
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => bytes.push(byte),
+            _ => return None,
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Atomically replace the file at `path` with `content`
+///
+/// The content is written to a temporary file next to `path` which is synced to disk and then renamed
+/// to `path`. Readers either see the old or the new content, never a partially written file.
+pub fn write_file_atomic(path: &path::Path, content: &[u8]) -> io::Result<()> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => path::Path::new("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Path has no file name"))?;
+
+    // Concurrent writes of the same file within one process use different temporary files
+    static TMP_COUNTER: sync::atomic::AtomicU64 = sync::atomic::AtomicU64::new(0);
+    let counter = TMP_COUNTER.fetch_add(1, sync::atomic::Ordering::Relaxed);
+    let mut tmp_name = ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".{}.{counter}.tmp", std::process::id()));
+    let tmp_path = dir.join(tmp_name);
+
+    let result = (|| {
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+        return result;
+    }
+
+    // Persist the rename itself, this is only possible on platforms which allow opening directories
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+    Ok(())
 }
 
 #[cfg(test)]
@@ -7,10 +163,92 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_to_kebab_case() {
-        assert_eq!(to_kebab_case("Hello World"), "hello-world");
-        assert_eq!(to_kebab_case("hello_world"), "hello-world");
-        assert_eq!(to_kebab_case("Rust Programming"), "rust-programming");
-        assert_eq!(to_kebab_case("Debug Tree CLI"), "debug-tree-cli");
+    fn test_write_file_atomic_concurrently() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flow.json");
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let path = &path;
+                scope.spawn(move || {
+                    for _ in 0..20 {
+                        write_file_atomic(path, format!("content {i}").as_bytes()).unwrap();
+                    }
+                });
+            }
+        });
+        assert!(fs::read_to_string(&path).unwrap().starts_with("content "));
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_encode_component() {
+        assert_eq!(encode_component("dndnode_1"), "dndnode_1");
+        assert_eq!(encode_component("v1.2-legacy"), "v1%2E2-legacy");
+        assert_eq!(encode_component("a/../b:c"), "a%2F%2E%2E%2Fb%3Ac");
+        assert_eq!(encode_component("ä"), "%C3%A4");
+    }
+
+    #[test]
+    fn test_decode_component() {
+        for id in ["dndnode_1", "v1.2-legacy", "a/../b:c", "ä", ""] {
+            assert_eq!(decode_component(&encode_component(id)).as_deref(), Some(id));
+        }
+        assert_eq!(decode_component("a.b"), None);
+        assert_eq!(decode_component("a%2"), None);
+        assert_eq!(decode_component("%ZZ"), None);
+        assert_eq!(decode_component("%FF"), None);
+    }
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(slugify("Hello World"), "hello-world");
+        assert_eq!(slugify("hello_world"), "hello-world");
+        assert_eq!(slugify("Rust Programming"), "rust-programming");
+        assert_eq!(slugify("Debug Tree CLI"), "debug-tree-cli");
+        assert_eq!(slugify("  Crash -- on   start! "), "crash-on-start");
+        assert_eq!(slugify("v1.2 regression"), "v1-2-regression");
+    }
+
+    #[test]
+    fn test_slugify_path_traversal() {
+        assert_eq!(slugify("../../etc"), "etc");
+        assert_eq!(slugify("..\\..\\windows\\system32"), "windows-system32");
+        assert_eq!(slugify("/etc/passwd"), "etc-passwd");
+        assert_eq!(slugify(".."), "untitled");
+        assert_eq!(slugify(""), "untitled");
+        for name in ["../../etc", "..", "a/../../b", "C:\\temp", ".hidden"] {
+            assert!(is_safe_file_stem(&slugify(name)), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_slugify_unicode() {
+        assert_eq!(slugify("Über Größe"), "ueber-groesse");
+        assert_eq!(slugify("Crème brûlée"), "creme-brulee");
+        assert_eq!(slugify("中文"), "_4e2d_6587");
+        assert_eq!(slugify("Bug 🐛"), "bug");
+    }
+
+    #[test]
+    fn test_slugify_max_length() {
+        let slug = slugify(&"a".repeat(100));
+        assert_eq!(slug.len(), MAX_SLUG_LENGTH);
+
+        // Encoded characters are never split
+        let slug = slugify(&"中".repeat(20));
+        assert!(slug.len() <= MAX_SLUG_LENGTH);
+        assert_eq!(slug.len() % 5, 0);
+    }
+
+    #[test]
+    fn test_is_safe_file_stem() {
+        assert!(is_safe_file_stem("crash-on-start"));
+        assert!(is_safe_file_stem("v1.2-legacy"));
+        assert!(!is_safe_file_stem(""));
+        assert!(!is_safe_file_stem(".."));
+        assert!(!is_safe_file_stem("../etc"));
+        assert!(!is_safe_file_stem("a/b"));
+        assert!(!is_safe_file_stem("a\\b"));
     }
 }
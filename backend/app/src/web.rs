@@ -36,19 +36,47 @@ struct AppState {
     flows_dir: flow::FlowsDir,
     git_actor: actors::git::GitActorAddr,
     git_status_tx: broadcast::Sender<git2_ox::Status>,
+    flow_revisions: flow::FlowRevisions,
+    flow_events_tx: broadcast::Sender<flow::FlowEvent>,
+    search_index: flow::SearchIndex,
+    flow_history: Option<flow::history::FlowHistory>,
+    command_runner: actors::runner::CommandRunnerAddr,
+    active_runs: actors::runner::ActiveRuns,
+    run_events_tx: broadcast::Sender<actors::runner::RunEvent>,
+    bisect_sessions: flow::bisect::BisectSessions,
 }
 
 impl AppState {
-    pub fn try_new(flows_dir: flow::FlowsDir) -> Result<Self, Box<dyn std::error::Error>> {
+    pub fn try_new(
+        flows_dir: flow::FlowsDir,
+        history_debounce: Option<std::time::Duration>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
         let repo = flows_dir.git_repo();
         let git_actor = crate::actors::git::GitActor::try_from_path(repo)?.spawn();
 
         let (tx, _rx) = broadcast::channel(16);
+        let flow_revisions = flows_dir.revisions()?;
+        let (flow_events_tx, _rx) = broadcast::channel(16);
+        let search_index = flow::SearchIndex::from_flows(flows_dir.flows()?);
+        let flow_history = history_debounce
+            .map(|debounce| flow::history::FlowHistory::spawn(flows_dir.clone(), debounce));
+        // Every line of output is an event, the channel buffers bursts of output
+        let (run_events_tx, _rx) = broadcast::channel(256);
+        let command_runner =
+            actors::runner::CommandRunner::new(flows_dir.clone(), run_events_tx.clone()).spawn();
 
         Ok(Self {
             flows_dir,
             git_actor,
             git_status_tx: tx,
+            flow_revisions,
+            flow_events_tx,
+            search_index,
+            flow_history,
+            command_runner,
+            active_runs: actors::runner::ActiveRuns::new(),
+            run_events_tx,
+            bisect_sessions: flow::bisect::BisectSessions::new(),
         })
     }
 
@@ -64,6 +92,67 @@ impl AppState {
     pub fn git_status_tx(&self) -> &broadcast::Sender<git2_ox::Status> {
         &self.git_status_tx
     }
+
+    /// Sender for the broadcast channel sending debug flow changes
+    pub fn flow_events_tx(&self) -> &broadcast::Sender<flow::FlowEvent> {
+        &self.flow_events_tx
+    }
+
+    pub fn command_runner(&self) -> &actors::runner::CommandRunnerAddr {
+        &self.command_runner
+    }
+
+    /// Nodes with a command running in their worktree
+    pub fn active_runs(&self) -> &actors::runner::ActiveRuns {
+        &self.active_runs
+    }
+
+    /// Sender for the broadcast channel sending the progress of command runs
+    pub fn run_events_tx(&self) -> &broadcast::Sender<actors::runner::RunEvent> {
+        &self.run_events_tx
+    }
+
+    /// Running bisections of the debug flows
+    pub fn bisect_sessions(&self) -> &flow::bisect::BisectSessions {
+        &self.bisect_sessions
+    }
+
+    /// Search index over all debug flows
+    pub fn search_index(&self) -> &flow::SearchIndex {
+        &self.search_index
+    }
+
+    /// Record a version of a debug flow in the flow history if recording the history is enabled
+    pub fn record_flow_history(&self, id: &str) {
+        if let Some(history) = &self.flow_history {
+            history.record(id);
+        }
+    }
+
+    /// Record the current revision of a debug flow and notify subscribers if it changed
+    ///
+    /// The search index is refreshed as well if the debug flow changed.
+    ///
+    /// * `id` - ID of the debug flow
+    /// * `revision` - Current revision of the debug flow, `None` if the debug flow was deleted
+    pub fn publish_flow_revision(&self, id: &str, revision: Option<String>) {
+        let Some(event) = self.flow_revisions.update(id, revision) else {
+            return;
+        };
+        match event.revision() {
+            Some(_) => match self.flows_dir.get_flow_by_id(id) {
+                Ok(flow) => self.search_index.update(&flow),
+                Err(e) => log::warn!("Failed to index debug flow '{id}': {e}"),
+            },
+            None => self.search_index.remove(id),
+        }
+        let num_rx = self.flow_events_tx.receiver_count();
+        match self.flow_events_tx.send(event) {
+            Ok(_) => log::debug!("Sent flow event to channel ({num_rx})"),
+            // Sending only fails if there are no subscribers
+            Err(e) => log::debug!("No subscribers for flow event: {:?}", e.0),
+        }
+    }
 }
 
 /// Serves the web application, including the API and frontend.
@@ -81,6 +170,8 @@ impl AppState {
 /// * `port` - The port to bind to.
 /// * `frontend_proxy_port` - The port of the frontend development server (only used in debug builds).
 /// * `flows_dir` - The `FlowsDir` instance, providing access to debug flow data.
+/// * `history_debounce` - Record versions of stored debug flows in the flow history, a version is recorded once
+///   the debug flow was not stored for this duration. `None` disables the flow history.
 /// * `on_bind` - A closure that is called once the server successfully binds to the address.
 ///
 /// # Returns
@@ -98,12 +189,13 @@ pub async fn serve<F>(
     port: u16,
     frontend_proxy_port: u16,
     flows_dir: crate::flow::FlowsDir,
+    history_debounce: Option<std::time::Duration>,
     on_bind: F,
 ) -> Result<(), Box<dyn std::error::Error>>
 where
     F: Fn(),
 {
-    let app_state = AppState::try_new(flows_dir)?;
+    let app_state = AppState::try_new(flows_dir, history_debounce)?;
 
     let repo_root = app_state.flows_dir().git_repo().to_path_buf();
 
@@ -141,7 +233,31 @@ where
         loop {
             let res = fs_rx.recv();
             match res {
-                Ok(_) => {
+                Ok(events) => {
+                    let flows_dir = app_state.flows_dir();
+                    // Commands run in the worktrees of nodes change neither debug flows nor the Git status
+                    let events: Vec<_> = events
+                        .into_iter()
+                        .filter(|ev| !flows_dir.is_in_worktrees(&ev.path))
+                        .collect();
+                    if events.is_empty() {
+                        continue;
+                    }
+
+                    // Publish changes of debug flows made outside of the API, e.g. by `git pull`
+                    let mut flow_ids: Vec<String> = events
+                        .iter()
+                        .filter_map(|ev| flows_dir.flow_id_from_path(&ev.path))
+                        .collect();
+                    flow_ids.sort();
+                    flow_ids.dedup();
+                    for id in flow_ids {
+                        match flows_dir.revision_by_id(&id) {
+                            Ok(revision) => app_state.publish_flow_revision(&id, revision),
+                            Err(e) => log::error!("Getting revision of flow '{id}': {e}"),
+                        }
+                    }
+
                     // We get multiple events but don't care at all, we only need to get one Git status
                     let actor = app_state.git_actor();
                     let msg = actors::git::GetRepositoryStatus;
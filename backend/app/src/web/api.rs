@@ -1,4 +1,4 @@
-use crate::web;
+use crate::{actors, flow, web};
 use axum::response::{IntoResponse, Response};
 use axum::{http, routing};
 use serde::Serialize;
@@ -15,6 +15,14 @@ pub enum AppError {
     InternalServerError(String),
     #[error("{0}")]
     BadRequest(String),
+    #[error("{0}")]
+    Conflict(String),
+    /// Conflict caused by the listed items, e.g. files blocking a checkout
+    #[error("{message}")]
+    ConflictWithDetails {
+        message: String,
+        details: Vec<String>,
+    },
     #[error("JSON Deserialization Error")]
     JsonExtractionError(#[from] axum::extract::rejection::JsonRejection), // Handle Axum's JSON parsing errors
 }
@@ -26,6 +34,9 @@ impl AppError {
             AppError::NotFound(_) => http::StatusCode::NOT_FOUND,
             AppError::InternalServerError(_) => http::StatusCode::INTERNAL_SERVER_ERROR,
             AppError::BadRequest(_) => http::StatusCode::BAD_REQUEST,
+            AppError::Conflict(_) | AppError::ConflictWithDetails { .. } => {
+                http::StatusCode::CONFLICT
+            }
             AppError::JsonExtractionError(rejection) => rejection.status(),
         }
     }
@@ -38,6 +49,14 @@ impl AppError {
             .to_string()
     }
 
+    /// Get the items which caused the error
+    pub fn details(&self) -> Option<Vec<String>> {
+        match self {
+            Self::ConflictWithDetails { details, .. } => Some(details.clone()),
+            _ => None,
+        }
+    }
+
     pub fn message(self) -> String {
         match self {
             Self::JsonExtractionError(rejection) => rejection.body_text(),
@@ -59,6 +78,63 @@ impl From<git2_ox::error::Error> for AppError {
     fn from(error: git2_ox::error::Error) -> Self {
         match error {
             git2_ox::error::Error::NotFound(_) => AppError::NotFound(error.to_string()),
+            git2_ox::error::Error::Invalid(_) => AppError::BadRequest(error.to_string()),
+            git2_ox::error::Error::Conflict(_) => AppError::Conflict(error.to_string()),
+            git2_ox::error::Error::CheckoutConflict { ref paths, .. }
+            | git2_ox::error::Error::StashConflict { ref paths, .. } => {
+                AppError::ConflictWithDetails {
+                    details: paths.clone(),
+                    message: error.to_string(),
+                }
+            }
+            // The status is determined by the error of the checkout, the message names the stash
+            git2_ox::error::Error::StashNotRestored {
+                error: ref checkout_error,
+                ..
+            } => match checkout_error.as_ref() {
+                git2_ox::error::Error::CheckoutConflict { paths, .. } => {
+                    AppError::ConflictWithDetails {
+                        details: paths.clone(),
+                        message: error.to_string(),
+                    }
+                }
+                _ => AppError::InternalServerError(error.to_string()),
+            },
+            _ => AppError::InternalServerError(error.to_string()),
+        }
+    }
+}
+
+impl From<flow::Error> for AppError {
+    fn from(error: flow::Error) -> Self {
+        match error {
+            flow::Error::Io(path, io_err) if io_err.kind() == std::io::ErrorKind::NotFound => {
+                // Only the file name is reported, the location on the server is not exposed
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                AppError::NotFound(format!("'{name}' not found"))
+            }
+            flow::Error::DebugFlowExistsAlready { .. } | flow::Error::RevisionConflict { .. } => {
+                AppError::Conflict(error.to_string())
+            }
+            flow::Error::InvalidFlowId(_) | flow::Error::NoRevision(_) => {
+                AppError::BadRequest(error.to_string())
+            }
+            flow::Error::NodeNotFound(_) => AppError::NotFound(error.to_string()),
+            flow::Error::NoBisection(_) => AppError::NotFound(error.to_string()),
+            flow::Error::BisectionFinished(_)
+            | flow::Error::BisectionRunning(_)
+            | flow::Error::NotBisectionNode { .. } => AppError::Conflict(error.to_string()),
+            flow::Error::Git(e) => e.into(),
+            _ => AppError::InternalServerError(error.to_string()),
+        }
+    }
+}
+
+impl From<actors::runner::Error> for AppError {
+    fn from(error: actors::runner::Error) -> Self {
+        match error {
+            actors::runner::Error::Flow(e) => e.into(),
+            actors::runner::Error::Git(e) => e.into(),
             _ => AppError::InternalServerError(error.to_string()),
         }
     }
@@ -108,8 +184,8 @@ impl From<AppError> for ApiStatusDetailResponse {
         ApiStatusDetailResponse {
             status: app_error.code().as_u16(),
             reason: app_error.reason(),
+            details: app_error.details(),
             message: app_error.message(),
-            details: None,
         }
     }
 }
@@ -134,6 +210,7 @@ pub mod v1 {
 
     use crate::web;
 
+    mod bisect;
     mod flows;
     mod git;
 
@@ -141,6 +218,7 @@ pub mod v1 {
     #[derive(utoipa::OpenApi)]
     #[openapi(nest(
         (path = "/flows", api = flows::ApiDoc),
+        (path = "/flows", api = bisect::ApiDoc),
         (path = "/git", api = git::ApiDoc)
     ))]
     pub(super) struct ApiDoc;
@@ -149,6 +227,7 @@ pub mod v1 {
     pub(super) fn router() -> routing::Router<web::AppState> {
         routing::Router::new()
             .merge(flows::router())
+            .merge(bisect::router())
             .nest("/git", git::router())
     }
 }
@@ -0,0 +1,278 @@
+use axum::extract::{Path, State};
+use axum::{Json, http, routing};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    actors,
+    flow::{self, bisect::BisectSession},
+    web::{self, api},
+};
+
+pub fn router() -> routing::Router<web::AppState> {
+    routing::Router::new()
+        .route(
+            "/flows/{id}/bisect",
+            routing::get(get_bisect)
+                .post(start_bisect)
+                .delete(reset_bisect),
+        )
+        .route("/flows/{id}/bisect/mark", routing::post(mark_bisect))
+        .route("/flows/{id}/bisect/run", routing::post(run_bisect))
+}
+
+/// API documentation for the bisect endpoints.
+#[derive(utoipa::OpenApi)]
+#[openapi(paths(get_bisect, start_bisect, mark_bisect, reset_bisect, run_bisect), tags((name = "Bisect", description="Guided bisections building a debug flow")) )]
+pub(super) struct ApiDoc;
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct BisectResponse {
+    bisect: BisectSession,
+    /// Revision of the debug flow with the nodes added by the bisection
+    revision: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/bisect",
+    summary = "Get bisection",
+    description = "Get the marked commits and the next step of the running bisection of the debug flow",
+    responses(
+        (status = http::StatusCode::OK, description = "Running bisection", body = BisectSession),
+        (status = http::StatusCode::NOT_FOUND, description = "No bisection is running", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn get_bisect(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> api::Result<BisectSession> {
+    let session = app_state
+        .bisect_sessions()
+        .get(&id)
+        .ok_or(flow::Error::NoBisection(id))?;
+    Ok(Json(session))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct StartBisectRequest {
+    /// Revision which has the problem
+    bad: String,
+    /// Revisions without the problem
+    good: Vec<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/bisect",
+    summary = "Start bisection",
+    description = "Start a bisection to find the first commit between the good revisions and the bad revision \
+    which has the problem. The commit to test next is added as a status node in progress, connected to the root \
+    node. A running bisection of the debug flow is replaced, the nodes it added are kept.",
+    request_body = StartBisectRequest,
+    responses(
+        (status = http::StatusCode::OK, description = "Bisection is started", body = BisectResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "No good revision or the bad revision is an ancestor of a good revision", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Debug flow or revision not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn start_bisect(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<StartBisectRequest>,
+) -> api::Result<BisectResponse> {
+    let (bisect, revision) = app_state.bisect_sessions().start(
+        app_state.flows_dir(),
+        &id,
+        &request.bad,
+        &request.good,
+    )?;
+    app_state.publish_flow_revision(&id, Some(revision.clone()));
+    app_state.record_flow_history(&id);
+    Ok(Json(BisectResponse { bisect, revision }))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct MarkBisectRequest {
+    /// Verdict on the commit to test
+    verdict: git2_ox::BisectVerdict,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/bisect/mark",
+    summary = "Mark bisection commit",
+    description = "Mark the commit to test as good, bad or skipped. The state of its node is set to success, fail \
+    or unknown and the next commit to test is added as a status node. Once the first bad commit is found, it is \
+    added as a failed status node and the bisection is finished.",
+    request_body = MarkBisectRequest,
+    responses(
+        (status = http::StatusCode::OK, description = "Commit is marked", body = BisectResponse),
+        (status = http::StatusCode::CONFLICT, description = "The bisection is finished or a command is testing its commits", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "No bisection is running", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn mark_bisect(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<MarkBisectRequest>,
+) -> api::Result<BisectResponse> {
+    let (bisect, revision) =
+        app_state
+            .bisect_sessions()
+            .mark(app_state.flows_dir(), &id, request.verdict)?;
+    app_state.publish_flow_revision(&id, Some(revision.clone()));
+    app_state.record_flow_history(&id);
+    Ok(Json(BisectResponse { bisect, revision }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/{id}/bisect",
+    summary = "Reset bisection",
+    description = "End the running bisection of the debug flow, the nodes it added are kept",
+    responses(
+        (status = http::StatusCode::OK, description = "Bisection is ended", body = api::ApiStatusResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "No bisection is running", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn reset_bisect(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> api::Result<api::ApiStatusResponse> {
+    app_state
+        .bisect_sessions()
+        .remove(&id)
+        .ok_or(flow::Error::NoBisection(id))?;
+    Ok(Json(http::StatusCode::OK.into()))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RunBisectRequest {
+    /// Shell command testing a commit, it is run with `sh -c` in the root of the worktree
+    command: String,
+    /// A test is killed if it runs longer, defaults to 300 seconds
+    timeout_secs: Option<u64>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/bisect/run",
+    summary = "Run bisection",
+    description = "Bisect automatically by running a test command for every commit to test, like `git bisect \
+    run`. The exit code 0 marks the commit as good, 125 skips it and the other codes up to 127 mark it as bad. The \
+    bisection stops if a test times out or is terminated. Every test is a command run of the node of the commit, \
+    its output is streamed as `run` events on `/{id}/runs/stream`, the worktree it ran in is removed afterwards. \
+    Commits cannot be marked manually while the bisection is running.",
+    request_body = RunBisectRequest,
+    responses(
+        (status = http::StatusCode::ACCEPTED, description = "Bisection is running", body = api::ApiStatusResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Empty command", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::CONFLICT, description = "The bisection is finished or a command is already testing its commits", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "No bisection is running", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn run_bisect(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<RunBisectRequest>,
+) -> Result<(http::StatusCode, Json<api::ApiStatusResponse>), api::AppError> {
+    if request.command.trim().is_empty() {
+        return Err(api::AppError::BadRequest("Command is empty".to_string()));
+    }
+    let bisect_run = app_state.bisect_sessions().start_run(&id)?;
+
+    let timeout = request
+        .timeout_secs
+        .map(std::time::Duration::from_secs)
+        .unwrap_or(actors::runner::DEFAULT_TIMEOUT);
+    tokio::spawn(run_bisect_task(
+        app_state,
+        id,
+        bisect_run,
+        request.command,
+        timeout,
+    ));
+    Ok((
+        http::StatusCode::ACCEPTED,
+        Json(http::StatusCode::ACCEPTED.into()),
+    ))
+}
+
+/// Test the commits of the bisection of a debug flow until it is finished or a test gives no verdict
+async fn run_bisect_task(
+    app_state: web::AppState,
+    id: String,
+    bisect_run: u64,
+    command: String,
+    timeout: std::time::Duration,
+) {
+    while let Some(node_id) = app_state.bisect_sessions().next_run_node(&id, bisect_run) {
+        let msg = actors::runner::RunCommand {
+            run_id: actors::runner::next_run_id(),
+            flow_id: id.clone(),
+            node_id: node_id.clone(),
+            command: command.clone(),
+            timeout,
+            active: app_state.active_runs().start(&id, &node_id),
+        };
+        let run = app_state.command_runner().call(msg).await;
+        // Every step tests another commit, the worktree is not needed once the result is attached to the node
+        prune_node_worktree(&app_state, &id, &node_id).await;
+        let run = match run {
+            Ok(Ok(run)) => run,
+            Ok(Err(e)) => {
+                log::warn!("Stopping bisection of debug flow '{id}': {e}");
+                break;
+            }
+            Err(e) => {
+                log::error!("Error calling command runner: {e}");
+                break;
+            }
+        };
+        app_state.publish_flow_revision(&id, Some(run.revision));
+
+        let Some(verdict) = flow::bisect::verdict(&run.result) else {
+            log::warn!(
+                "Stopping bisection of debug flow '{id}', '{command}' timed out or was terminated"
+            );
+            break;
+        };
+        match app_state.bisect_sessions().mark_run(
+            app_state.flows_dir(),
+            &id,
+            bisect_run,
+            &node_id,
+            verdict,
+        ) {
+            Ok((_, revision)) => app_state.publish_flow_revision(&id, Some(revision)),
+            Err(e) => {
+                log::warn!("Stopping bisection of debug flow '{id}': {e}");
+                break;
+            }
+        }
+    }
+    app_state.bisect_sessions().finish_run(&id, bisect_run);
+    app_state.record_flow_history(&id);
+}
+
+/// Remove the linked worktree of a node unless another command is queued or running in it
+async fn prune_node_worktree(app_state: &web::AppState, id: &str, node_id: &str) {
+    if app_state.active_runs().contains(id, node_id) {
+        return;
+    }
+    let msg = actors::git::PruneWorktree {
+        name: flow::FlowsDir::worktree_name(id, node_id),
+    };
+    match app_state.git_actor().call(msg).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::warn!("Failed to prune the worktree of node '{node_id}': {e}"),
+        Err(e) => log::error!("Error calling git actor: {e}"),
+    }
+}
@@ -1,27 +1,58 @@
 use std::io;
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
+use axum::response::{self, IntoResponse, Response};
 use axum::{Json, http, routing};
+use futures_util::stream::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::{
-    flow,
+    actors, flow,
     web::{self, api},
 };
 
 pub fn router() -> routing::Router<web::AppState> {
     routing::Router::new()
         .route("/flows", routing::get(list_flows).post(create_flow))
+        .route("/flows/search", routing::get(search_flows))
+        .route("/flows/templates", routing::get(list_templates))
         .route(
             "/flows/{id}",
-            routing::get(get_flow).delete(delete_flow).post(store_flow),
+            routing::get(get_flow)
+                .delete(delete_flow)
+                .post(store_flow)
+                .patch(rename_flow),
         )
+        .route("/flows/{id}/duplicate", routing::post(duplicate_flow))
+        .route("/flows/{id}/history", routing::get(list_flow_versions))
+        .route(
+            "/flows/{id}/history/{version}",
+            routing::get(get_flow_version),
+        )
+        .route("/flows/{id}/stream", routing::any(flow_sse_handler))
+        .route("/flows/{id}/export", routing::get(export_flow))
+        .route("/flows/{id}/diff", routing::get(diff_flow))
+        .route("/flows/{id}/validate", routing::get(validate_flow))
+        .route("/flows/{id}/pin", routing::post(pin_flow_revisions))
+        .route(
+            "/flows/{id}/nodes/{node_id}/run",
+            routing::post(run_node_command),
+        )
+        .route(
+            "/flows/{id}/nodes/{node_id}/checkout",
+            routing::post(checkout_node_revision),
+        )
+        .route(
+            "/flows/{id}/nodes/{node_id}/stash/restore",
+            routing::post(restore_node_stash),
+        )
+        .route("/flows/{id}/runs/stream", routing::any(run_sse_handler))
 }
 
 /// API documentation for the flows endpoints.
 #[derive(utoipa::OpenApi)]
-#[openapi(paths(list_flows, create_flow, get_flow, delete_flow, store_flow), tags((name = "Debug Flow Management", description="Debug Flow related endpoints")) )]
+#[openapi(paths(list_flows, search_flows, list_templates, create_flow, get_flow, delete_flow, store_flow, rename_flow, duplicate_flow, list_flow_versions, get_flow_version, export_flow, diff_flow, validate_flow, pin_flow_revisions, run_node_command, checkout_node_revision, restore_node_stash), tags((name = "Debug Flow Management", description="Debug Flow related endpoints")) )]
 pub(super) struct ApiDoc;
 
 #[derive(Serialize, ToSchema)]
@@ -51,42 +82,110 @@ where
     get,
     path = "",
     summary="List debug flows",
-    description = "List all debug flows",
+    description = "List all debug flows, the most recently changed debug flow comes first",
     responses(
         (status = http::StatusCode::OK, description = "List debug flows", body = ListFlowsResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
     )
 )]
 async fn list_flows(State(app_state): State<web::AppState>) -> api::Result<ListFlowsResponse> {
-    let flows = app_state
+    let mut flows: Vec<flow::FlowMetadata> = app_state
         .flows_dir()
         .metadatas()
-        .map_err(|e| api::AppError::InternalServerError(e.to_string()))?;
-    Ok(Json(flows.collect::<ListFlowsResponse>()))
+        .map_err(|e| api::AppError::InternalServerError(e.to_string()))?
+        .collect();
+    flows.sort_by(|a, b| b.last_modified_date().cmp(a.last_modified_date()));
+    Ok(Json(flows.into_iter().collect::<ListFlowsResponse>()))
+}
+
+/// Default number of matches returned by a search
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct SearchFlowsQuery {
+    /// Search terms separated by whitespace, all terms must occur in a text for it to match
+    q: String,
+    /// Maximum number of matches
+    limit: Option<usize>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct SearchFlowsResponse {
+    matches: Vec<flow::search::SearchMatch>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/search",
+    summary = "Search debug flows",
+    description = "Search the names of all debug flows and the titles, descriptions and linked Git revisions of \
+    their nodes. Searching ignores case, every match contains a snippet of the matching text with the character \
+    ranges of the search terms.",
+    params(SearchFlowsQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "Matches of the search", body = SearchFlowsResponse),
+    )
+)]
+async fn search_flows(
+    State(app_state): State<web::AppState>,
+    Query(query): Query<SearchFlowsQuery>,
+) -> api::Result<SearchFlowsResponse> {
+    let matches = app_state
+        .search_index()
+        .search(&query.q, query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT));
+    Ok(Json(SearchFlowsResponse { matches }))
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ListTemplatesResponse {
+    templates: Vec<flow::template::TemplateMetadata>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/templates",
+    summary = "List templates",
+    description = "List the templates in `.debug-flow/templates/` a debug flow can be created from",
+    responses(
+        (status = http::StatusCode::OK, description = "Available templates", body = ListTemplatesResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn list_templates(
+    State(app_state): State<web::AppState>,
+) -> api::Result<ListTemplatesResponse> {
+    let templates = app_state.flows_dir().templates()?;
+    Ok(Json(ListTemplatesResponse { templates }))
 }
 
 #[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CreateFlowRequest {
     name: String,
+    /// ID of the template to seed the nodes and edges from
+    template: Option<String>,
 }
 
 #[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct CreateFlowResponse {
     flow: flow::FlowMetadata,
-}
-
-impl CreateFlowResponse {
-    pub fn new(flow: flow::FlowMetadata) -> Self {
-        Self { flow }
-    }
+    /// Revision of the stored debug flow, send it when storing changes of the debug flow
+    #[schema(nullable = false)]
+    revision: Option<String>,
 }
 
 impl TryFrom<flow::Flow> for CreateFlowResponse {
     type Error = flow::Error;
     fn try_from(flow: flow::Flow) -> Result<Self, flow::Error> {
-        Ok(Self::new(flow.try_into()?))
+        let revision = flow.revision().map(str::to_string);
+        Ok(Self {
+            flow: flow.try_into()?,
+            revision,
+        })
     }
 }
 
@@ -97,6 +196,8 @@ impl TryFrom<flow::Flow> for CreateFlowResponse {
     description = "Create debug flow",
     responses(
         (status = http::StatusCode::OK, description = "Debug Flow created", body = CreateFlowResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Template not found", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::CONFLICT, description = "Debug flow exists already", body = api::ApiStatusDetailResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
     )
 )]
@@ -104,10 +205,13 @@ async fn create_flow(
     State(app_state): State<web::AppState>,
     Json(new_flow): Json<CreateFlowRequest>,
 ) -> api::Result<CreateFlowResponse> {
-    let resp: CreateFlowResponse = app_state
-        .flows_dir()
-        .create_flow(&new_flow.name, false)
-        .map_err(|e| api::AppError::InternalServerError(e.to_string()))?
+    let flow =
+        app_state
+            .flows_dir()
+            .create_flow(&new_flow.name, new_flow.template.as_deref(), false)?;
+    app_state.publish_flow_revision(&flow.id(), flow.revision().map(str::to_string));
+
+    let resp: CreateFlowResponse = flow
         .try_into()
         .map_err(|e: flow::Error| api::AppError::InternalServerError(e.to_string()))?;
 
@@ -118,11 +222,21 @@ async fn create_flow(
 #[serde(rename_all = "camelCase")]
 struct FullFlowRequestResponse {
     flow: flow::FlowData,
+    /// Revision of the stored debug flow, this is the Git blob ID of the stored file.
+    ///
+    /// When storing a debug flow, this is the revision the changes are based on. If it is set and the
+    /// stored debug flow has a different revision, the debug flow is not stored.
+    #[serde(default)]
+    #[schema(nullable = false)]
+    revision: Option<String>,
 }
 
 impl FullFlowRequestResponse {
     pub fn new(flow: flow::FlowData) -> Self {
-        Self { flow }
+        Self {
+            flow,
+            revision: None,
+        }
     }
 }
 
@@ -138,6 +252,15 @@ impl From<&flow::FlowData> for FullFlowRequestResponse {
     }
 }
 
+impl From<&flow::Flow> for FullFlowRequestResponse {
+    fn from(value: &flow::Flow) -> Self {
+        Self {
+            flow: value.data().clone(),
+            revision: value.revision().map(str::to_string),
+        }
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/{id}",
@@ -165,10 +288,10 @@ async fn get_flow(
                 ));
             }
         },
-        Err(e) => return Err(api::AppError::InternalServerError(e.to_string())),
+        Err(e) => return Err(e.into()),
     };
 
-    Ok(Json(flow.data().into()))
+    Ok(Json((&flow).into()))
 }
 
 #[utoipa::path(
@@ -187,14 +310,48 @@ async fn delete_flow(
     Path(id): Path<String>,
 ) -> api::Result<api::ApiStatusResponse> {
     match app_state.flows_dir().delete_flow_by_id(&id) {
-        Ok(_) => Ok(Json(http::StatusCode::OK.into())),
+        Ok(_) => {
+            app_state.publish_flow_revision(&id, None);
+            Ok(Json(http::StatusCode::OK.into()))
+        }
         Err(flow::Error::Io(_, io_err)) => match io_err.kind() {
             io::ErrorKind::NotFound => Err(api::AppError::NotFound(id)),
             _ => Err(api::AppError::InternalServerError(
                 io_err.kind().to_string(),
             )),
         },
-        Err(e) => Err(api::AppError::InternalServerError(e.to_string())),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct StoreFlowResponse {
+    /// Revision of the stored debug flow
+    revision: String,
+}
+
+/// Errors of the store debug flow endpoint
+enum StoreFlowError {
+    /// The debug flow was modified since the revision the request is based on
+    Conflict(FullFlowRequestResponse),
+    App(api::AppError),
+}
+
+impl From<api::AppError> for StoreFlowError {
+    fn from(error: api::AppError) -> Self {
+        StoreFlowError::App(error)
+    }
+}
+
+impl IntoResponse for StoreFlowError {
+    fn into_response(self) -> Response {
+        match self {
+            StoreFlowError::Conflict(current) => {
+                (http::StatusCode::CONFLICT, Json(current)).into_response()
+            }
+            StoreFlowError::App(e) => e.into_response(),
+        }
     }
 }
 
@@ -202,9 +359,12 @@ async fn delete_flow(
     post,
     path = "/{id}",
     summary = "Store debug flow",
-    description = "Store debug flow",
+    description = "Store debug flow. If the request contains a revision and the stored debug flow was modified \
+    since that revision, the debug flow is not stored and the current version is returned instead. The debug flow \
+    is always stored under the ID in the path, use the rename endpoint to change the ID of a debug flow.",
     responses(
-        (status = http::StatusCode::OK, description = "Debug flow is stored", body = api::ApiStatusResponse),
+        (status = http::StatusCode::OK, description = "Debug flow is stored", body = StoreFlowResponse),
+        (status = http::StatusCode::CONFLICT, description = "Debug flow was modified in the meantime", body = FullFlowRequestResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
     )
 )]
@@ -212,15 +372,550 @@ async fn store_flow(
     State(app_state): State<web::AppState>,
     Path(id): Path<String>,
     Json(new_flow): Json<FullFlowRequestResponse>,
-) -> api::Result<api::ApiStatusResponse> {
-    match app_state.flows_dir().save_flow(&new_flow.flow) {
-        Ok(_) => Ok(Json(http::StatusCode::OK.into())),
+) -> Result<Json<StoreFlowResponse>, StoreFlowError> {
+    let flows_dir = app_state.flows_dir();
+    match flows_dir.save_flow(&id, &new_flow.flow, new_flow.revision.as_deref()) {
+        Ok(revision) => {
+            app_state.publish_flow_revision(&id, Some(revision.clone()));
+            app_state.record_flow_history(&id);
+            Ok(Json(StoreFlowResponse { revision }))
+        }
+        Err(flow::Error::RevisionConflict { id, .. }) => {
+            let current = flows_dir
+                .get_flow_by_id(&id)
+                .map_err(|e| api::AppError::InternalServerError(e.to_string()))?;
+            Err(StoreFlowError::Conflict((&current).into()))
+        }
         Err(flow::Error::Io(_, io_err)) => match io_err.kind() {
-            io::ErrorKind::NotFound => Err(api::AppError::NotFound(id)),
-            _ => Err(api::AppError::InternalServerError(
-                io_err.kind().to_string(),
-            )),
+            io::ErrorKind::NotFound => Err(api::AppError::NotFound(id).into()),
+            _ => Err(api::AppError::InternalServerError(io_err.kind().to_string()).into()),
         },
-        Err(e) => Err(api::AppError::InternalServerError(e.to_string())),
+        Err(e) => Err(api::AppError::from(e).into()),
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RenameFlowRequest {
+    /// New name of the debug flow, the ID of the debug flow is derived from it
+    name: String,
+}
+
+#[utoipa::path(
+    patch,
+    path = "/{id}",
+    summary = "Rename debug flow",
+    description = "Rename a debug flow. The ID of a debug flow is derived from its name, so the stored file is moved \
+    and the response contains the new ID. Renaming fails if a different debug flow with the new ID exists already.",
+    request_body = RenameFlowRequest,
+    responses(
+        (status = http::StatusCode::OK, description = "Debug flow is renamed", body = CreateFlowResponse),
+        (status = http::StatusCode::CONFLICT, description = "Debug flow with the new ID exists already", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "File not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn rename_flow(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<RenameFlowRequest>,
+) -> api::Result<CreateFlowResponse> {
+    let flow = app_state.flows_dir().rename_flow(&id, &request.name)?;
+    if flow.id() != id {
+        app_state.publish_flow_revision(&id, None);
     }
+    app_state.publish_flow_revision(&flow.id(), flow.revision().map(str::to_string));
+
+    Ok(Json(flow.try_into()?))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct DuplicateFlowRequest {
+    /// Name of the copy, the ID of the copy is derived from it
+    name: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/duplicate",
+    summary = "Duplicate debug flow",
+    description = "Store a copy of a debug flow under a new name",
+    request_body = DuplicateFlowRequest,
+    responses(
+        (status = http::StatusCode::OK, description = "Debug flow is duplicated", body = CreateFlowResponse),
+        (status = http::StatusCode::CONFLICT, description = "Debug flow with the new ID exists already", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "File not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn duplicate_flow(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<DuplicateFlowRequest>,
+) -> api::Result<CreateFlowResponse> {
+    let flow = app_state.flows_dir().duplicate_flow(&id, &request.name)?;
+    app_state.publish_flow_revision(&flow.id(), flow.revision().map(str::to_string));
+
+    Ok(Json(flow.try_into()?))
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ListFlowVersionsResponse {
+    versions: Vec<flow::history::FlowVersion>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/history",
+    summary = "List debug flow versions",
+    description = "List the recorded versions of a debug flow, newest first. Versions are only recorded if the \
+    flow history is enabled, they are stored as commits on `refs/debug-flow/history`.",
+    responses(
+        (status = http::StatusCode::OK, description = "Recorded versions of the debug flow", body = ListFlowVersionsResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn list_flow_versions(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> api::Result<ListFlowVersionsResponse> {
+    let versions = flow::history::versions(app_state.flows_dir(), &id)?;
+    Ok(Json(ListFlowVersionsResponse { versions }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/history/{version}",
+    summary = "Get debug flow version",
+    description = "Get a debug flow as it was recorded in a version. The revision in the response is the \
+    revision of the debug flow in that version.",
+    responses(
+        (status = http::StatusCode::OK, description = "Debug flow in the version", body = FullFlowRequestResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Version not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn get_flow_version(
+    State(app_state): State<web::AppState>,
+    Path((id, version)): Path<(String, String)>,
+) -> api::Result<FullFlowRequestResponse> {
+    let (data, revision) = flow::history::flow_at_version(app_state.flows_dir(), &id, &version)?;
+    Ok(Json(FullFlowRequestResponse {
+        flow: data,
+        revision: Some(revision),
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct ExportFlowQuery {
+    /// Format to export the debug flow to
+    format: flow::export::Format,
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/export",
+    summary = "Export debug flow",
+    description = "Export a debug flow to a text format. The Markdown report starts at the root node and \
+    follows the edges of the flow, branches of the flow become nested sections. Git revisions of the nodes are \
+    resolved to show the summary, author and hash of the linked commits. The Mermaid and DOT graphs color \
+    status nodes by their state and label all nodes with their Git revision.",
+    params(ExportFlowQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "Exported debug flow", content(
+            (String = "text/markdown"),
+            (String = "text/vnd.mermaid"),
+            (String = "text/vnd.graphviz"),
+        )),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "File not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn export_flow(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<ExportFlowQuery>,
+) -> Result<Response, api::AppError> {
+    let content = app_state.flows_dir().export(&id, query.format)?;
+    Ok((
+        [(http::header::CONTENT_TYPE, query.format.content_type())],
+        content,
+    )
+        .into_response())
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct DiffFlowQuery {
+    /// Git revision of the repository to compare the stored debug flow to, e.g. `HEAD` or a commit hash
+    rev: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/diff",
+    summary = "Diff debug flow",
+    description = "Compare the debug flow as it is stored in a Git revision of the repository with the stored \
+    debug flow. Nodes and edges are matched by their IDs, changed nodes and edges list the paths of the changed \
+    fields, e.g. `data.state`.",
+    params(DiffFlowQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "Differences of the debug flow", body = flow::diff::FlowDiff),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Debug flow or revision not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn diff_flow(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+    Query(query): Query<DiffFlowQuery>,
+) -> api::Result<flow::diff::FlowDiff> {
+    Ok(Json(app_state.flows_dir().diff(&id, &query.rev)?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/{id}/validate",
+    summary = "Validate debug flow revisions",
+    description = "Resolve the Git revisions linked to the nodes of the debug flow and report revisions which do \
+    not resolve anymore, commits which are not reachable from any branch or tag and branches or tags which moved \
+    to another commit since they were linked.",
+    responses(
+        (status = http::StatusCode::OK, description = "Revisions which need attention", body = flow::validation::FlowValidation),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "File not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn validate_flow(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> api::Result<flow::validation::FlowValidation> {
+    Ok(Json(app_state.flows_dir().validate(&id)?))
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct PinFlowRevisionsResponse {
+    /// Revision of the debug flow after pinning
+    revision: String,
+    /// Number of nodes whose revision was replaced by a full commit hash
+    pinned: usize,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/pin",
+    summary = "Pin debug flow revisions",
+    description = "Replace the Git revisions linked to the nodes of the debug flow by the full hashes of the \
+    commits they resolve to, so rebases or moved branches do not change what the nodes refer to. Revisions which do \
+    not resolve are kept.",
+    responses(
+        (status = http::StatusCode::OK, description = "Revisions are pinned", body = PinFlowRevisionsResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "File not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn pin_flow_revisions(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> api::Result<PinFlowRevisionsResponse> {
+    let (revision, pinned) = app_state.flows_dir().pin_revisions(&id)?;
+    if pinned > 0 {
+        app_state.publish_flow_revision(&id, Some(revision.clone()));
+        app_state.record_flow_history(&id);
+    }
+    Ok(Json(PinFlowRevisionsResponse { revision, pinned }))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RunCommandRequest {
+    /// Shell command, it is run with `sh -c` in the root of the worktree
+    command: String,
+    /// The command is killed if it runs longer, defaults to 300 seconds
+    timeout_secs: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RunCommandResponse {
+    /// ID of the run, the events of the run in the run stream of the debug flow carry it
+    run_id: u64,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/nodes/{node_id}/run",
+    summary = "Run command at node revision",
+    description = "Check out the Git revision of the node in the worktree \
+    `.debug-flow/worktrees/<flow-id>/<node-id>`, named `debug-flow-<flow-id>.<node-id>`, and run a shell command in \
+    it. The working tree of the repository is not touched. The command runs in the background, its output is \
+    streamed as `run` events on `/{id}/runs/stream`. Once it finished, the exit code and the end of the output are \
+    attached to the node and status nodes are marked as success or fail.",
+    request_body = RunCommandRequest,
+    responses(
+        (status = http::StatusCode::ACCEPTED, description = "Command is started", body = RunCommandResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Empty command or node without Git revision", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Debug flow or node not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn run_node_command(
+    State(app_state): State<web::AppState>,
+    Path((id, node_id)): Path<(String, String)>,
+    Json(request): Json<RunCommandRequest>,
+) -> Result<(http::StatusCode, Json<RunCommandResponse>), api::AppError> {
+    if request.command.trim().is_empty() {
+        return Err(api::AppError::BadRequest("Command is empty".to_string()));
+    }
+    app_state
+        .flows_dir()
+        .get_flow_by_id(&id)?
+        .data()
+        .find_node(&node_id)?
+        .revision()?;
+
+    let run_id = actors::runner::next_run_id();
+    let msg = actors::runner::RunCommand {
+        run_id,
+        flow_id: id.clone(),
+        active: app_state.active_runs().start(&id, &node_id),
+        node_id,
+        command: request.command,
+        timeout: request
+            .timeout_secs
+            .map(std::time::Duration::from_secs)
+            .unwrap_or(actors::runner::DEFAULT_TIMEOUT),
+    };
+    tokio::spawn(async move {
+        // Failures are sent as events of the run
+        match app_state.command_runner().call(msg).await {
+            Ok(Ok(run)) => {
+                app_state.publish_flow_revision(&id, Some(run.revision));
+                app_state.record_flow_history(&id);
+            }
+            Ok(Err(e)) => log::warn!("Run {run_id} of debug flow '{id}' failed: {e}"),
+            Err(e) => log::error!("Error calling command runner: {e}"),
+        }
+    });
+    Ok((
+        http::StatusCode::ACCEPTED,
+        Json(RunCommandResponse { run_id }),
+    ))
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct CheckoutNodeRequest {
+    /// How uncommitted changes in the working directory are handled, defaults to `refuse`
+    #[serde(default)]
+    mode: git2_ox::CheckoutMode,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct CheckoutNodeResponse {
+    /// The checked out commit
+    commit: git2_ox::CommitWithReferences,
+    /// Stash of the uncommitted changes attached to the node, only set for the mode `stash` if there were changes
+    stash: Option<flow::StashRecord>,
+    /// Revision of the debug flow, only set if the stash was attached to the node
+    revision: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/nodes/{node_id}/checkout",
+    summary = "Check out node revision",
+    description = "Check out the Git revision of the node in the working directory of the repository. \
+    Uncommitted changes which would be overwritten block the checkout in the mode `refuse`, `force` discards them. \
+    The mode `stash` stashes the changes to tracked files first and attaches the stash to the node, it can be \
+    restored with `/{id}/nodes/{node_id}/stash/restore`.",
+    request_body = CheckoutNodeRequest,
+    responses(
+        (status = http::StatusCode::OK, description = "Revision checked out successfully", body = CheckoutNodeResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Node without Git revision", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::CONFLICT, description = "Uncommitted changes block the checkout, the details list the paths", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Debug flow or node not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn checkout_node_revision(
+    State(app_state): State<web::AppState>,
+    Path((id, node_id)): Path<(String, String)>,
+    Json(request): Json<CheckoutNodeRequest>,
+) -> api::Result<CheckoutNodeResponse> {
+    let rev = app_state
+        .flows_dir()
+        .get_flow_by_id(&id)?
+        .data()
+        .find_node(&node_id)?
+        .revision()?
+        .to_string();
+    let msg = actors::git::CheckoutRevision {
+        revision: rev,
+        mode: request.mode,
+    };
+    let (commit, stash) = app_state.git_actor().call(msg).await??.into_parts();
+    let Some(stash) = stash else {
+        return Ok(Json(CheckoutNodeResponse {
+            commit,
+            stash: None,
+            revision: None,
+        }));
+    };
+
+    let record = flow::StashRecord {
+        id: stash.id().to_string(),
+        message: stash.message().to_string(),
+        commit: git2_ox::CommitProperties::id(&commit).to_string(),
+        created_at: chrono::Utc::now(),
+    };
+    // The debug flow is read after the checkout, its file can be part of the checked out revision
+    let flow = app_state.flows_dir().get_flow_by_id(&id)?;
+    let mut data = flow.data().clone();
+    data.find_node_mut(&node_id)?
+        .add_attachment(flow::Attachment::Stash(record.clone()));
+    let revision = app_state
+        .flows_dir()
+        .save_flow(&id, &data, flow.revision())?;
+    app_state.publish_flow_revision(&id, Some(revision.clone()));
+    app_state.record_flow_history(&id);
+    Ok(Json(CheckoutNodeResponse {
+        commit,
+        stash: Some(record),
+        revision: Some(revision),
+    }))
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct RestoreStashResponse {
+    /// The restored stash
+    stash: flow::StashRecord,
+    /// Revision of the debug flow without the stash attached to the node
+    revision: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/{id}/nodes/{node_id}/stash/restore",
+    summary = "Restore node stash",
+    description = "Apply the last stash attached to the node by `/{id}/nodes/{node_id}/checkout` to the working \
+    directory of the repository and drop it. The stash is removed from the node.",
+    responses(
+        (status = http::StatusCode::OK, description = "Stash restored", body = RestoreStashResponse),
+        (status = http::StatusCode::CONFLICT, description = "The stash was restored with conflicts or conflicts with uncommitted changes, it is kept on the node. The details list the conflicting paths.", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Debug flow, node or stash not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn restore_node_stash(
+    State(app_state): State<web::AppState>,
+    Path((id, node_id)): Path<(String, String)>,
+) -> api::Result<RestoreStashResponse> {
+    let mut data = app_state.flows_dir().get_flow_by_id(&id)?.data().clone();
+    let stash = data
+        .find_node_mut(&node_id)?
+        .take_stash()
+        .ok_or_else(|| api::AppError::NotFound(format!("Node '{node_id}' has no stash")))?;
+
+    let msg = actors::git::RestoreStash {
+        id: stash.id.clone(),
+    };
+    app_state.git_actor().call(msg).await??;
+
+    // Restoring the stash can change the file of the debug flow, it is read again
+    let flow = app_state.flows_dir().get_flow_by_id(&id)?;
+    let mut data = flow.data().clone();
+    if let Some(node) = data.node_mut(&node_id) {
+        node.take_stash();
+    }
+    let revision = app_state
+        .flows_dir()
+        .save_flow(&id, &data, flow.revision())?;
+    app_state.publish_flow_revision(&id, Some(revision.clone()));
+    app_state.record_flow_history(&id);
+    Ok(Json(RestoreStashResponse { stash, revision }))
+}
+
+/// Stream the progress of commands run for the nodes of a debug flow as server-sent events
+///
+/// Every [`actors::runner::RunEvent`] of the debug flow is sent as a `run` event.
+async fn run_sse_handler(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> response::sse::Sse<impl Stream<Item = Result<response::sse::Event, std::convert::Infallible>>>
+{
+    log::info!("Received SSE request for runs of flow '{id}'");
+    let rx = app_state.run_events_tx().subscribe();
+    let stream = tokio_stream::wrappers::BroadcastStream::new(rx).filter_map(move |msg| {
+        let id = id.clone();
+        async move {
+            match msg {
+                Ok(event) if event.flow_id == id => {
+                    let ev = response::sse::Event::default()
+                        .event("run")
+                        .json_data(event)
+                        .inspect_err(|e| log::error!("Error serializing run event {e}"))
+                        .ok()?;
+                    Some(Ok(ev))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("{e}");
+                    None
+                }
+            }
+        }
+    });
+
+    response::sse::Sse::new(stream).keep_alive(
+        response::sse::KeepAlive::new()
+            .interval(std::time::Duration::from_secs(15))
+            .text("keep-alive-text"),
+    )
+}
+
+/// Stream changes of a debug flow as server-sent events
+///
+/// Changes made through the API and changes of the stored file, e.g. after a `git pull`, are sent as
+/// `flow` events containing a [`flow::FlowEvent`] with the new revision of the debug flow.
+async fn flow_sse_handler(
+    State(app_state): State<web::AppState>,
+    Path(id): Path<String>,
+) -> response::sse::Sse<impl Stream<Item = Result<response::sse::Event, std::convert::Infallible>>>
+{
+    log::info!("Received SSE request for flow '{id}'");
+    let rx = app_state.flow_events_tx().subscribe();
+    let stream = tokio_stream::wrappers::BroadcastStream::new(rx).filter_map(move |msg| {
+        let id = id.clone();
+        async move {
+            match msg {
+                Ok(event) if event.id() == id => {
+                    let ev = response::sse::Event::default()
+                        .event("flow")
+                        .json_data(event)
+                        .inspect_err(|e| log::error!("Error serializing flow event {e}"))
+                        .ok()?;
+                    Some(Ok(ev))
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    log::error!("{e}");
+                    // drop silently; next message will arrive
+                    None
+                }
+            }
+        }
+    });
+
+    response::sse::Sse::new(stream).keep_alive(
+        response::sse::KeepAlive::new()
+            .interval(std::time::Duration::from_secs(15))
+            .text("keep-alive-text"),
+    )
 }
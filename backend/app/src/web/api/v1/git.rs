@@ -1,7 +1,7 @@
-use crate::{actors, web, web::api};
+use crate::{actors, flow, web, web::api};
 
 use axum::extract::{Path, Query, State};
-use axum::{Json, response, routing};
+use axum::{Json, http, response, routing};
 use futures_util::stream::{Stream, StreamExt};
 use git2_ox::{ReferenceKind, ReferenceKindFilter, ResolvedReference, Status, commit};
 use serde::{Deserialize, Serialize};
@@ -15,6 +15,9 @@ pub fn router() -> routing::Router<web::AppState> {
         )
         .route("/commits", routing::get(list_commits))
         .route("/diff", routing::get(get_diff))
+        .route("/blame", routing::get(get_blame))
+        .route("/tree", routing::get(list_tree))
+        .route("/blob", routing::get(read_blob))
         .route("/tags", routing::get(list_tags).post(create_tag))
         .route("/branches", routing::get(list_branches).post(create_branch))
         .route("/repository/status", routing::get(get_repository_status))
@@ -23,13 +26,20 @@ pub fn router() -> routing::Router<web::AppState> {
             routing::any(repository_status_sse_handler),
         )
         .route("/references", routing::get(list_references))
+        .route("/worktrees", routing::get(list_worktrees))
+        .route("/worktrees/{name}", routing::delete(prune_worktree))
+        .route(
+            "/worktrees/{name}/lock",
+            routing::post(lock_worktree).delete(unlock_worktree),
+        )
 }
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
     paths(
         get_revision, checkout_revision, list_commits,  list_tags, create_tag, list_branches, create_branch,
-        get_repository_status, get_diff, list_references
+        get_repository_status, get_diff, get_blame, list_tree, read_blob, list_references, list_worktrees, prune_worktree, lock_worktree,
+        unlock_worktree
     ),
     tags(
         (name = "Git Repository", description="Git Repository related endpoints")
@@ -69,6 +79,31 @@ async fn get_revision(
     Ok(Json(commit))
 }
 
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct CheckoutQuery {
+    /// ID of the debug flow of the node given by `nodeId`
+    #[param(nullable = false)]
+    flow_id: Option<String>,
+    /// Check out the revision in the linked worktree of this node in `.debug-flow/worktrees/` instead of the
+    /// working directory of the repository, requires `flowId`
+    #[param(nullable = false)]
+    node_id: Option<String>,
+    /// How uncommitted changes in the working directory are handled, defaults to `refuse`. Not supported
+    /// together with `nodeId`, worktrees of nodes are always reset.
+    #[param(nullable = false)]
+    mode: Option<git2_ox::CheckoutMode>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct CheckoutResponse {
+    #[serde(flatten)]
+    commit: commit::CommitWithReferences,
+    /// Stash of the uncommitted changes, only set for the mode `stash` if there were changes
+    stash: Option<git2_ox::Stash>,
+}
+
 #[utoipa::path(
     post,
     path = "/commit/{revision}",
@@ -77,12 +112,19 @@ async fn get_revision(
         description = "The revision of the commit to checkout.\n\n\
             This can be the short hash, full hash, a tag, or any other \
             reference such as `HEAD`, a branch name or a tag name", example = "HEAD"),
+        CheckoutQuery,
     ),
     summary="Checkout commit for a revision",
     description = "Checkout a commit by its revision.
-    The revision can be anything accepted by `git rev-parse`. For a branch it will checkout the HEAD of the branch.",
+    The revision can be anything accepted by `git rev-parse`. For a branch it will checkout the HEAD of the branch.
+    Uncommitted changes which would be overwritten block the checkout in the mode `refuse`, the mode `stash` \
+    stashes the changes to tracked files first and `force` discards them.
+    With `flowId` and `nodeId` the commit is checked out with a detached HEAD in the worktree of the node, which \
+    is added if it does not exist yet. Changes in an existing worktree of the node are discarded.",
     responses(
-        (status = http::StatusCode::OK, description = "Revision checked out successfully", body = commit::Commit),
+        (status = http::StatusCode::OK, description = "Revision checked out successfully", body = CheckoutResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Mode given for the worktree of a node or node without debug flow", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::CONFLICT, description = "Uncommitted changes block the checkout, the details list the paths, or a command is running in the worktree of the node", body = api::ApiStatusDetailResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
         (status = http::StatusCode::NOT_FOUND, description = "Revision not found", body = api::ApiStatusDetailResponse),
     )
@@ -90,13 +132,41 @@ async fn get_revision(
 async fn checkout_revision(
     State(state): State<web::AppState>,
     Path(commit_id): Path<String>,
-) -> Result<Json<commit::CommitWithReferences>, api::AppError> {
+    Query(query): Query<CheckoutQuery>,
+) -> Result<Json<CheckoutResponse>, api::AppError> {
     let actor = state.git_actor();
-    let msg = actors::git::CheckoutRevision {
-        revision: commit_id,
+    let (commit, stash) = match query.node_id {
+        Some(_) if query.mode.is_some() => {
+            return Err(api::AppError::BadRequest(
+                "The checkout mode is not supported for worktrees of nodes".to_string(),
+            ));
+        }
+        Some(node_id) => {
+            let flow_id = query.flow_id.ok_or_else(|| {
+                api::AppError::BadRequest("The debug flow of the node is missing".to_string())
+            })?;
+            // The checkout would change the files the command is running on
+            if state.active_runs().contains(&flow_id, &node_id) {
+                return Err(api::AppError::Conflict(format!(
+                    "A command is running in the worktree of node '{node_id}'"
+                )));
+            }
+            let msg = actors::git::CheckoutWorktree {
+                name: flow::FlowsDir::worktree_name(&flow_id, &node_id),
+                path: state.flows_dir().worktree_path(&flow_id, &node_id)?,
+                revision: commit_id,
+            };
+            (actor.call(msg).await??, None)
+        }
+        None => {
+            let msg = actors::git::CheckoutRevision {
+                revision: commit_id,
+                mode: query.mode.unwrap_or_default(),
+            };
+            actor.call(msg).await??.into_parts()
+        }
     };
-    let commit = actor.call(msg).await??;
-    Ok(Json(commit))
+    Ok(Json(CheckoutResponse { commit, stash }))
 }
 
 #[derive(Serialize, ToSchema, Deserialize, IntoParams)]
@@ -105,6 +175,13 @@ struct ListCommitsQuery {
     /// string filter for the commits. Filters commits by their ID or summary.
     #[param(nullable = false)]
     filter: Option<String>,
+    /// Only list commits changing the file or directory at this path relative to the repository root,
+    /// like `git log -- <path>`
+    #[param(nullable = false)]
+    path: Option<String>,
+    /// Follow the file at `path` across renames like `git log --follow`, defaults to true
+    #[param(nullable = false)]
+    follow_renames: Option<bool>,
 
     // serde(flatten) does not work here, see https://github.com/juhaku/utoipa/issues/841
     /// The base revision of the range, this can be short hash, full hash, a tag,
@@ -130,7 +207,8 @@ struct ListCommitsResponse {
     path = "/commits",
     summary = "List commits",
     description = "List the commits in a range similar to `git log`, \
-    the commits are always ordered from newest to oldest in the tree.",
+    the commits are always ordered from newest to oldest in the tree. With a `path` only the commits \
+    changing the file or directory are listed, following renames of a file unless `followRenames` is false.",
     params(ListCommitsQuery),
     responses(
         (status = http::StatusCode::OK, description = "List of commits", body = ListCommitsResponse),
@@ -146,6 +224,8 @@ async fn list_commits(
         base_rev: query.base_rev,
         head_rev: query.head_rev,
         filter: query.filter,
+        path: query.path.filter(|path| !path.is_empty()),
+        follow_renames: query.follow_renames.unwrap_or(true),
     };
     let commits = actor.call(msg).await??;
     Ok(Json(ListCommitsResponse { commits }))
@@ -162,6 +242,25 @@ struct CommitRangeQuery {
     /// or any other reference such a branch name. If empty, the current HEAD is used.
     #[param(nullable = false)]
     head_rev: Option<String>,
+    /// Only diff files matching one of these pathspecs, e.g. `src/` or `*.rs`.
+    /// Renames are only detected if both paths match.
+    #[param(min_items = 1, nullable = false)]
+    path: Option<Vec<String>>,
+    /// Number of unchanged lines around changes, defaults to 3
+    #[param(nullable = false)]
+    context_lines: Option<u32>,
+    /// How whitespace changes are handled, defaults to `show`
+    #[param(nullable = false)]
+    whitespace: Option<git2_ox::WhitespaceMode>,
+    /// Minimum similarity in percent for renamed files, defaults to 50
+    #[param(nullable = false, maximum = 100)]
+    rename_threshold: Option<u16>,
+    /// Minimum similarity in percent for copied files, copies are only detected if it is set
+    #[param(nullable = false, maximum = 100)]
+    copy_threshold: Option<u16>,
+    /// Size in bytes above which files are treated as binary, defaults to 512 MiB
+    #[param(nullable = false)]
+    max_file_size: Option<u64>,
 }
 
 #[derive(Serialize, ToSchema, IntoParams)]
@@ -178,26 +277,205 @@ struct ListDiffsResponse {
     description = "Get the diff of a commit range. The diff consists of the patch describing the \
     differences between the two revisions, similar to the output of `git diff rev1..rev2`, the stats \
     of this diff similar to `git diff --stat` and a map of old source file contents, these can be used \
-    to expand diffs on the client side.",
+    to expand diffs on the client side. The same changes are also provided as structured `files`, with \
+    their paths, status, similarity, binary flag and hunks of typed lines with old and new line numbers, \
+    so the patch does not need to be parsed. The diff can be narrowed to paths and tuned with the number \
+    of context lines, whitespace handling, rename and copy detection thresholds and a maximum file size.",
     params(CommitRangeQuery),
     responses(
         (status = http::StatusCode::OK, description = "List of diffs", body = ListDiffsResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Invalid diff options", body = api::ApiStatusDetailResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
     )
 )]
 async fn get_diff(
     State(state): State<web::AppState>,
-    Query(query): Query<CommitRangeQuery>,
+    axum_extra::extract::Query(query): axum_extra::extract::Query<CommitRangeQuery>,
 ) -> Result<Json<ListDiffsResponse>, api::AppError> {
     let actor = state.git_actor();
+    let options = git2_ox::DiffOptions {
+        pathspecs: query.path.unwrap_or_default(),
+        context_lines: query.context_lines,
+        whitespace: query.whitespace.unwrap_or_default(),
+        rename_threshold: query.rename_threshold,
+        copy_threshold: query.copy_threshold,
+        max_file_size: query.max_file_size,
+    };
     let msg = actors::git::GetDiff {
         base_rev: query.base_rev,
         head_rev: query.head_rev,
+        options,
     };
     let diff = actor.call(msg).await??;
     Ok(Json(ListDiffsResponse { diff }))
 }
 
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct BlameQuery {
+    /// Path of the file relative to the repository root
+    path: String,
+    /// The revision to blame the file at, this can be short hash, full hash, a tag,
+    /// or any other reference such a branch name. If empty, the current HEAD is used.
+    #[param(nullable = false)]
+    rev: Option<String>,
+    /// First line to blame, starting at 1. Must be given together with `endLine`.
+    #[param(nullable = false, minimum = 1)]
+    start_line: Option<usize>,
+    /// Last line to blame, inclusive. Must be given together with `startLine`.
+    #[param(nullable = false, minimum = 1)]
+    end_line: Option<usize>,
+    /// Follow the file across renames, defaults to true
+    #[param(nullable = false)]
+    follow_renames: Option<bool>,
+    /// Ignore whitespace changes, defaults to false
+    #[param(nullable = false)]
+    ignore_whitespace: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct BlameResponse {
+    /// Path of the blamed file
+    path: String,
+    /// Hunks of consecutive lines last changed in the same commit, ordered by line
+    hunks: Vec<git2_ox::BlameHunk>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/blame",
+    summary = "Blame file",
+    description = "Get the commit which last changed each line of a file, similar to `git blame`. Lines are \
+    grouped into hunks of consecutive lines changed in the same commit. If the file was renamed, lines \
+    changed before the rename are blamed on their original commits unless `followRenames` is false.",
+    params(BlameQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "Blame of the file", body = BlameResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "Invalid line range", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Revision or file not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn get_blame(
+    State(state): State<web::AppState>,
+    Query(query): Query<BlameQuery>,
+) -> api::Result<BlameResponse> {
+    let line_range = match (query.start_line, query.end_line) {
+        (Some(start), Some(end)) => Some(start..=end),
+        (None, None) => None,
+        _ => {
+            return Err(api::AppError::BadRequest(
+                "startLine and endLine must be given together".to_string(),
+            ));
+        }
+    };
+    let options = git2_ox::BlameOptions {
+        follow_renames: query.follow_renames.unwrap_or(true),
+        ignore_whitespace: query.ignore_whitespace.unwrap_or_default(),
+    };
+    let actor = state.git_actor();
+    let msg = actors::git::Blame {
+        path: query.path.clone(),
+        revision: query.rev.unwrap_or_else(|| "HEAD".to_string()),
+        line_range,
+        options,
+    };
+    let hunks = actor.call(msg).await??;
+    Ok(Json(BlameResponse {
+        path: query.path,
+        hunks,
+    }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct ListTreeQuery {
+    /// The revision of the tree, this can be short hash, full hash, a tag,
+    /// or any other reference such a branch name. If empty, the current HEAD is used.
+    #[param(nullable = false)]
+    rev: Option<String>,
+    /// Path of the directory relative to the repository root. If empty, the root directory is listed.
+    #[param(nullable = false)]
+    path: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct ListTreeResponse {
+    /// Path of the listed directory
+    path: String,
+    /// Files, directories, symbolic links and submodules in the directory
+    entries: Vec<git2_ox::TreeEntry>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/tree",
+    summary = "List tree",
+    description = "List the entries of a directory at a revision, similar to `git ls-tree`. \
+    The working directory is not considered.",
+    params(ListTreeQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "Entries of the directory", body = ListTreeResponse),
+        (status = http::StatusCode::BAD_REQUEST, description = "The path is not a directory", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Revision or path not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn list_tree(
+    State(state): State<web::AppState>,
+    Query(query): Query<ListTreeQuery>,
+) -> api::Result<ListTreeResponse> {
+    let path = query.path.unwrap_or_default();
+    let actor = state.git_actor();
+    let msg = actors::git::ListTree {
+        revision: query.rev.unwrap_or_else(|| "HEAD".to_string()),
+        path: path.clone(),
+    };
+    let entries = actor.call(msg).await??;
+    Ok(Json(ListTreeResponse { path, entries }))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct ReadBlobQuery {
+    /// Path of the file relative to the repository root
+    path: String,
+    /// The revision of the file, this can be short hash, full hash, a tag,
+    /// or any other reference such a branch name. If empty, the current HEAD is used.
+    #[param(nullable = false)]
+    rev: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/blob",
+    summary = "Read file",
+    description = "Read a file at a revision, similar to `git show <rev>:<path>`. The content is only \
+    returned for text files, binary files only report their size. Symbolic links are not resolved, their \
+    target path is returned instead.",
+    params(ReadBlobQuery),
+    responses(
+        (status = http::StatusCode::OK, description = "The file", body = git2_ox::Blob),
+        (status = http::StatusCode::BAD_REQUEST, description = "The path is not a file", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Revision or file not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn read_blob(
+    State(state): State<web::AppState>,
+    Query(query): Query<ReadBlobQuery>,
+) -> api::Result<git2_ox::Blob> {
+    let actor = state.git_actor();
+    let msg = actors::git::ReadBlob {
+        revision: query.rev.unwrap_or_else(|| "HEAD".to_string()),
+        path: query.path,
+    };
+    let blob = actor.call(msg).await??;
+    Ok(Json(blob))
+}
+
 #[derive(ToSchema, Serialize, Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
 struct ListTagsQuery {
@@ -343,18 +621,57 @@ async fn create_branch(
     Ok(Json(branch))
 }
 
+/// Linked worktree belonging to a node of a debug flow
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct NodeWorktree {
+    /// Name of the linked worktree
+    name: String,
+    /// ID of the debug flow of the node
+    flow_id: String,
+    /// ID of the node the revision is checked out for
+    node_id: String,
+}
+
+/// Get the linked worktrees which belong to nodes of debug flows
+fn node_worktrees(worktrees: &[git2_ox::Worktree]) -> Vec<NodeWorktree> {
+    worktrees
+        .iter()
+        .filter_map(|worktree| {
+            let (flow_id, node_id) = flow::FlowsDir::worktree_node(worktree.name())?;
+            Some(NodeWorktree {
+                name: worktree.name().to_string(),
+                flow_id,
+                node_id,
+            })
+        })
+        .collect()
+}
+
 #[derive(Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct RepositoryStatusResponse {
     #[serde(flatten)]
     status: Status,
+    /// Linked worktrees of nodes, a subset of `linkedWorktrees`
+    node_worktrees: Vec<NodeWorktree>,
+}
+
+impl From<Status> for RepositoryStatusResponse {
+    fn from(status: Status) -> Self {
+        Self {
+            node_worktrees: node_worktrees(status.linked_worktrees()),
+            status,
+        }
+    }
 }
 
 #[utoipa::path(
     get,
     path = "/repository/status",
     summary = "Get repository status",
-    description = "Get the current status of the repository, including the current HEAD commit and branch.",
+    description = "Get the current status of the repository, including the current HEAD commit and branch and the \
+    linked worktrees with the nodes they belong to.",
     responses(
         (status = http::StatusCode::OK, description = "Repository status", body = RepositoryStatusResponse),
         (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
@@ -366,7 +683,7 @@ async fn get_repository_status(
     let actor = state.git_actor();
     let msg = actors::git::GetRepositoryStatus;
     let status = actor.call(msg).await??;
-    Ok(Json(RepositoryStatusResponse { status }))
+    Ok(Json(status.into()))
 }
 
 async fn repository_status_sse_handler(
@@ -383,7 +700,7 @@ async fn repository_status_sse_handler(
                 log::info!("Received Git status from channel");
                 let ev = response::sse::Event::default()
                     .event("git-status")
-                    .json_data(payload)
+                    .json_data(RepositoryStatusResponse::from(payload))
                     .inspect_err(|e| log::error!("Error serializing Git status {e}"))
                     .ok()?;
 
@@ -469,3 +786,127 @@ async fn list_references(
     let references = actor.call(msg).await??;
     Ok(Json(ListReferencesResponse { references }))
 }
+
+#[derive(ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ListWorktreesResponse {
+    /// Linked worktrees ordered by name
+    worktrees: Vec<git2_ox::Worktree>,
+    /// Linked worktrees of nodes
+    node_worktrees: Vec<NodeWorktree>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/worktrees",
+    summary = "List worktrees",
+    description = "List the linked worktrees of the repository, like `git worktree list`, and the nodes the \
+    worktrees in `.debug-flow/worktrees/` belong to.",
+    responses(
+        (status = http::StatusCode::OK, description = "List of worktrees", body = ListWorktreesResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn list_worktrees(
+    State(state): State<web::AppState>,
+) -> Result<Json<ListWorktreesResponse>, api::AppError> {
+    let actor = state.git_actor();
+    let worktrees = actor.call(actors::git::ListWorktrees).await??;
+    Ok(Json(ListWorktreesResponse {
+        node_worktrees: node_worktrees(&worktrees),
+        worktrees,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/worktrees/{name}",
+    params(("name", description = "Name of the linked worktree")),
+    summary = "Prune worktree",
+    description = "Remove a linked worktree and its working directory, changes in it are lost. Locked worktrees \
+    are not removed.",
+    responses(
+        (status = http::StatusCode::OK, description = "Worktree removed", body = api::ApiStatusResponse),
+        (status = http::StatusCode::CONFLICT, description = "The worktree is locked or a command is running in the worktree of the node", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Worktree not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn prune_worktree(
+    State(state): State<web::AppState>,
+    Path(name): Path<String>,
+) -> api::Result<api::ApiStatusResponse> {
+    // The command would lose the files it is running on
+    if let Some((_, node_id)) = flow::FlowsDir::worktree_node(&name)
+        .filter(|(flow_id, node_id)| state.active_runs().contains(flow_id, node_id))
+    {
+        return Err(api::AppError::Conflict(format!(
+            "A command is running in the worktree of node '{node_id}'"
+        )));
+    }
+    let actor = state.git_actor();
+    actor.call(actors::git::PruneWorktree { name }).await??;
+    Ok(Json(http::StatusCode::OK.into()))
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+struct LockWorktreeQuery {
+    /// Reason the worktree is locked for
+    #[param(nullable = false)]
+    reason: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/worktrees/{name}/lock",
+    params(("name", description = "Name of the linked worktree"), LockWorktreeQuery),
+    summary = "Lock worktree",
+    description = "Lock a linked worktree so it is not pruned, like `git worktree lock`.",
+    responses(
+        (status = http::StatusCode::OK, description = "Worktree locked", body = git2_ox::Worktree),
+        (status = http::StatusCode::CONFLICT, description = "The worktree is locked already", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Worktree not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn lock_worktree(
+    State(state): State<web::AppState>,
+    Path(name): Path<String>,
+    Query(query): Query<LockWorktreeQuery>,
+) -> api::Result<git2_ox::Worktree> {
+    let actor = state.git_actor();
+    let msg = actors::git::LockWorktree {
+        name,
+        lock: true,
+        reason: query.reason,
+    };
+    let worktree = actor.call(msg).await??;
+    Ok(Json(worktree))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/worktrees/{name}/lock",
+    params(("name", description = "Name of the linked worktree")),
+    summary = "Unlock worktree",
+    description = "Unlock a linked worktree, like `git worktree unlock`.",
+    responses(
+        (status = http::StatusCode::OK, description = "Worktree unlocked", body = git2_ox::Worktree),
+        (status = http::StatusCode::INTERNAL_SERVER_ERROR, description = "Internal server error", body = api::ApiStatusDetailResponse),
+        (status = http::StatusCode::NOT_FOUND, description = "Worktree not found", body = api::ApiStatusDetailResponse),
+    )
+)]
+async fn unlock_worktree(
+    State(state): State<web::AppState>,
+    Path(name): Path<String>,
+) -> api::Result<git2_ox::Worktree> {
+    let actor = state.git_actor();
+    let msg = actors::git::LockWorktree {
+        name,
+        lock: false,
+        reason: None,
+    };
+    let worktree = actor.call(msg).await??;
+    Ok(Json(worktree))
+}
@@ -0,0 +1,234 @@
+use crate::{Commit, Result, error::Error, utils};
+use std::collections::HashMap;
+
+/// Verdict on a tested revision
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BisectVerdict {
+    /// The revision does not have the problem
+    Good,
+    /// The revision has the problem
+    Bad,
+    /// The revision cannot be tested, e.g. it does not build
+    Skip,
+}
+
+/// Revisions marked in a bisection, all revisions are full commit IDs
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bisect {
+    /// Newest known bad commit
+    bad: String,
+    /// Commits without the problem
+    good: Vec<String>,
+    /// Commits which cannot be tested
+    skipped: Vec<String>,
+}
+
+impl Bisect {
+    /// Get the newest known bad commit
+    pub fn bad(&self) -> &str {
+        &self.bad
+    }
+
+    /// Get the commits without the problem
+    pub fn good(&self) -> &[String] {
+        &self.good
+    }
+
+    /// Get the commits which cannot be tested
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+}
+
+/// Next step of a bisection
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase", tag = "type")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum BisectStep {
+    /// The commit should be tested next
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    Test {
+        commit: Commit,
+        /// Number of commits which can still be the first bad commit
+        candidates: usize,
+        /// Estimated number of remaining tests
+        steps: u32,
+    },
+    /// The first bad commit was found
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    FirstBad { commit: Commit },
+    /// Only skipped commits are left to test, the first bad commit is one of the candidates
+    #[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+    Inconclusive { candidates: Vec<Commit> },
+}
+
+fn commit_id(repo: &git2::Repository, rev: &str) -> Result<String> {
+    Ok(utils::get_commit_for_revision(repo, rev)?.id().to_string())
+}
+
+/// Start a bisection, see [`crate::Repository::bisect_start`]
+pub(crate) fn start<S: AsRef<str>>(
+    repo: &git2::Repository,
+    bad: &str,
+    good: &[S],
+) -> Result<Bisect> {
+    if good.is_empty() {
+        return Err(Error::from_ctx_and_error(
+            "Bisection",
+            git2::Error::new(
+                git2::ErrorCode::Invalid,
+                git2::ErrorClass::Invalid,
+                "At least one good revision is required",
+            ),
+        ));
+    }
+    let mut bisect = Bisect {
+        bad: commit_id(repo, bad)?,
+        good: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for rev in good {
+        mark(repo, &mut bisect, rev.as_ref(), BisectVerdict::Good)?;
+    }
+    Ok(bisect)
+}
+
+/// Mark a revision in a bisection, see [`crate::Repository::bisect_mark`]
+pub(crate) fn mark(
+    repo: &git2::Repository,
+    bisect: &mut Bisect,
+    rev: &str,
+    verdict: BisectVerdict,
+) -> Result<Commit> {
+    let commit = utils::get_commit_for_revision(repo, rev)?;
+    let id = commit.id().to_string();
+    bisect.good.retain(|good| *good != id);
+    bisect.skipped.retain(|skipped| *skipped != id);
+    match verdict {
+        BisectVerdict::Good => bisect.good.push(id),
+        BisectVerdict::Bad => bisect.bad = id,
+        BisectVerdict::Skip => bisect.skipped.push(id),
+    }
+    Ok((&commit).into())
+}
+
+/// Walk the commits reachable from `head` which are not reachable from the good commits of the bisection
+fn revwalk_without_good<'repo>(
+    repo: &'repo git2::Repository,
+    bisect: &Bisect,
+    head: &str,
+) -> Result<git2::Revwalk<'repo>> {
+    let mut revwalk =
+        utils::revwalk_for_range(repo, bisect.good.first().map(String::as_str), Some(head))?;
+    for good in bisect.good.iter().skip(1) {
+        let oid = git2::Oid::from_str(good)
+            .map_err(|e| Error::from_ctx_and_error(format!("Commit '{good}'"), e))?;
+        revwalk.hide(oid).map_err(|e| {
+            Error::from_ctx_and_error(format!("Failed to hide good commit '{good}'"), e)
+        })?;
+    }
+    Ok(revwalk)
+}
+
+/// Get the commits which can be the first bad commit, ancestors come before their descendants
+fn candidates(repo: &git2::Repository, bisect: &Bisect) -> Result<Vec<git2::Oid>> {
+    let mut revwalk = revwalk_without_good(repo, bisect, &bisect.bad)?;
+    // The walk has not started yet, changing the sorting keeps the pushed and hidden commits
+    revwalk
+        .set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)
+        .map_err(|e| Error::from_ctx_and_error("Failed to sort revwalk", e))?;
+    revwalk
+        .map(|oid| oid.map_err(|e| Error::from_ctx_and_error("Failed to get oid object", e)))
+        .collect()
+}
+
+/// Get the next step of a bisection, see [`crate::Repository::bisect_step`]
+///
+/// The commit to test is the one splitting the candidates most evenly: the number of candidates it is a
+/// descendant of, including itself, is as close as possible to half of the candidates.
+pub(crate) fn step(repo: &git2::Repository, bisect: &Bisect) -> Result<BisectStep> {
+    let candidates = candidates(repo, bisect)?;
+    if candidates.is_empty() {
+        return Err(Error::from_ctx_and_error(
+            "Bisection",
+            git2::Error::new(
+                git2::ErrorCode::Invalid,
+                git2::ErrorClass::Invalid,
+                format!(
+                    "The bad commit '{}' is an ancestor of a good commit",
+                    bisect.bad
+                ),
+            ),
+        ));
+    }
+    let commit =
+        |oid: git2::Oid| -> Result<Commit> { Ok((&utils::get_commit_for_oid(repo, oid)?).into()) };
+
+    // Number of candidates reachable from every candidate, including itself. Like in git, the count of a
+    // commit with a single parent among the candidates is the count of the parent plus one, only the
+    // candidates reachable from merges are walked. Parents come before their children.
+    let total = candidates.len();
+    let mut counts: HashMap<git2::Oid, usize> = HashMap::with_capacity(total);
+    for oid in &candidates {
+        let parents: Vec<git2::Oid> = utils::get_commit_for_oid(repo, *oid)?
+            .parent_ids()
+            .filter(|parent| counts.contains_key(parent))
+            .collect();
+        let count = match parents.as_slice() {
+            [] => 1,
+            [parent] => counts[parent] + 1,
+            _ => revwalk_without_good(repo, bisect, &oid.to_string())?.count(),
+        };
+        counts.insert(*oid, count);
+    }
+
+    let is_testable = |oid: &git2::Oid| {
+        let id = oid.to_string();
+        id != bisect.bad && !bisect.skipped.contains(&id)
+    };
+    let mut best: Option<(usize, git2::Oid)> = None;
+    for oid in &candidates {
+        if !is_testable(oid) {
+            continue;
+        }
+        let count = counts[oid];
+        let balance = usize::min(count, total - count);
+        if best.is_none_or(|(best_balance, _)| balance > best_balance) {
+            best = Some((balance, *oid));
+        }
+    }
+
+    match best {
+        Some((_, oid)) => Ok(BisectStep::Test {
+            commit: commit(oid)?,
+            candidates: total,
+            steps: usize::BITS - (total - 1).leading_zeros(),
+        }),
+        None if total == 1 => Ok(BisectStep::FirstBad {
+            commit: commit(candidates[0])?,
+        }),
+        None => Ok(BisectStep::Inconclusive {
+            candidates: candidates
+                .iter()
+                .rev()
+                .map(|oid| commit(*oid))
+                .collect::<Result<_>>()?,
+        }),
+    }
+}
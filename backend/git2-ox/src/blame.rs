@@ -0,0 +1,192 @@
+use crate::{CommitWithReferences, Result, error::Error, reference::ReferencesMap, utils};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Options of a blame
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlameOptions {
+    /// Blame lines on the commits which changed them before the file was renamed, like `git blame`.
+    /// Otherwise lines older than the rename are blamed on the commit which added the file at its path.
+    pub follow_renames: bool,
+    /// Ignore changes of whitespace, like `git blame -w`
+    pub ignore_whitespace: bool,
+}
+
+impl Default for BlameOptions {
+    fn default() -> Self {
+        Self {
+            follow_renames: true,
+            ignore_whitespace: false,
+        }
+    }
+}
+
+/// Consecutive lines of a file last changed in the same commit
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlameHunk {
+    /// Commit which last changed the lines
+    commit: CommitWithReferences,
+    /// Path of the file in the commit, it differs from the blamed path if the file was renamed since
+    path: String,
+    /// Number of the first line of the hunk in the blamed file, starting at 1
+    start_line: usize,
+    /// Number of the first line of the hunk in the file of the commit, starting at 1
+    orig_start_line: usize,
+    /// Number of lines in the hunk
+    lines: usize,
+    /// Whether the lines are blamed on the oldest considered commit, they can be older
+    is_boundary: bool,
+}
+
+impl BlameHunk {
+    /// Get the commit which last changed the lines
+    pub fn commit(&self) -> &CommitWithReferences {
+        &self.commit
+    }
+
+    /// Get the path of the file in the commit
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the number of the first line of the hunk in the blamed file, starting at 1
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    /// Get the number of the first line of the hunk in the file of the commit, starting at 1
+    pub fn orig_start_line(&self) -> usize {
+        self.orig_start_line
+    }
+
+    /// Get the number of lines in the hunk
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Get the lines of the hunk in the blamed file
+    pub fn line_range(&self) -> RangeInclusive<usize> {
+        self.start_line..=self.start_line + self.lines - 1
+    }
+
+    /// Whether the lines are blamed on the oldest considered commit
+    pub fn is_boundary(&self) -> bool {
+        self.is_boundary
+    }
+}
+
+/// Get the number of lines of the file at `path` in the tree of `commit`
+fn line_count(repo: &git2::Repository, commit: &git2::Commit, path: &Path) -> Result<usize> {
+    let ctx = || format!("File '{}' in commit '{}'", path.display(), commit.id());
+    let entry = commit
+        .tree()
+        .and_then(|tree| tree.get_path(path))
+        .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+    let content = blob.content();
+    let newlines = content.iter().filter(|byte| **byte == b'\n').count();
+    Ok(match content.last() {
+        Some(b'\n') | None => newlines,
+        Some(_) => newlines + 1,
+    })
+}
+
+fn validate_line_range(range: &RangeInclusive<usize>, line_count: usize) -> Result<()> {
+    if *range.start() >= 1 && !range.is_empty() && *range.end() <= line_count {
+        return Ok(());
+    }
+    Err(Error::from_ctx_and_error(
+        format!("Line range {}-{}", range.start(), range.end()),
+        git2::Error::new(
+            git2::ErrorCode::Invalid,
+            git2::ErrorClass::Invalid,
+            format!("Lines start at 1 and end at most at line {line_count} of the file"),
+        ),
+    ))
+}
+
+/// Get the commit which added the file at `path`, following the first parents from `commit`
+fn added_in<'repo>(commit: git2::Commit<'repo>, path: &Path) -> Result<git2::Commit<'repo>> {
+    let mut commit = commit;
+    loop {
+        let Ok(parent) = commit.parent(0) else {
+            return Ok(commit);
+        };
+        let parent_has_path = parent
+            .tree()
+            .map_err(|e| Error::from_ctx_and_error(format!("Tree of commit '{}'", parent.id()), e))?
+            .get_path(path)
+            .is_ok();
+        if !parent_has_path {
+            return Ok(commit);
+        }
+        commit = parent;
+    }
+}
+
+/// Blame the lines of a file, see [`crate::Repository::blame`]
+pub(crate) fn blame(
+    repo: &git2::Repository,
+    path: &str,
+    rev: &str,
+    line_range: Option<RangeInclusive<usize>>,
+    options: &BlameOptions,
+) -> Result<Vec<BlameHunk>> {
+    let commit = utils::get_commit_for_revision(repo, rev)?;
+    let file_path = Path::new(path);
+
+    let mut opts = git2::BlameOptions::new();
+    opts.newest_commit(commit.id())
+        .ignore_whitespace(options.ignore_whitespace);
+    if let Some(range) = &line_range {
+        validate_line_range(range, line_count(repo, &commit, file_path)?)?;
+        opts.min_line(*range.start()).max_line(*range.end());
+    }
+    if !options.follow_renames {
+        opts.oldest_commit(added_in(commit, file_path)?.id());
+    }
+
+    let blame = repo.blame_file(file_path, Some(&mut opts)).map_err(|e| {
+        Error::from_ctx_and_error(format!("Failed to blame '{path}' at '{rev}'"), e)
+    })?;
+
+    let ref_map = ReferencesMap::try_from(repo)?;
+    let mut commits: HashMap<git2::Oid, CommitWithReferences> = HashMap::new();
+    let mut hunks = Vec::with_capacity(blame.len());
+    for hunk in blame.iter() {
+        let oid = hunk.final_commit_id();
+        let commit = match commits.get(&oid) {
+            Some(commit) => commit.clone(),
+            None => {
+                let commit = CommitWithReferences::try_from_oid_and_references(
+                    repo,
+                    oid,
+                    ref_map.get_references_for_commit_oid(oid),
+                )?;
+                commits.insert(oid, commit.clone());
+                commit
+            }
+        };
+        hunks.push(BlameHunk {
+            commit,
+            path: hunk
+                .path()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string()),
+            start_line: hunk.final_start_line(),
+            orig_start_line: hunk.orig_start_line(),
+            lines: hunk.lines_in_hunk(),
+            is_boundary: hunk.is_boundary(),
+        });
+    }
+    Ok(hunks)
+}
@@ -0,0 +1,299 @@
+use crate::{CommitWithReferences, Result, error::Error};
+
+/// How uncommitted changes are handled when checking out a revision
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CheckoutMode {
+    /// Fail with [`Error::CheckoutConflict`] if changes would be overwritten, like `git checkout`
+    #[default]
+    Refuse,
+    /// Stash the changes to tracked files before checking out, see [`crate::Repository::restore_stash`]
+    Stash,
+    /// Discard the changes to tracked files, like `git checkout --force`
+    Force,
+}
+
+/// Stash created for a checkout
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Stash {
+    /// ID of the stash commit
+    id: String,
+    /// Message of the stash
+    message: String,
+}
+
+impl Stash {
+    /// Get the ID of the stash commit
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the message of the stash
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// Result of checking out a revision
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Checkout {
+    /// The checked out commit
+    commit: CommitWithReferences,
+    /// Stash of the changes made before the checkout, only set for [`CheckoutMode::Stash`] if there were changes
+    stash: Option<Stash>,
+}
+
+impl Checkout {
+    pub(crate) fn new(commit: CommitWithReferences, stash: Option<Stash>) -> Self {
+        Self { commit, stash }
+    }
+
+    /// Get the checked out commit
+    pub fn commit(&self) -> &CommitWithReferences {
+        &self.commit
+    }
+
+    /// Get the stash of the changes made before the checkout
+    pub fn stash(&self) -> Option<&Stash> {
+        self.stash.as_ref()
+    }
+
+    /// Split the checkout into the commit and the stash
+    pub fn into_parts(self) -> (CommitWithReferences, Option<Stash>) {
+        (self.commit, self.stash)
+    }
+}
+
+/// Reload the index from disk
+///
+/// The index is cached and only reloaded if its file changed noticeably, changes made by other processes
+/// within the same second can go unnoticed.
+fn reload_index(repo: &git2::Repository) -> Result<()> {
+    repo.index()
+        .and_then(|mut index| index.read(true))
+        .map_err(|e| Error::from_ctx_and_error("Failed to read index", e))
+}
+
+/// Whether tracked files have changes in the index or working directory
+fn has_tracked_changes(repo: &git2::Repository) -> Result<bool> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    let statuses = repo
+        .statuses(Some(&mut opts))
+        .map_err(|e| Error::from_ctx_and_error("Failed to create statuses", e))?;
+    Ok(!statuses.is_empty())
+}
+
+fn stash_save(repo: &mut git2::Repository, rev: &str) -> Result<Stash> {
+    // Repositories without a configured user can still stash changes
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("debug-flow", "debug-flow@localhost"))
+        .map_err(|e| Error::from_ctx_and_error("Failed to create signature", e))?;
+    let message = format!("debug-flow: checkout of '{rev}'");
+    let id = repo
+        .stash_save(&signature, &message, None)
+        .map_err(|e| Error::from_ctx_and_error("Failed to stash changes", e))?;
+    Ok(Stash {
+        id: id.to_string(),
+        message,
+    })
+}
+
+/// Get the index of the stash with the commit ID `id` in the stash list
+fn stash_index(repo: &mut git2::Repository, id: &str) -> Result<usize> {
+    let mut index = None;
+    repo.stash_foreach(|i, _, oid| {
+        if oid.to_string() == id {
+            index = Some(i);
+        }
+        index.is_none()
+    })
+    .map_err(|e| Error::from_ctx_and_error("Failed to list stashes", e))?;
+    index.ok_or_else(|| {
+        Error::from_ctx_and_error(
+            format!("Stash '{id}'"),
+            git2::Error::new(
+                git2::ErrorCode::NotFound,
+                git2::ErrorClass::Stash,
+                "The stash does not exist",
+            ),
+        )
+    })
+}
+
+/// Get the paths with conflicts in the index
+fn conflicted_paths(repo: &git2::Repository) -> Result<Vec<String>> {
+    let index = repo
+        .index()
+        .map_err(|e| Error::from_ctx_and_error("Failed to get index", e))?;
+    let conflicts = index
+        .conflicts()
+        .map_err(|e| Error::from_ctx_and_error("Failed to get conflicts", e))?;
+    let mut paths = Vec::new();
+    for conflict in conflicts {
+        let conflict =
+            conflict.map_err(|e| Error::from_ctx_and_error("Failed to get conflict", e))?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    Ok(paths)
+}
+
+/// Apply a stash to the working directory and drop it, see [`crate::Repository::restore_stash`]
+pub(crate) fn restore_stash(repo: &mut git2::Repository, id: &str) -> Result<()> {
+    reload_index(repo)?;
+    let index = stash_index(repo, id)?;
+    repo.stash_apply(index, None)
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to restore stash '{id}'"), e))?;
+    // Like `git stash pop`, the stash is kept if it was applied with conflicts
+    let paths = conflicted_paths(repo)?;
+    if !paths.is_empty() {
+        return Err(Error::StashConflict {
+            id: id.to_string(),
+            paths,
+        });
+    }
+    repo.stash_drop(index)
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to drop stash '{id}'"), e))
+}
+
+/// Check out `object` and point `HEAD` to it
+///
+/// Without `force` nothing is changed if uncommitted changes would be overwritten, libgit2 determines all
+/// changes before writing the first file. The paths of these changes are collected from the conflict
+/// notifications.
+fn checkout_object(
+    repo: &git2::Repository,
+    object: &git2::Object,
+    reference: Option<&git2::Reference>,
+    rev: &str,
+    force: bool,
+) -> Result<()> {
+    let mut paths = Vec::new();
+    let result = {
+        let mut builder = git2::build::CheckoutBuilder::new();
+        match force {
+            true => builder.force(),
+            false => builder
+                .safe()
+                .notify_on(git2::CheckoutNotificationType::CONFLICT)
+                .notify(|_, path, _, _, _| {
+                    if let Some(path) = path {
+                        paths.push(path.to_string_lossy().to_string());
+                    }
+                    true
+                }),
+        };
+        repo.checkout_tree(object, Some(&mut builder))
+    };
+    match result {
+        Err(_) if !paths.is_empty() => {
+            paths.sort();
+            paths.dedup();
+            return Err(Error::CheckoutConflict {
+                rev: rev.to_string(),
+                paths,
+            });
+        }
+        result => result.map_err(|e| {
+            Error::from_ctx_and_error(format!("Failed to checkout revision '{rev}'"), e)
+        })?,
+    }
+
+    match reference {
+        // gref is an actual reference like branches or tags
+        Some(gref) => repo.set_head(gref.name().unwrap_or_default()),
+        // this is a commit, not a reference
+        None => repo.set_head_detached(object.id()),
+    }
+    .map_err(|e| Error::from_ctx_and_error(format!("Failed to set head to revision '{rev}'"), e))
+}
+
+/// Check out a revision without changing uncommitted changes, see [`crate::Repository::checkout_revision`]
+///
+/// Returns the ID of the checked out commit.
+pub(crate) fn checkout_revision(repo: &git2::Repository, rev: &str) -> Result<git2::Oid> {
+    reload_index(repo)?;
+    let (object, reference) = parse_revision(repo, rev)?;
+    checkout_object(repo, &object, reference.as_ref(), rev, false)?;
+    peel_to_commit_id(&object, rev)
+}
+
+/// Check out a revision handling uncommitted changes according to `mode`, see
+/// [`crate::Repository::checkout_revision_with_mode`]
+///
+/// Returns the ID of the checked out commit and the stash of the changes.
+pub(crate) fn checkout_revision_with_mode(
+    repo: &mut git2::Repository,
+    rev: &str,
+    mode: CheckoutMode,
+) -> Result<(git2::Oid, Option<Stash>)> {
+    match mode {
+        CheckoutMode::Refuse => Ok((checkout_revision(repo, rev)?, None)),
+        CheckoutMode::Force => {
+            reload_index(repo)?;
+            let (object, reference) = parse_revision(repo, rev)?;
+            checkout_object(repo, &object, reference.as_ref(), rev, true)?;
+            Ok((peel_to_commit_id(&object, rev)?, None))
+        }
+        CheckoutMode::Stash => {
+            // Fail early for unknown revisions, before anything is stashed
+            parse_revision(repo, rev)?;
+            reload_index(repo)?;
+            let stash = match has_tracked_changes(repo)? {
+                true => Some(stash_save(repo, rev)?),
+                false => None,
+            };
+            // Untracked files are not stashed and can still block the checkout
+            match checkout_revision(repo, rev) {
+                Ok(id) => Ok((id, stash)),
+                Err(e) => match &stash {
+                    Some(stash) => match restore_stash(repo, &stash.id) {
+                        Ok(()) => Err(e),
+                        // The error of the checkout is kept, the changes are still in the stash
+                        Err(restore_error) => Err(Error::StashNotRestored {
+                            stash: stash.id.clone(),
+                            error: Box::new(e),
+                            restore_error: Box::new(restore_error),
+                        }),
+                    },
+                    None => Err(e),
+                },
+            }
+        }
+    }
+}
+
+fn parse_revision<'repo>(
+    repo: &'repo git2::Repository,
+    rev: &str,
+) -> Result<(git2::Object<'repo>, Option<git2::Reference<'repo>>)> {
+    repo.revparse_ext(rev)
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to parse revision '{rev}'"), e))
+}
+
+fn peel_to_commit_id(object: &git2::Object, rev: &str) -> Result<git2::Oid> {
+    object
+        .peel_to_commit()
+        .map(|commit| commit.id())
+        .map_err(|e| Error::from_ctx_and_error(format!("Commit of revision '{rev}'"), e))
+}
@@ -12,11 +12,23 @@ pub trait CommitProperties {
     serde(rename_all = "camelCase")
 )]
 #[derive(Clone, Debug, PartialEq)]
-#[allow(dead_code)]
-struct Signature {
+pub struct Signature {
     name: String,
     email: String,
 }
+
+impl Signature {
+    /// Get the name of the signature
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the email of the signature
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
 impl From<git2::Signature<'_>> for Signature {
     fn from(signature: git2::Signature<'_>) -> Self {
         Signature {
@@ -82,6 +94,33 @@ impl CommitProperties for Commit {
     }
 }
 
+impl Commit {
+    /// Get the body of the commit message
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Get the commit time
+    pub fn time(&self) -> &chrono::DateTime<chrono::Utc> {
+        &self.time
+    }
+
+    /// Get the author of the commit
+    pub fn author(&self) -> &Signature {
+        &self.author
+    }
+
+    /// Get the committer of the commit
+    pub fn committer(&self) -> &Signature {
+        &self.committer
+    }
+
+    /// Get the abbreviated commit ID
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(7)]
+    }
+}
+
 impl<'repo> Commit {
     /// Try to create a `Commit` from an revision string
     /// * `repo` - Reference to the repository
@@ -105,7 +144,6 @@ impl<'repo> Commit {
     serde(rename_all = "camelCase")
 )]
 #[derive(Clone, Debug, PartialEq)]
-#[allow(dead_code)]
 pub struct CommitWithReferences {
     #[cfg_attr(feature = "serde", serde(flatten))]
     commit: Commit,
@@ -124,6 +162,16 @@ impl CommitProperties for CommitWithReferences {
 }
 
 impl CommitWithReferences {
+    /// Get the commit
+    pub fn commit(&self) -> &Commit {
+        &self.commit
+    }
+
+    /// Get the references pointing to the commit
+    pub fn references(&self) -> &[ReferenceMetadata] {
+        &self.references
+    }
+
     pub fn try_from_git2_commit_and_references(
         commit: &git2::Commit,
         references: Option<&ReferenceMetadatas>,
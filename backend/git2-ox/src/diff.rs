@@ -49,6 +49,420 @@ impl DiffStats {
     }
 }
 
+/// How whitespace changes are handled in a diff
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WhitespaceMode {
+    /// Whitespace changes are part of the diff
+    #[default]
+    Show,
+    /// Ignore all whitespace, like `git diff --ignore-all-space`
+    IgnoreAll,
+    /// Ignore changes in the amount of whitespace, like `git diff --ignore-space-change`
+    IgnoreChange,
+    /// Ignore whitespace at the end of lines, like `git diff --ignore-space-at-eol`
+    IgnoreEol,
+}
+
+/// Options of a diff between revisions
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DiffOptions {
+    /// Only diff the files matching one of these pathspecs, all files are diffed if it is empty.
+    /// Renames are only detected if both paths match.
+    pub pathspecs: Vec<String>,
+    /// Number of unchanged lines around changes, like `git diff --unified`. Defaults to 3.
+    pub context_lines: Option<u32>,
+    /// How whitespace changes are handled
+    pub whitespace: WhitespaceMode,
+    /// Minimum similarity in percent for a deleted and an added file to be a rename, like
+    /// `git diff --find-renames`. Defaults to 50.
+    pub rename_threshold: Option<u16>,
+    /// Minimum similarity in percent for an added file to be a copy of a modified file, like
+    /// `git diff --find-copies`. Copies are only detected if it is set.
+    pub copy_threshold: Option<u16>,
+    /// Size in bytes above which files are treated as binary and have no hunks. Defaults to 512 MiB.
+    pub max_file_size: Option<u64>,
+}
+
+impl DiffOptions {
+    fn invalid(ctx: &str, message: &str) -> error::Error {
+        error::Error::from_ctx_and_error(
+            ctx,
+            git2::Error::new(git2::ErrorCode::Invalid, git2::ErrorClass::Invalid, message),
+        )
+    }
+
+    /// Get the options to create the diff and to find renames and copies in it
+    pub(crate) fn to_git2(&self) -> Result<(git2::DiffOptions, git2::DiffFindOptions)> {
+        for (name, threshold) in [
+            ("Rename threshold", self.rename_threshold),
+            ("Copy threshold", self.copy_threshold),
+        ] {
+            if threshold.is_some_and(|threshold| threshold > 100) {
+                return Err(Self::invalid(
+                    name,
+                    "The similarity must be between 0 and 100 percent",
+                ));
+            }
+        }
+        let max_size = self
+            .max_file_size
+            .map(i64::try_from)
+            .transpose()
+            .map_err(|_| Self::invalid("Max file size", "The size is too large"))?;
+
+        let mut opts = git2::DiffOptions::new();
+        for pathspec in &self.pathspecs {
+            opts.pathspec(pathspec);
+        }
+        if let Some(context_lines) = self.context_lines {
+            opts.context_lines(context_lines);
+        }
+        if let Some(max_size) = max_size {
+            opts.max_size(max_size);
+        }
+        opts.ignore_whitespace(self.whitespace == WhitespaceMode::IgnoreAll)
+            .ignore_whitespace_change(self.whitespace == WhitespaceMode::IgnoreChange)
+            .ignore_whitespace_eol(self.whitespace == WhitespaceMode::IgnoreEol);
+
+        let mut find_opts = git2::DiffFindOptions::new();
+        find_opts.renames(true);
+        if let Some(threshold) = self.rename_threshold {
+            find_opts.rename_threshold(threshold);
+        }
+        if let Some(threshold) = self.copy_threshold {
+            find_opts.copies(true).copy_threshold(threshold);
+        }
+        Ok((opts, find_opts))
+    }
+}
+
+/// Change of a file in a diff
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffFileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    /// The kind of the file changed, e.g. from a regular file to a symbolic link
+    Typechange,
+}
+
+impl From<git2::Delta> for DiffFileStatus {
+    fn from(delta: git2::Delta) -> Self {
+        match delta {
+            git2::Delta::Added => Self::Added,
+            git2::Delta::Deleted => Self::Deleted,
+            git2::Delta::Renamed => Self::Renamed,
+            git2::Delta::Copied => Self::Copied,
+            git2::Delta::Typechange => Self::Typechange,
+            // Unmodified, untracked, ignored and conflicted files do not occur in diffs between trees
+            _ => Self::Modified,
+        }
+    }
+}
+
+/// Kind of a line in a hunk
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiffLineKind {
+    /// Unchanged line
+    Context,
+    /// Line only in the new file
+    Addition,
+    /// Line only in the old file
+    Deletion,
+    /// Marker that the preceding line has no newline at the end of the file
+    NoNewlineAtEof,
+}
+
+/// Line of a hunk
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffLine {
+    /// Kind of the line
+    kind: DiffLineKind,
+    /// Line number in the old file, `None` for additions
+    old_line_no: Option<u32>,
+    /// Line number in the new file, `None` for deletions
+    new_line_no: Option<u32>,
+    /// Content of the line including its newline
+    content: String,
+}
+
+impl DiffLine {
+    pub fn kind(&self) -> DiffLineKind {
+        self.kind
+    }
+
+    pub fn old_line_no(&self) -> Option<u32> {
+        self.old_line_no
+    }
+
+    pub fn new_line_no(&self) -> Option<u32> {
+        self.new_line_no
+    }
+
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+}
+
+/// Hunk of consecutive changed lines with their context
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffHunk {
+    /// Header of the hunk, e.g. `@@ -1,3 +1,4 @@ fn main()`
+    header: String,
+    /// First line of the hunk in the old file
+    old_start: u32,
+    /// Number of lines of the hunk in the old file
+    old_lines: u32,
+    /// First line of the hunk in the new file
+    new_start: u32,
+    /// Number of lines of the hunk in the new file
+    new_lines: u32,
+    /// Lines of the hunk
+    lines: Vec<DiffLine>,
+}
+
+impl DiffHunk {
+    pub fn header(&self) -> &str {
+        &self.header
+    }
+
+    pub fn old_start(&self) -> u32 {
+        self.old_start
+    }
+
+    pub fn old_lines(&self) -> u32 {
+        self.old_lines
+    }
+
+    pub fn new_start(&self) -> u32 {
+        self.new_start
+    }
+
+    pub fn new_lines(&self) -> u32 {
+        self.new_lines
+    }
+
+    pub fn lines(&self) -> &[DiffLine] {
+        &self.lines
+    }
+}
+
+/// Changes of a single file in a diff
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffFile {
+    /// Path of the old file, `None` for added files
+    old_path: Option<String>,
+    /// Path of the new file, `None` for deleted files
+    new_path: Option<String>,
+    /// Kind of the change
+    status: DiffFileStatus,
+    /// Similarity of the old and new file in percent, only set for renamed and copied files
+    similarity: Option<u8>,
+    /// Whether the old or new file is binary, binary files have no hunks
+    is_binary: bool,
+    /// Number of added lines
+    insertions: usize,
+    /// Number of deleted lines
+    deletions: usize,
+    /// Hunks of the changes
+    hunks: Vec<DiffHunk>,
+}
+
+impl DiffFile {
+    pub fn old_path(&self) -> Option<&str> {
+        self.old_path.as_deref()
+    }
+
+    pub fn new_path(&self) -> Option<&str> {
+        self.new_path.as_deref()
+    }
+
+    pub fn status(&self) -> DiffFileStatus {
+        self.status
+    }
+
+    pub fn similarity(&self) -> Option<u8> {
+        self.similarity
+    }
+
+    pub fn is_binary(&self) -> bool {
+        self.is_binary
+    }
+
+    pub fn insertions(&self) -> usize {
+        self.insertions
+    }
+
+    pub fn deletions(&self) -> usize {
+        self.deletions
+    }
+
+    pub fn hunks(&self) -> &[DiffHunk] {
+        &self.hunks
+    }
+
+    fn file_path(file: &git2::DiffFile) -> Option<String> {
+        match file.id().is_zero() {
+            true => None,
+            false => file.path().map(|p| p.to_string_lossy().to_string()),
+        }
+    }
+
+    /// Get the similarity from the `similarity index` line of the header of the patch
+    ///
+    /// git2 does not expose the similarity of a delta.
+    fn similarity_from_patch(patch: &mut git2::Patch) -> Result<Option<u8>> {
+        let mut similarity = None;
+        patch
+            .print(&mut |_, _, line| {
+                if line.origin_value() != git2::DiffLineType::FileHeader {
+                    return true;
+                }
+                let header = String::from_utf8_lossy(line.content());
+                similarity = header.lines().find_map(|line| {
+                    line.strip_prefix("similarity index ")
+                        .and_then(|value| value.trim_end_matches('%').parse().ok())
+                });
+                false
+            })
+            // Stopping the iteration early is reported as an error
+            .or_else(|e| match e.code() {
+                git2::ErrorCode::User => Ok(()),
+                _ => Err(e),
+            })
+            .map_err(|e| error::Error::from_ctx_and_error("Error reading patch header", e))?;
+        Ok(similarity)
+    }
+
+    fn try_from_diff_and_index(diff: &git2::Diff, index: usize) -> Result<Self> {
+        let ctx = || format!("Error creating patch of file {index} of the diff");
+        let patch = git2::Patch::from_diff(diff, index)
+            .map_err(|e| error::Error::from_ctx_and_error(ctx(), e))?;
+        // The delta of the patch has binary flags set once the content was loaded
+        let delta = match &patch {
+            Some(patch) => patch.delta(),
+            None => diff.get_delta(index).ok_or_else(|| {
+                error::Error::from_ctx_and_error(
+                    ctx(),
+                    git2::Error::from_str("The diff has no such file"),
+                )
+            })?,
+        };
+        let status = DiffFileStatus::from(delta.status());
+        let old_path = Self::file_path(&delta.old_file());
+        let new_path = Self::file_path(&delta.new_file());
+        let is_binary = delta.flags().is_binary();
+
+        let Some(mut patch) = patch else {
+            return Ok(Self {
+                old_path,
+                new_path,
+                status,
+                similarity: None,
+                is_binary,
+                insertions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+            });
+        };
+        let similarity = match status {
+            DiffFileStatus::Renamed | DiffFileStatus::Copied => {
+                Self::similarity_from_patch(&mut patch)?
+            }
+            _ => None,
+        };
+        let (_, insertions, deletions) = patch
+            .line_stats()
+            .map_err(|e| error::Error::from_ctx_and_error(ctx(), e))?;
+
+        let mut hunks = Vec::with_capacity(patch.num_hunks());
+        for hunk_index in 0..patch.num_hunks() {
+            let (hunk, num_lines) = patch
+                .hunk(hunk_index)
+                .map_err(|e| error::Error::from_ctx_and_error(ctx(), e))?;
+            let mut lines = Vec::with_capacity(num_lines);
+            for line_index in 0..num_lines {
+                let line = patch
+                    .line_in_hunk(hunk_index, line_index)
+                    .map_err(|e| error::Error::from_ctx_and_error(ctx(), e))?;
+                let kind = match line.origin_value() {
+                    git2::DiffLineType::Addition => DiffLineKind::Addition,
+                    git2::DiffLineType::Deletion => DiffLineKind::Deletion,
+                    git2::DiffLineType::ContextEOFNL
+                    | git2::DiffLineType::AddEOFNL
+                    | git2::DiffLineType::DeleteEOFNL => DiffLineKind::NoNewlineAtEof,
+                    _ => DiffLineKind::Context,
+                };
+                lines.push(DiffLine {
+                    kind,
+                    old_line_no: line.old_lineno(),
+                    new_line_no: line.new_lineno(),
+                    content: String::from_utf8_lossy(line.content()).to_string(),
+                });
+            }
+            hunks.push(DiffHunk {
+                header: String::from_utf8_lossy(hunk.header())
+                    .trim_end()
+                    .to_string(),
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines,
+            });
+        }
+
+        Ok(Self {
+            old_path,
+            new_path,
+            status,
+            similarity,
+            is_binary,
+            insertions,
+            deletions,
+            hunks,
+        })
+    }
+}
+
 type Path = String;
 type FileContent = String;
 
@@ -67,6 +481,8 @@ pub struct Diff {
     stats: DiffStats,
     /// Map of old source paths to the old content
     old_sources: FilesContent,
+    /// Changes of the files, the same changes as in the patch
+    files: Vec<DiffFile>,
 }
 
 impl Diff {
@@ -82,6 +498,10 @@ impl Diff {
         &self.old_sources
     }
 
+    pub fn files(&self) -> &[DiffFile] {
+        &self.files
+    }
+
     pub fn try_from_repo_and_diff(repo: &git2::Repository, diff: &git2::Diff) -> Result<Self> {
         let mut patch_output = String::new();
         let mut total_num_lines: usize = 0;
@@ -131,6 +551,10 @@ impl Diff {
         })
         .map_err(|e| error::Error::from_ctx_and_error("Error creating patch", e))?;
 
+        let files = (0..diff.deltas().len())
+            .map(|index| DiffFile::try_from_diff_and_index(diff, index))
+            .collect::<Result<Vec<_>>>()?;
+
         Ok(Self {
             patch: patch_output,
             stats: DiffStats::from_stats_and_total_old_num_lines(
@@ -140,6 +564,7 @@ impl Diff {
                 total_num_lines,
             ),
             old_sources: old_files,
+            files,
         })
     }
 }
@@ -30,6 +30,34 @@ pub enum Error {
     NotFound(ErrorCtx),
     #[error("Invalid {0}")]
     Invalid(ErrorCtx),
+    #[error("Conflict: {0}")]
+    Conflict(ErrorCtx),
+    #[error("Checkout of '{rev}' would overwrite changes to {}", paths.join(", "))]
+    CheckoutConflict {
+        /// Revision to check out
+        rev: String,
+        /// Paths with uncommitted changes blocking the checkout
+        paths: Vec<String>,
+    },
+    #[error("Stash '{id}' was restored with conflicts in {}", paths.join(", "))]
+    StashConflict {
+        /// ID of the stash commit
+        id: String,
+        /// Paths with conflicts
+        paths: Vec<String>,
+    },
+    #[error(
+        "{error}, the stashed changes could not be restored and are kept in stash '{stash}': {restore_error}"
+    )]
+    StashNotRestored {
+        /// ID of the stash commit with the uncommitted changes
+        stash: String,
+        /// Error which failed the checkout
+        #[source]
+        error: Box<Error>,
+        /// Error which failed restoring the stash
+        restore_error: Box<Error>,
+    },
 }
 
 impl Error {
@@ -37,6 +65,10 @@ impl Error {
         match e.code() {
             git2::ErrorCode::NotFound => Error::NotFound(ErrorCtx::new(ctx, e)),
             git2::ErrorCode::Invalid => Error::Invalid(ErrorCtx::new(ctx, e)),
+            git2::ErrorCode::Exists
+            | git2::ErrorCode::Locked
+            | git2::ErrorCode::Conflict
+            | git2::ErrorCode::MergeConflict => Error::Conflict(ErrorCtx::new(ctx, e)),
             _ => Error::Generic(ErrorCtx::new(ctx, e)),
         }
     }
@@ -0,0 +1,253 @@
+use crate::{Commit, Result, error::Error, utils};
+
+/// Version of a file on a reference, i.e. a commit which changed the file
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileVersion {
+    /// Commit which changed the file
+    commit: Commit,
+    /// ID of the blob of the file in the commit
+    blob_id: String,
+}
+
+impl FileVersion {
+    /// Get the commit which changed the file
+    pub fn commit(&self) -> &Commit {
+        &self.commit
+    }
+
+    /// Get the ID of the blob of the file in the commit
+    pub fn blob_id(&self) -> &str {
+        &self.blob_id
+    }
+}
+
+/// Get the ID of the entry at `path` in `tree`, `None` if it does not exist
+fn entry_id(tree: &git2::Tree, path: &str) -> Option<git2::Oid> {
+    tree.get_path(std::path::Path::new(path))
+        .ok()
+        .map(|entry| entry.id())
+}
+
+/// Get the tip of a reference, `None` if the reference does not exist
+fn reference_tip<'repo>(
+    repo: &'repo git2::Repository,
+    reference: &str,
+) -> Result<Option<git2::Commit<'repo>>> {
+    match repo.find_reference(reference) {
+        Ok(r) => r
+            .peel_to_commit()
+            .map(Some)
+            .map_err(|e| Error::from_ctx_and_error(format!("Commit of '{reference}'"), e)),
+        Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+        Err(e) => Err(Error::from_ctx_and_error(
+            format!("Reference '{reference}'"),
+            e,
+        )),
+    }
+}
+
+/// Commit `content` as the file `file_name` on top of `reference`, see
+/// [`crate::Repository::commit_file_to_reference`]
+pub(crate) fn commit_file_to_reference(
+    repo: &git2::Repository,
+    reference: &str,
+    file_name: &str,
+    content: &[u8],
+    message: &str,
+) -> Result<Option<Commit>> {
+    if file_name.is_empty() || file_name.contains('/') {
+        return Err(Error::from_ctx_and_error(
+            format!("File name '{file_name}'"),
+            git2::Error::new(
+                git2::ErrorCode::Invalid,
+                git2::ErrorClass::Invalid,
+                "Only files at the root of the tree can be committed",
+            ),
+        ));
+    }
+
+    let parent = reference_tip(repo, reference)?;
+    let parent_tree = parent
+        .as_ref()
+        .map(|c| c.tree())
+        .transpose()
+        .map_err(|e| Error::from_ctx_and_error(format!("Tree of '{reference}'"), e))?;
+
+    let blob_id = repo
+        .blob(content)
+        .map_err(|e| Error::from_ctx_and_error(format!("Blob for '{file_name}'"), e))?;
+    if parent_tree.as_ref().and_then(|t| entry_id(t, file_name)) == Some(blob_id) {
+        return Ok(None);
+    }
+
+    let mut builder = repo
+        .treebuilder(parent_tree.as_ref())
+        .map_err(|e| Error::from_ctx_and_error("Failed to create tree builder", e))?;
+    builder
+        .insert(file_name, blob_id, git2::FileMode::Blob.into())
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to insert '{file_name}'"), e))?;
+    let tree_id = builder
+        .write()
+        .map_err(|e| Error::from_ctx_and_error("Failed to write tree", e))?;
+    let tree = repo
+        .find_tree(tree_id)
+        .map_err(|e| Error::from_ctx_and_error(format!("Tree '{tree_id}'"), e))?;
+
+    // Repositories without a configured user can still record versions
+    let signature = repo
+        .signature()
+        .or_else(|_| git2::Signature::now("debug-flow", "debug-flow@localhost"))
+        .map_err(|e| Error::from_ctx_and_error("Failed to create signature", e))?;
+
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let commit_id = repo
+        .commit(
+            Some(reference),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to commit to '{reference}'"), e))?;
+    let commit = utils::get_commit_for_oid(repo, commit_id)?;
+    Ok(Some((&commit).into()))
+}
+
+/// Get the versions of the file at `path` reachable from `rev`, see [`crate::Repository::file_versions`]
+pub(crate) fn file_versions(
+    repo: &git2::Repository,
+    rev: &str,
+    path: &str,
+) -> Result<Vec<FileVersion>> {
+    let revwalk = utils::revwalk_for_range(repo, None, Some(rev))?;
+    let mut versions = Vec::new();
+    for oid in revwalk {
+        let oid = oid.map_err(|e| Error::from_ctx_and_error("Failed to get oid object", e))?;
+        let commit = utils::get_commit_for_oid(repo, oid)?;
+        let tree = commit
+            .tree()
+            .map_err(|e| Error::from_ctx_and_error(format!("Tree of commit '{oid}'"), e))?;
+        let Some(blob_id) = entry_id(&tree, path) else {
+            continue;
+        };
+        let parent_blob_id = commit
+            .parent(0)
+            .ok()
+            .and_then(|parent| parent.tree().ok())
+            .and_then(|tree| entry_id(&tree, path));
+        if parent_blob_id != Some(blob_id) {
+            versions.push(FileVersion {
+                commit: (&commit).into(),
+                blob_id: blob_id.to_string(),
+            });
+        }
+    }
+    Ok(versions)
+}
+
+/// Read the content of the file at `path` in `rev`, see [`crate::Repository::read_file`]
+pub(crate) fn read_file(repo: &git2::Repository, rev: &str, path: &str) -> Result<Vec<u8>> {
+    let tree = utils::get_tree_for_revision(repo, rev)?;
+    let entry = tree
+        .get_path(std::path::Path::new(path))
+        .map_err(|e| Error::from_ctx_and_error(format!("File '{path}' in '{rev}'"), e))?;
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|e| Error::from_ctx_and_error(format!("Blob of '{path}' in '{rev}'"), e))?;
+    Ok(blob.content().to_vec())
+}
+
+/// Filter for commits touching a path, see [`crate::Repository::iter_commits_for_path`]
+///
+/// Commits are expected from newest to oldest. When following renames, the filtered path changes to the
+/// old path of the file once the commit which renamed the file was seen, like `git log --follow`.
+pub(crate) struct PathFilter {
+    path: String,
+    follow_renames: bool,
+}
+
+impl PathFilter {
+    pub(crate) fn new(path: &str, follow_renames: bool) -> Self {
+        Self {
+            path: path.trim_matches('/').to_string(),
+            follow_renames,
+        }
+    }
+
+    /// Get the ID of the tree or blob at the filtered path in `commit`, `None` if it does not exist
+    fn path_id(&self, commit: &git2::Commit) -> Result<Option<git2::Oid>> {
+        if self.path.is_empty() {
+            return Ok(Some(commit.tree_id()));
+        }
+        let tree = commit.tree().map_err(|e| {
+            Error::from_ctx_and_error(format!("Tree of commit '{}'", commit.id()), e)
+        })?;
+        Ok(entry_id(&tree, &self.path))
+    }
+
+    /// Get the path the file at the filtered path was renamed from in `commit`
+    fn renamed_from(
+        &self,
+        repo: &git2::Repository,
+        commit: &git2::Commit,
+    ) -> Result<Option<String>> {
+        let ctx = || format!("Renames in commit '{}'", commit.id());
+        let Ok(parent) = commit.parent(0) else {
+            return Ok(None);
+        };
+        let old_tree = parent
+            .tree()
+            .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+        let new_tree = commit
+            .tree()
+            .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+        let mut diff = repo
+            .diff_tree_to_tree(Some(&old_tree), Some(&new_tree), None)
+            .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+        diff.find_similar(Some(git2::DiffFindOptions::new().renames(true)))
+            .map_err(|e| Error::from_ctx_and_error(ctx(), e))?;
+        let path = std::path::Path::new(&self.path);
+        Ok(diff
+            .deltas()
+            .filter(|delta| delta.status() == git2::Delta::Renamed)
+            .find(|delta| delta.new_file().path() == Some(path))
+            .and_then(|delta| delta.old_file().path())
+            .map(|old_path| old_path.to_string_lossy().to_string()))
+    }
+
+    /// Whether `commit` changed the filtered path
+    ///
+    /// Merge commits only touch the path if it differs from all parents, i.e. the merge changed it.
+    pub(crate) fn touches(&mut self, repo: &git2::Repository, oid: git2::Oid) -> Result<bool> {
+        let commit = utils::get_commit_for_oid(repo, oid)?;
+        let id = self.path_id(&commit)?;
+        let mut parent_ids = Vec::new();
+        for parent in commit.parents() {
+            parent_ids.push(self.path_id(&parent)?);
+        }
+        let touches = match parent_ids.is_empty() {
+            true => id.is_some(),
+            false => parent_ids.iter().all(|parent_id| *parent_id != id),
+        };
+        // A file without a path in the first parent was added or renamed
+        let added = id.is_some()
+            && parent_ids
+                .first()
+                .is_some_and(|parent_id| parent_id.is_none());
+        if touches
+            && added
+            && self.follow_renames
+            && let Some(old_path) = self.renamed_from(repo, &commit)?
+        {
+            self.path = old_path;
+        }
+        Ok(touches)
+    }
+}
@@ -1,19 +1,33 @@
+pub mod bisect;
+pub mod blame;
 pub mod branch;
+pub mod checkout;
 pub mod commit;
 pub mod diff;
 pub mod error;
+pub mod history;
 pub mod reference;
 pub mod repository;
 pub mod status;
 pub mod tag;
+pub mod tree;
 pub mod utils;
+pub mod worktree;
 
+pub use bisect::{Bisect, BisectStep, BisectVerdict};
+pub use blame::{BlameHunk, BlameOptions};
 pub use branch::Branch;
-pub use commit::{Commit, CommitProperties, CommitWithReferences};
-pub use diff::Diff;
+pub use checkout::{Checkout, CheckoutMode, Stash};
+pub use commit::{Commit, CommitProperties, CommitWithReferences, Signature};
+pub use diff::{
+    Diff, DiffFile, DiffFileStatus, DiffHunk, DiffLine, DiffLineKind, DiffOptions, WhitespaceMode,
+};
+pub use history::FileVersion;
 pub use reference::{ReferenceKind, ReferenceMetadata, ResolvedReference};
 pub use repository::{ReferenceKindFilter, Repository};
 pub use status::Status;
 pub use tag::TaggedCommit;
+pub use tree::{Blob, TreeEntry, TreeEntryKind};
+pub use worktree::Worktree;
 
 type Result<T> = std::result::Result<T, error::Error>;
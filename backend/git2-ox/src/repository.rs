@@ -1,11 +1,19 @@
 use git2::IntoCString;
 
+use crate::bisect::{self, Bisect, BisectStep, BisectVerdict};
+use crate::blame::{self, BlameHunk, BlameOptions};
+use crate::checkout::{self, Checkout, CheckoutMode};
 use crate::commit::{CommitProperties, CommitWithReferences};
 use crate::error::Error;
+use crate::history::{self, FileVersion};
 use crate::reference::ReferencesMap;
+use crate::tree::{self, Blob, TreeEntry};
+use crate::worktree;
 use crate::{
-    Branch, Commit, Diff, ReferenceKind, ResolvedReference, Result, Status, TaggedCommit, utils,
+    Branch, Commit, Diff, DiffOptions, ReferenceKind, ResolvedReference, Result, Signature, Status,
+    TaggedCommit, Worktree, utils,
 };
+use std::ops::RangeInclusive;
 use std::path::Path;
 
 pub struct Repository {
@@ -69,6 +77,45 @@ impl Repository {
         }))
     }
 
+    /// Returns an iterator over the Commits from `head_rev` to `base_rev` which changed a path, like
+    /// `git log -- <path>`
+    ///
+    /// * `base_rev` - Base revision until which to iterate. Iterating to initial commit if set to `None`
+    /// * `head_rev` - Head revision from which to iterate. Iterating from current `HEAD` if set to `None`
+    /// * `path` - Path of a file or directory relative to the repository root
+    /// * `follow_renames` - Continue with the old path of a renamed file, like `git log --follow -- <path>`
+    pub fn iter_commits_for_path(
+        &self,
+        base_rev: Option<&str>,
+        head_rev: Option<&str>,
+        path: &str,
+        follow_renames: bool,
+    ) -> Result<impl Iterator<Item = Result<CommitWithReferences>>> {
+        let revwalk = utils::revwalk_for_range(&self.repo, base_rev, head_rev)?;
+        let ref_map = ReferencesMap::try_from(&self.repo)?;
+        let mut filter = history::PathFilter::new(path, follow_renames);
+        Ok(revwalk.filter_map(move |oid_result| {
+            let oid = match oid_result {
+                Ok(oid) => oid,
+                Err(e) => {
+                    return Some(Err(Error::from_ctx_and_error(
+                        "Failed to get oid object",
+                        e,
+                    )));
+                }
+            };
+            match filter.touches(&self.repo, oid) {
+                Ok(true) => Some(CommitWithReferences::try_from_oid_and_references(
+                    &self.repo,
+                    oid,
+                    ref_map.get_references_for_commit_oid(oid),
+                )),
+                Ok(false) => None,
+                Err(e) => Some(Err(e)),
+            }
+        }))
+    }
+
     /// Get a commit for a revision
     ///
     /// * `rev` - Revision to get the commit for. This can be the short hash, full hash, a tag, or any other
@@ -82,45 +129,88 @@ impl Repository {
         )
     }
 
+    /// Check if the commit of a revision is reachable from `HEAD`, a branch, a remote-tracking branch or a tag
+    ///
+    /// Commits which are only reachable from other references, e.g. the reflog after a rebase, are removed
+    /// by the next garbage collection.
+    ///
+    /// * `rev` - Revision of the commit, see [`Self::get_commit_for_revision`]
+    pub fn is_reachable(&self, rev: &str) -> Result<bool> {
+        let oid = self
+            .repo
+            .revparse_single(rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| Error::from_ctx_and_error(format!("Failed to parse revision '{rev}'"), e))?
+            .id();
+
+        let references = self
+            .repo
+            .references()
+            .map_err(|e| Error::from_ctx_and_error("Failed to get references", e))?;
+        let tips = references
+            .filter_map(std::result::Result::ok)
+            .filter(|r| r.is_branch() || r.is_remote() || r.is_tag())
+            .chain(self.repo.head().ok())
+            .filter_map(|r| r.peel_to_commit().ok().map(|c| c.id()));
+        for tip in tips {
+            let is_descendant = self.repo.graph_descendant_of(tip, oid).map_err(|e| {
+                Error::from_ctx_and_error(format!("Failed to check ancestry of '{rev}'"), e)
+            })?;
+            if tip == oid || is_descendant {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     /// Checkout a revision
     ///
+    /// Uncommitted changes are kept, the checkout fails with [`Error::CheckoutConflict`] listing the paths if
+    /// they would be overwritten.
+    ///
     /// * `rev` - Revision to checkout. This can be the short hash, full hash, a tag, or any other
     ///   reference such as `HEAD`, a branch name or a tag name
     pub fn checkout_revision(&self, rev: &str) -> Result<CommitWithReferences> {
-        let (object, reference) = self.repo.revparse_ext(rev).map_err(|e| {
-            Error::from_ctx_and_error(format!("Failed to parse revision '{rev}'"), e)
-        })?;
-
-        self.repo.checkout_tree(&object, None).map_err(|e| {
-            Error::from_ctx_and_error(format!("Failed to checkout revision '{rev}'"), e)
-        })?;
-
-        match reference {
-            // gref is an actual reference like branches or tags
-            Some(gref) => self.repo.set_head(gref.name().unwrap_or_default()),
-            // this is a commit, not a reference
-            None => self.repo.set_head_detached(object.id()),
-        }
-        .map_err(|e| {
-            Error::from_ctx_and_error(format!("Failed to set head to revision '{rev}'"), e)
-        })?;
+        let id = checkout::checkout_revision(&self.repo, rev)?;
+        self.get_commit_for_revision(&id.to_string())
+    }
 
-        let ref_map = ReferencesMap::try_from(&self.repo)?;
+    /// Checkout a revision handling uncommitted changes according to `mode`
+    ///
+    /// With [`CheckoutMode::Stash`] the changes to tracked files are stashed first, the stash is part of the
+    /// result and can be restored with [`Self::restore_stash`]. If untracked files still block the checkout,
+    /// the stash is restored right away.
+    ///
+    /// * `rev` - Revision to checkout. This can be the short hash, full hash, a tag, or any other
+    ///   reference such as `HEAD`, a branch name or a tag name
+    /// * `mode` - How uncommitted changes are handled
+    pub fn checkout_revision_with_mode(
+        &mut self,
+        rev: &str,
+        mode: CheckoutMode,
+    ) -> Result<Checkout> {
+        let (id, stash) = checkout::checkout_revision_with_mode(&mut self.repo, rev, mode)?;
+        Ok(Checkout::new(
+            self.get_commit_for_revision(&id.to_string())?,
+            stash,
+        ))
+    }
 
-        // self.repo
-        //     .set_head(obj.Ok
-        //     .map_err(|e| Error::from_ctx_and_error(format!("Failed to set head to {rev}"), e))?;
-        let commit = Commit::try_from_revision(&self.repo, rev)?;
-        CommitWithReferences::from_commit_and_references(
-            &commit,
-            ref_map.get_references_for_commit_id(commit.id()),
-        )
+    /// Apply a stash created by [`Self::checkout_revision_with_mode`] to the working directory and drop it
+    ///
+    /// If the stash conflicts with the checked out commit, the conflicts are written to the working directory
+    /// and the index, the stash is kept and [`Error::StashConflict`] lists the paths.
+    ///
+    /// * `id` - ID of the stash commit
+    pub fn restore_stash(&mut self, id: &str) -> Result<()> {
+        checkout::restore_stash(&mut self.repo, id)
     }
 
     fn git2_diff_for_revisions(
         &self,
         base_rev: Option<&str>,
         head_rev: Option<&str>,
+        options: &DiffOptions,
     ) -> Result<git2::Diff<'_>> {
         let head = head_rev.unwrap_or("HEAD");
         let tree = utils::get_tree_for_revision(&self.repo, head)?;
@@ -133,24 +223,32 @@ impl Repository {
             None => None,
         };
 
+        let (mut diff_opts, mut find_opts) = options.to_git2()?;
         let mut diff = self
             .repo
-            .diff_tree_to_tree(base_tree.as_ref(), Some(&tree), None)
+            .diff_tree_to_tree(base_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
             .map_err(|e| {
                 Error::from_ctx_and_error(format!("Failed to diff tree {base_rev:?} to {head}"), e)
             })?;
 
-        // Enable rename detection with DiffFindOptions
-        let mut find_opts = git2::DiffFindOptions::new();
-        find_opts.renames(true);
         // Transform a diff marking file renames, copies, etc.
         diff.find_similar(Some(&mut find_opts))
             .map_err(|e| Error::from_ctx_and_error("Failed to find similar files in diff", e))?;
         Ok(diff)
     }
 
-    pub fn diff(&self, base_rev: Option<&str>, head_rev: Option<&str>) -> Result<Diff> {
-        let diff = self.git2_diff_for_revisions(base_rev, head_rev)?;
+    /// Get the diff between two revisions, like `git diff base_rev..head_rev`
+    ///
+    /// * `base_rev` - Base revision of the diff. Diffing against an empty tree if set to `None`
+    /// * `head_rev` - Head revision of the diff. Diffing the current `HEAD` if set to `None`
+    /// * `options` - Files to diff, context lines, whitespace handling and rename detection
+    pub fn diff(
+        &self,
+        base_rev: Option<&str>,
+        head_rev: Option<&str>,
+        options: &DiffOptions,
+    ) -> Result<Diff> {
+        let diff = self.git2_diff_for_revisions(base_rev, head_rev, options)?;
         Diff::try_from_repo_and_diff(self.repo(), &diff)
     }
 
@@ -222,6 +320,223 @@ impl Repository {
             .filter_map(move |r| ResolvedReference::try_from(r).ok()))
     }
 
+    /// Get the signature of the configured user, taken from `user.name` and `user.email` in the git config
+    pub fn signature(&self) -> Result<Signature> {
+        let signature = self
+            .repo()
+            .signature()
+            .map_err(|e| Error::from_ctx_and_error("Failed to get the signature of the user", e))?;
+        Ok(signature.into())
+    }
+
+    /// Set a string entry in the configuration of the repository, e.g. `.git/config`
+    ///
+    /// * `name` - Name of the entry, e.g. `merge.custom.driver`
+    /// * `value` - New value of the entry
+    pub fn set_config(&self, name: &str, value: &str) -> Result<()> {
+        self.repo()
+            .config()
+            .and_then(|config| config.open_level(git2::ConfigLevel::Local))
+            .and_then(|mut config| config.set_str(name, value))
+            .map_err(|e| Error::from_ctx_and_error(format!("Failed to set config '{name}'"), e))
+    }
+
+    /// Make the references below `prefix` point to exactly the commits of `targets`
+    ///
+    /// References in `targets` are created or moved, references below `prefix` which are not in `targets`
+    /// are deleted. This allows keeping commits alive which are not on any branch, e.g. in hidden references
+    /// like `refs/notes/pins/<name>`.
+    ///
+    /// * `prefix` - Full name of the namespace, e.g. `refs/notes/pins`
+    /// * `targets` - Pairs of the name of the reference relative to `prefix` and the revision of its commit
+    /// * `message` - Message for the reflog
+    pub fn sync_references<I, N, R>(&self, prefix: &str, targets: I, message: &str) -> Result<()>
+    where
+        I: IntoIterator<Item = (N, R)>,
+        N: AsRef<str>,
+        R: AsRef<str>,
+    {
+        let prefix = prefix.trim_end_matches('/');
+        let mut names = std::collections::HashSet::new();
+        for (name, rev) in targets {
+            let name = format!("{prefix}/{}", name.as_ref());
+            let rev = rev.as_ref();
+            let oid = self
+                .repo
+                .revparse_single(rev)
+                .and_then(|object| object.peel_to_commit())
+                .map_err(|e| {
+                    Error::from_ctx_and_error(format!("Failed to parse revision '{rev}'"), e)
+                })?
+                .id();
+            let current = self.repo.refname_to_id(&name).ok();
+            if current != Some(oid) {
+                self.repo
+                    .reference(&name, oid, true, message)
+                    .map_err(|e| Error::from_ctx_and_error(format!("Reference '{name}'"), e))?;
+            }
+            names.insert(name);
+        }
+
+        let stale: Vec<String> = self
+            .repo
+            .references()
+            .map_err(|e| Error::from_ctx_and_error("Failed to get references", e))?
+            .filter_map(std::result::Result::ok)
+            .filter_map(|r| r.name().map(str::to_string))
+            .filter(|name| name.starts_with(&format!("{prefix}/")) && !names.contains(name))
+            .collect();
+        for name in stale {
+            self.repo
+                .find_reference(&name)
+                .and_then(|mut r| r.delete())
+                .map_err(|e| {
+                    Error::from_ctx_and_error(format!("Failed to delete reference '{name}'"), e)
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Commit `content` as the file `file_name` on top of `reference` without touching the index, the
+    /// working directory or `HEAD`
+    ///
+    /// The tree of the new commit is the tree of the current tip of `reference` with the file replaced, the
+    /// reference is created if it does not exist. Returns `None` if the file has this content already.
+    ///
+    /// * `reference` - Full name of the reference, e.g. `refs/notes/history`
+    /// * `file_name` - Name of the file at the root of the tree
+    /// * `content` - New content of the file
+    /// * `message` - Commit message
+    pub fn commit_file_to_reference(
+        &self,
+        reference: &str,
+        file_name: &str,
+        content: &[u8],
+        message: &str,
+    ) -> Result<Option<Commit>> {
+        history::commit_file_to_reference(&self.repo, reference, file_name, content, message)
+    }
+
+    /// Get the commits reachable from `rev` which changed the file at `path`, newest first
+    ///
+    /// * `rev` - Revision to start from
+    /// * `path` - Path of the file relative to the root of the tree
+    pub fn file_versions(&self, rev: &str, path: &str) -> Result<Vec<FileVersion>> {
+        history::file_versions(&self.repo, rev, path)
+    }
+
+    /// Get the commits which last changed the lines of a file, like `git blame`
+    ///
+    /// Consecutive lines changed in the same commit are grouped into one hunk, hunks are ordered by line.
+    ///
+    /// * `path` - Path of the file relative to the root of the tree
+    /// * `rev` - Revision of the file to blame
+    /// * `line_range` - Lines to blame, starting at 1. All lines are blamed if set to `None`
+    /// * `options` - Whether renames are followed and whitespace changes are ignored
+    pub fn blame(
+        &self,
+        path: &str,
+        rev: &str,
+        line_range: Option<RangeInclusive<usize>>,
+        options: &BlameOptions,
+    ) -> Result<Vec<BlameHunk>> {
+        blame::blame(&self.repo, path, rev, line_range, options)
+    }
+
+    /// Read the content of the file at `path` in the tree of `rev`
+    pub fn read_file(&self, rev: &str, path: &str) -> Result<Vec<u8>> {
+        history::read_file(&self.repo, rev, path)
+    }
+
+    /// List the entries of a directory at a revision, like `git ls-tree`
+    ///
+    /// * `rev` - Revision of the tree
+    /// * `path` - Path of the directory relative to the root of the tree, the root is listed if it is empty
+    pub fn list_tree(&self, rev: &str, path: &str) -> Result<Vec<TreeEntry>> {
+        tree::list_tree(&self.repo, rev, path)
+    }
+
+    /// Read a file at a revision, like `git show <rev>:<path>`
+    ///
+    /// The content is only returned for text files. Symbolic links are not resolved, their target path is
+    /// returned instead.
+    ///
+    /// * `rev` - Revision of the file
+    /// * `path` - Path of the file relative to the root of the tree
+    pub fn read_blob(&self, rev: &str, path: &str) -> Result<Blob> {
+        tree::read_blob(&self.repo, rev, path)
+    }
+
+    /// Check out a revision with a detached `HEAD` in a linked worktree
+    ///
+    /// The worktree is added if it does not exist yet, an existing worktree is reused and its changes are
+    /// discarded. Neither the branches nor the working directory of the repository are changed.
+    ///
+    /// * `name` - Name of the worktree in the repository
+    /// * `path` - Working directory of the worktree
+    /// * `rev` - Revision to check out
+    pub fn checkout_worktree(&self, name: &str, path: &Path, rev: &str) -> Result<Commit> {
+        worktree::checkout_worktree(&self.repo, name, path, rev)
+    }
+
+    /// Get the linked worktrees of the repository sorted by name
+    pub fn worktrees(&self) -> Result<Vec<Worktree>> {
+        worktree::worktrees(&self.repo)
+    }
+
+    /// Add a linked worktree with a detached `HEAD` at `rev`
+    ///
+    /// Fails if a worktree named `name` exists already, see [`Self::checkout_worktree`] to reuse worktrees.
+    ///
+    /// * `name` - Name of the worktree in the repository
+    /// * `path` - Working directory of the worktree, it must not exist or be empty
+    /// * `rev` - Revision to check out
+    pub fn create_worktree(&self, name: &str, path: &Path, rev: &str) -> Result<Worktree> {
+        worktree::create_worktree(&self.repo, name, path, rev)
+    }
+
+    /// Remove a linked worktree and its working directory, locked worktrees are not removed
+    pub fn prune_worktree(&self, name: &str) -> Result<()> {
+        worktree::prune_worktree(&self.repo, name)
+    }
+
+    /// Lock a linked worktree so it is not pruned
+    pub fn lock_worktree(&self, name: &str, reason: Option<&str>) -> Result<Worktree> {
+        worktree::lock_worktree(&self.repo, name, true, reason)
+    }
+
+    /// Unlock a linked worktree
+    pub fn unlock_worktree(&self, name: &str) -> Result<Worktree> {
+        worktree::lock_worktree(&self.repo, name, false, None)
+    }
+
+    /// Start a bisection to find the first commit between `good` and `bad` which has a problem
+    ///
+    /// * `bad` - Revision which has the problem
+    /// * `good` - Revisions without the problem, at least one is required
+    pub fn bisect_start<S: AsRef<str>>(&self, bad: &str, good: &[S]) -> Result<Bisect> {
+        bisect::start(&self.repo, bad, good)
+    }
+
+    /// Mark a tested revision in a bisection and get its commit
+    ///
+    /// Marking a revision as bad makes it the newest known bad commit.
+    pub fn bisect_mark(
+        &self,
+        bisect: &mut Bisect,
+        rev: &str,
+        verdict: BisectVerdict,
+    ) -> Result<Commit> {
+        bisect::mark(&self.repo, bisect, rev, verdict)
+    }
+
+    /// Get the next commit to test in a bisection or the first bad commit once it is found
+    ///
+    /// Fails if the bad commit is an ancestor of a good commit.
+    pub fn bisect_step(&self, bisect: &Bisect) -> Result<BisectStep> {
+        bisect::step(&self.repo, bisect)
+    }
+
     /// Get the status of the repository
     pub fn status(&self) -> Result<Status> {
         Status::try_from_repository(self)
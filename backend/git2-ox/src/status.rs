@@ -1,4 +1,4 @@
-use crate::{CommitWithReferences, Repository, Result, error::Error};
+use crate::{CommitWithReferences, Repository, Result, Worktree, error::Error};
 
 type Files = Vec<String>;
 
@@ -64,9 +64,16 @@ pub struct Status {
     worktree: TreeStatus,
     /// Paths with conflicts
     conflicts: Files,
+    /// Linked worktrees of the repository
+    linked_worktrees: Vec<Worktree>,
 }
 
 impl Status {
+    /// Get the name of the current branch, `None` if the head is detached
+    pub fn current_branch(&self) -> Option<&str> {
+        self.current_branch.as_deref()
+    }
+
     pub fn head(&self) -> &CommitWithReferences {
         &self.head
     }
@@ -91,6 +98,10 @@ impl Status {
         &self.conflicts
     }
 
+    pub fn linked_worktrees(&self) -> &[Worktree] {
+        &self.linked_worktrees
+    }
+
     pub fn try_from_repository(repo: &Repository) -> Result<Self> {
         let head = repo.get_commit_for_revision("HEAD")?;
 
@@ -148,6 +159,7 @@ impl Status {
             index: index_status,
             worktree: worktree_status,
             conflicts,
+            linked_worktrees: repo.worktrees()?,
         })
     }
 }
@@ -0,0 +1,252 @@
+use crate::{Result, error::Error, utils};
+use std::path::Path;
+
+/// Kind of an entry of a tree
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "lowercase")
+)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeEntryKind {
+    /// Regular or executable file
+    File,
+    /// Directory
+    Directory,
+    /// Symbolic link, its blob contains the target path
+    Symlink,
+    /// Commit of a submodule, it is not part of the repository
+    Submodule,
+}
+
+impl TreeEntryKind {
+    fn from_filemode(mode: i32) -> Self {
+        match mode {
+            mode if mode == i32::from(git2::FileMode::Tree) => Self::Directory,
+            mode if mode == i32::from(git2::FileMode::Link) => Self::Symlink,
+            mode if mode == i32::from(git2::FileMode::Commit) => Self::Submodule,
+            _ => Self::File,
+        }
+    }
+}
+
+/// Entry of a tree, i.e. a file or directory at a revision
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeEntry {
+    /// Name of the entry
+    name: String,
+    /// Path of the entry relative to the repository root
+    path: String,
+    /// Kind of the entry
+    kind: TreeEntryKind,
+    /// Git file mode of the entry, e.g. `0o100644` for a regular file or `0o100755` for an executable file
+    mode: i32,
+    /// ID of the blob, tree or submodule commit of the entry
+    id: String,
+    /// Size of the blob in bytes, only set for files and symbolic links
+    size: Option<usize>,
+}
+
+impl TreeEntry {
+    /// Get the name of the entry
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the path of the entry relative to the repository root
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the kind of the entry
+    pub fn kind(&self) -> TreeEntryKind {
+        self.kind
+    }
+
+    /// Get the git file mode of the entry
+    pub fn mode(&self) -> i32 {
+        self.mode
+    }
+
+    /// Whether the entry is an executable file
+    pub fn is_executable(&self) -> bool {
+        self.mode == i32::from(git2::FileMode::BlobExecutable)
+    }
+
+    /// Get the ID of the blob, tree or submodule commit of the entry
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the size of the blob in bytes, `None` for directories and submodules
+    pub fn size(&self) -> Option<usize> {
+        self.size
+    }
+}
+
+/// File at a revision
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blob {
+    /// Path of the file relative to the repository root
+    path: String,
+    /// ID of the blob
+    id: String,
+    /// Kind of the file, either a regular file or a symbolic link
+    kind: TreeEntryKind,
+    /// Git file mode of the file
+    mode: i32,
+    /// Size of the blob in bytes
+    size: usize,
+    /// Whether the content is binary, as detected by git
+    is_binary: bool,
+    /// Content of the file, `None` for symbolic links, binary files or content which is not valid UTF-8
+    content: Option<String>,
+    /// Target path of a symbolic link, it is not resolved
+    symlink_target: Option<String>,
+}
+
+impl Blob {
+    /// Get the path of the file relative to the repository root
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the ID of the blob
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Get the kind of the file
+    pub fn kind(&self) -> TreeEntryKind {
+        self.kind
+    }
+
+    /// Get the git file mode of the file
+    pub fn mode(&self) -> i32 {
+        self.mode
+    }
+
+    /// Get the size of the blob in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the content is binary
+    pub fn is_binary(&self) -> bool {
+        self.is_binary
+    }
+
+    /// Get the content of the file, `None` for symbolic links, binary files or content which is not valid UTF-8
+    pub fn content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+
+    /// Get the target path of a symbolic link
+    pub fn symlink_target(&self) -> Option<&str> {
+        self.symlink_target.as_deref()
+    }
+}
+
+fn invalid_entry(path: &str, rev: &str, message: &str) -> Error {
+    Error::from_ctx_and_error(
+        format!("Path '{path}' in '{rev}'"),
+        git2::Error::new(git2::ErrorCode::Invalid, git2::ErrorClass::Tree, message),
+    )
+}
+
+/// Get the entry at `path` in the tree of `rev`
+fn find_entry(repo: &git2::Repository, rev: &str, path: &str) -> Result<git2::TreeEntry<'static>> {
+    utils::get_tree_for_revision(repo, rev)?
+        .get_path(Path::new(path))
+        .map_err(|e| Error::from_ctx_and_error(format!("Path '{path}' in '{rev}'"), e))
+}
+
+/// List the entries of the directory at `path` in `rev`, see [`crate::Repository::list_tree`]
+pub(crate) fn list_tree(repo: &git2::Repository, rev: &str, path: &str) -> Result<Vec<TreeEntry>> {
+    let path = path.trim_matches('/');
+    let tree = match path.is_empty() {
+        true => utils::get_tree_for_revision(repo, rev)?,
+        false => {
+            let entry = find_entry(repo, rev, path)?;
+            if entry.kind() != Some(git2::ObjectType::Tree) {
+                return Err(invalid_entry(path, rev, "The path is not a directory"));
+            }
+            repo.find_tree(entry.id())
+                .map_err(|e| Error::from_ctx_and_error(format!("Tree '{path}' in '{rev}'"), e))?
+        }
+    };
+    let odb = repo
+        .odb()
+        .map_err(|e| Error::from_ctx_and_error("Failed to open object database", e))?;
+
+    tree.iter()
+        .map(|entry| {
+            let name = String::from_utf8_lossy(entry.name_bytes()).to_string();
+            let kind = TreeEntryKind::from_filemode(entry.filemode());
+            let size = match kind {
+                TreeEntryKind::File | TreeEntryKind::Symlink => {
+                    let (size, _) = odb.read_header(entry.id()).map_err(|e| {
+                        Error::from_ctx_and_error(format!("Blob of '{name}' in '{rev}'"), e)
+                    })?;
+                    Some(size)
+                }
+                TreeEntryKind::Directory | TreeEntryKind::Submodule => None,
+            };
+            Ok(TreeEntry {
+                path: match path.is_empty() {
+                    true => name.clone(),
+                    false => format!("{path}/{name}"),
+                },
+                name,
+                kind,
+                mode: entry.filemode(),
+                id: entry.id().to_string(),
+                size,
+            })
+        })
+        .collect()
+}
+
+/// Read the file at `path` in `rev`, see [`crate::Repository::read_blob`]
+pub(crate) fn read_blob(repo: &git2::Repository, rev: &str, path: &str) -> Result<Blob> {
+    let path = path.trim_matches('/');
+    let entry = find_entry(repo, rev, path)?;
+    let kind = TreeEntryKind::from_filemode(entry.filemode());
+    if !matches!(kind, TreeEntryKind::File | TreeEntryKind::Symlink) {
+        return Err(invalid_entry(path, rev, "The path is not a file"));
+    }
+    let blob = repo
+        .find_blob(entry.id())
+        .map_err(|e| Error::from_ctx_and_error(format!("Blob of '{path}' in '{rev}'"), e))?;
+    let text = std::str::from_utf8(blob.content())
+        .ok()
+        .filter(|_| !blob.is_binary())
+        .map(|text| text.to_string());
+    let (content, symlink_target) = match kind {
+        TreeEntryKind::Symlink => (None, text),
+        _ => (text, None),
+    };
+    Ok(Blob {
+        path: path.to_string(),
+        id: blob.id().to_string(),
+        kind,
+        mode: entry.filemode(),
+        size: blob.size(),
+        is_binary: blob.is_binary(),
+        content,
+        symlink_target,
+    })
+}
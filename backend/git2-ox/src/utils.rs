@@ -25,6 +25,13 @@ pub fn get_tree_for_revision<'repo>(
         .map_err(|e| Error::from_ctx_and_error(format!("Tree for revision'{rev}'"), e))
 }
 
+/// Get the ID `content` would have if it was stored as a blob, equivalent to `git hash-object`
+pub fn hash_blob(content: &[u8]) -> Result<String> {
+    git2::Oid::hash_object(git2::ObjectType::Blob, content)
+        .map(|oid| oid.to_string())
+        .map_err(|e| Error::from_ctx_and_error("Failed to hash blob", e))
+}
+
 pub fn to_safe_glob(prefix: &str) -> String {
     let escaped = prefix
         .replace('*', "\\*")
@@ -0,0 +1,259 @@
+use crate::{Commit, Result, error::Error, utils};
+use std::path::Path;
+
+/// Linked worktree of a repository
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Worktree {
+    /// Name of the worktree in the repository
+    name: String,
+    /// Working directory of the worktree
+    path: String,
+    /// ID of the commit checked out in the worktree, `None` if the worktree is not valid
+    head: Option<String>,
+    /// Whether the worktree is locked, locked worktrees are not pruned
+    is_locked: bool,
+    /// Reason the worktree was locked for
+    lock_reason: Option<String>,
+    /// Whether the working directory of the worktree exists
+    is_valid: bool,
+}
+
+impl Worktree {
+    /// Get the name of the worktree
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the working directory of the worktree
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Get the ID of the commit checked out in the worktree
+    pub fn head(&self) -> Option<&str> {
+        self.head.as_deref()
+    }
+
+    /// Whether the worktree is locked
+    pub fn is_locked(&self) -> bool {
+        self.is_locked
+    }
+
+    /// Get the reason the worktree was locked for
+    pub fn lock_reason(&self) -> Option<&str> {
+        self.lock_reason.as_deref()
+    }
+
+    /// Whether the working directory of the worktree exists
+    pub fn is_valid(&self) -> bool {
+        self.is_valid
+    }
+
+    fn try_from_git2(worktree: &git2::Worktree) -> Result<Self> {
+        let name = worktree.name().unwrap_or("<invalid utf-8>").to_string();
+        let lock_status = worktree.is_locked().map_err(|e| {
+            Error::from_ctx_and_error(format!("Failed to get lock of worktree '{name}'"), e)
+        })?;
+        let (is_locked, lock_reason) = match lock_status {
+            git2::WorktreeLockStatus::Unlocked => (false, None),
+            git2::WorktreeLockStatus::Locked(reason) => (true, reason),
+        };
+        let is_valid = worktree.validate().is_ok();
+        let head = is_valid
+            .then(|| git2::Repository::open_from_worktree(worktree).ok())
+            .flatten()
+            .and_then(|repo| {
+                repo.head()
+                    .and_then(|head| head.peel_to_commit())
+                    .map(|commit| commit.id().to_string())
+                    .ok()
+            });
+        Ok(Self {
+            name,
+            path: worktree.path().to_string_lossy().to_string(),
+            head,
+            is_locked,
+            lock_reason,
+            is_valid,
+        })
+    }
+}
+
+fn find_worktree(repo: &git2::Repository, name: &str) -> Result<git2::Worktree> {
+    repo.find_worktree(name)
+        .map_err(|e| Error::from_ctx_and_error(format!("Worktree '{name}'"), e))
+}
+
+/// Get the linked worktrees of the repository sorted by name, see [`crate::Repository::worktrees`]
+pub(crate) fn worktrees(repo: &git2::Repository) -> Result<Vec<Worktree>> {
+    let names = repo
+        .worktrees()
+        .map_err(|e| Error::from_ctx_and_error("Failed to list worktrees", e))?;
+    let mut worktrees = names
+        .iter()
+        .flatten()
+        .map(|name| Worktree::try_from_git2(&find_worktree(repo, name)?))
+        .collect::<Result<Vec<_>>>()?;
+    worktrees.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(worktrees)
+}
+
+/// Add a linked worktree with `rev` checked out, see [`crate::Repository::create_worktree`]
+pub(crate) fn create_worktree(
+    repo: &git2::Repository,
+    name: &str,
+    path: &Path,
+    rev: &str,
+) -> Result<Worktree> {
+    if repo.find_worktree(name).is_ok() {
+        return Err(Error::from_ctx_and_error(
+            format!("Worktree '{name}'"),
+            git2::Error::new(
+                git2::ErrorCode::Exists,
+                git2::ErrorClass::Worktree,
+                "The worktree exists already",
+            ),
+        ));
+    }
+    let commit = utils::get_commit_for_revision(repo, rev)?;
+    let worktree = add_detached_worktree(repo, name, path, &commit)?;
+    Worktree::try_from_git2(&worktree)
+}
+
+/// Remove a linked worktree and its working directory, see [`crate::Repository::prune_worktree`]
+pub(crate) fn prune_worktree(repo: &git2::Repository, name: &str) -> Result<()> {
+    let worktree = find_worktree(repo, name)?;
+    if Worktree::try_from_git2(&worktree)?.is_locked {
+        return Err(Error::from_ctx_and_error(
+            format!("Worktree '{name}'"),
+            git2::Error::new(
+                git2::ErrorCode::Locked,
+                git2::ErrorClass::Worktree,
+                "The worktree is locked",
+            ),
+        ));
+    }
+    worktree
+        .prune(Some(
+            git2::WorktreePruneOptions::new()
+                .valid(true)
+                .working_tree(true),
+        ))
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to prune worktree '{name}'"), e))
+}
+
+/// Lock or unlock a linked worktree, see [`crate::Repository::lock_worktree`]
+pub(crate) fn lock_worktree(
+    repo: &git2::Repository,
+    name: &str,
+    lock: bool,
+    reason: Option<&str>,
+) -> Result<Worktree> {
+    let worktree = find_worktree(repo, name)?;
+    let result = match lock {
+        true => worktree.lock(reason),
+        false => worktree.unlock(),
+    };
+    result.map_err(|e| {
+        Error::from_ctx_and_error(format!("Failed to change lock of worktree '{name}'"), e)
+    })?;
+    Worktree::try_from_git2(&worktree)
+}
+
+/// Check out `rev` with a detached `HEAD` in the linked worktree `name` at `path`, see
+/// [`crate::Repository::checkout_worktree`]
+pub(crate) fn checkout_worktree(
+    repo: &git2::Repository,
+    name: &str,
+    path: &Path,
+    rev: &str,
+) -> Result<Commit> {
+    let commit = utils::get_commit_for_revision(repo, rev)?;
+
+    let worktree = match repo.find_worktree(name) {
+        Ok(worktree) if worktree.validate().is_ok() => worktree,
+        found => {
+            // The working directory of the worktree was removed, forget about it
+            if let Ok(stale) = found {
+                stale
+                    .prune(Some(
+                        git2::WorktreePruneOptions::new()
+                            .valid(true)
+                            .working_tree(true),
+                    ))
+                    .map_err(|e| {
+                        Error::from_ctx_and_error(format!("Failed to prune worktree '{name}'"), e)
+                    })?;
+            }
+            add_detached_worktree(repo, name, path, &commit)?
+        }
+    };
+
+    let worktree_repo = git2::Repository::open_from_worktree(&worktree)
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to open worktree '{name}'"), e))?;
+    worktree_repo.set_head_detached(commit.id()).map_err(|e| {
+        Error::from_ctx_and_error(format!("Failed to set head of worktree '{name}'"), e)
+    })?;
+    worktree_repo
+        .checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .map_err(|e| {
+            Error::from_ctx_and_error(
+                format!("Failed to checkout '{rev}' in worktree '{name}'"),
+                e,
+            )
+        })?;
+    Ok((&commit).into())
+}
+
+/// Add a linked worktree with a detached `HEAD` at `commit`
+///
+/// Git requires a branch to add a worktree, a temporary branch is used and deleted once the worktree is
+/// detached. Fails if a branch with the name of the temporary branch exists, it is not overwritten.
+fn add_detached_worktree(
+    repo: &git2::Repository,
+    name: &str,
+    path: &Path,
+    commit: &git2::Commit,
+) -> Result<git2::Worktree> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| {
+            Error::from_ctx_and_error(
+                format!("Failed to create '{}'", parent.display()),
+                git2::Error::from_str(&e.to_string()),
+            )
+        })?;
+    }
+
+    let branch_name = format!("{name}-worktree");
+    let mut branch = repo.branch(&branch_name, commit, false).map_err(|e| {
+        Error::from_ctx_and_error(format!("Failed to create branch '{branch_name}'"), e)
+    })?;
+    let worktree = repo
+        .worktree(
+            name,
+            path,
+            Some(git2::WorktreeAddOptions::new().reference(Some(branch.get()))),
+        )
+        .map_err(|e| Error::from_ctx_and_error(format!("Failed to add worktree '{name}'"), e));
+
+    // Detach the worktree from the temporary branch before deleting the branch
+    let detached = worktree.and_then(|worktree| {
+        let worktree_repo = git2::Repository::open_from_worktree(&worktree).map_err(|e| {
+            Error::from_ctx_and_error(format!("Failed to open worktree '{name}'"), e)
+        })?;
+        worktree_repo.set_head_detached(commit.id()).map_err(|e| {
+            Error::from_ctx_and_error(format!("Failed to detach worktree '{name}'"), e)
+        })?;
+        Ok(worktree)
+    });
+    branch.delete().map_err(|e| {
+        Error::from_ctx_and_error(format!("Failed to delete branch '{branch_name}'"), e)
+    })?;
+    detached
+}
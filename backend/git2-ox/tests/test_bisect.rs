@@ -0,0 +1,149 @@
+mod common;
+use git2_ox::{BisectStep, BisectVerdict, CommitProperties};
+
+/// Create a linear history and get the commit IDs, oldest first
+fn linear_history(t: &common::TempRepository, len: usize) -> Vec<String> {
+    (0..len)
+        .map(|_| t.create_and_commit_random_file().1)
+        .collect()
+}
+
+#[test]
+fn test_bisect_finds_first_bad_commit() {
+    let t = common::TempRepository::try_init().unwrap();
+    let commits = linear_history(&t, 10);
+    let first_bad = 6;
+
+    let mut bisect = t.repo().bisect_start("HEAD", &[&commits[0]]).unwrap();
+    assert_eq!(bisect.bad(), commits[9]);
+    let mut tested = Vec::new();
+    let found = loop {
+        match t.repo().bisect_step(&bisect).unwrap() {
+            BisectStep::Test { commit, .. } => {
+                let i = commits.iter().position(|c| c == commit.id()).unwrap();
+                assert!(!tested.contains(&i));
+                tested.push(i);
+                let verdict = match i < first_bad {
+                    true => BisectVerdict::Good,
+                    false => BisectVerdict::Bad,
+                };
+                t.repo()
+                    .bisect_mark(&mut bisect, commit.id(), verdict)
+                    .unwrap();
+            }
+            BisectStep::FirstBad { commit } => break commit,
+            BisectStep::Inconclusive { .. } => panic!("Nothing was skipped"),
+        }
+    };
+    assert_eq!(found.id(), commits[first_bad]);
+    // 9 candidates need at most 4 tests
+    assert!(tested.len() <= 4, "{tested:?}");
+    // The first test splits the 9 candidates into 4 commits up to the tested one and 5 after it
+    assert_eq!(tested[0], 4);
+}
+
+#[test]
+fn test_bisect_first_step() {
+    let t = common::TempRepository::try_init().unwrap();
+    let commits = linear_history(&t, 5);
+
+    let bisect = t.repo().bisect_start(&commits[4], &[&commits[0]]).unwrap();
+    let BisectStep::Test {
+        commit,
+        candidates,
+        steps,
+    } = t.repo().bisect_step(&bisect).unwrap()
+    else {
+        panic!("Expected a commit to test");
+    };
+    assert_eq!(commit.id(), commits[2]);
+    assert_eq!(candidates, 4);
+    assert_eq!(steps, 2);
+}
+
+#[test]
+fn test_bisect_merge() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, good) = t.create_and_commit_random_file();
+    let repo = t.repo().repo();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let commit = |message: &str, parents: &[&str]| {
+        let parents: Vec<git2::Commit> = parents
+            .iter()
+            .map(|id| repo.find_commit(git2::Oid::from_str(id).unwrap()).unwrap())
+            .collect();
+        let tree = parents[0].tree().unwrap();
+        let parents: Vec<&git2::Commit> = parents.iter().collect();
+        repo.commit(None, &signature, &signature, message, &tree, &parents)
+            .unwrap()
+            .to_string()
+    };
+    // Two branches of 3 and 2 commits are merged, followed by 2 more commits
+    let a1 = commit("a1", &[&good]);
+    let a2 = commit("a2", &[&a1]);
+    let a3 = commit("a3", &[&a2]);
+    let c1 = commit("c1", &[&good]);
+    let c2 = commit("c2", &[&c1]);
+    let merge = commit("merge", &[&a3, &c2]);
+    let m1 = commit("m1", &[&merge]);
+    let bad = commit("m2", &[&m1]);
+
+    let bisect = t.repo().bisect_start(&bad, &[&good]).unwrap();
+    let BisectStep::Test {
+        commit, candidates, ..
+    } = t.repo().bisect_step(&bisect).unwrap()
+    else {
+        panic!("Expected a commit to test");
+    };
+    assert_eq!(candidates, 8);
+    // 3 of the 8 candidates are reachable from a3, the merge reaches 6 of them
+    assert_eq!(commit.id(), a3);
+}
+
+#[test]
+fn test_bisect_skip() {
+    let t = common::TempRepository::try_init().unwrap();
+    let commits = linear_history(&t, 4);
+
+    let mut bisect = t.repo().bisect_start(&commits[3], &[&commits[0]]).unwrap();
+    t.repo()
+        .bisect_mark(&mut bisect, &commits[2], BisectVerdict::Skip)
+        .unwrap();
+    // Skipped commits are not tested again
+    match t.repo().bisect_step(&bisect).unwrap() {
+        BisectStep::Test { commit, .. } => assert_eq!(commit.id(), commits[1]),
+        step => panic!("Unexpected step {step:?}"),
+    }
+    t.repo()
+        .bisect_mark(&mut bisect, &commits[1], BisectVerdict::Good)
+        .unwrap();
+    match t.repo().bisect_step(&bisect).unwrap() {
+        BisectStep::Inconclusive { candidates } => assert_eq!(
+            candidates.iter().map(|c| c.id()).collect::<Vec<_>>(),
+            [&commits[3], &commits[2]]
+        ),
+        step => panic!("Unexpected step {step:?}"),
+    }
+    assert_eq!(bisect.skipped(), [commits[2].clone()]);
+}
+
+#[test]
+fn test_bisect_invalid() {
+    let t = common::TempRepository::try_init().unwrap();
+    let commits = linear_history(&t, 3);
+
+    assert!(matches!(
+        t.repo().bisect_start::<&str>("HEAD", &[]),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+    assert!(matches!(
+        t.repo().bisect_start("HEAD", &["unknown"]),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+    // The bad commit is older than the good commit
+    let bisect = t.repo().bisect_start(&commits[0], &[&commits[2]]).unwrap();
+    assert!(matches!(
+        t.repo().bisect_step(&bisect),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+}
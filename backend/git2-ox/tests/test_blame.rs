@@ -0,0 +1,169 @@
+mod common;
+use git2_ox::{BlameOptions, CommitProperties};
+use std::ops::RangeInclusive;
+
+/// Write the files with `Some` content and delete the files with `None`, then commit all of them to `HEAD`
+fn commit_files(
+    t: &common::TempRepository,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+) -> String {
+    let repo = t.repo().repo();
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        let path = std::path::Path::new(name);
+        match content {
+            Some(content) => {
+                std::fs::write(t.path().join(path), content).unwrap();
+                index.add_path(path).unwrap();
+            }
+            None => {
+                std::fs::remove_file(t.path().join(path)).unwrap();
+                index.remove_path(path).unwrap();
+            }
+        }
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+    .to_string()
+}
+
+fn commit_file(t: &common::TempRepository, name: &str, content: &str, message: &str) -> String {
+    commit_files(t, &[(name, Some(content))], message)
+}
+
+/// Get the blamed commit of every line
+fn blamed_lines(hunks: &[git2_ox::BlameHunk]) -> Vec<&str> {
+    hunks
+        .iter()
+        .flat_map(|hunk| hunk.line_range().map(move |_| hunk.commit().id()))
+        .collect()
+}
+
+#[test]
+fn test_blame() {
+    let t = common::TempRepository::try_init().unwrap();
+    let first = commit_file(&t, "a.txt", "one\ntwo\nthree\nfour\n", "First");
+    let second = commit_file(&t, "a.txt", "one\nTWO\nthree\nfour\n", "Second");
+
+    let hunks = t
+        .repo()
+        .blame("a.txt", "HEAD", None, &BlameOptions::default())
+        .unwrap();
+    assert_eq!(hunks.len(), 3);
+    assert_eq!(blamed_lines(&hunks), [&first, &second, &first, &first]);
+    assert_eq!(hunks[1].start_line(), 2);
+    assert_eq!(hunks[1].lines(), 1);
+    assert_eq!(hunks[1].path(), "a.txt");
+    assert_eq!(hunks[1].commit().summary(), "Second");
+
+    // Commits of other files do not change the blame
+    t.create_and_commit_random_file();
+    let hunks = t
+        .repo()
+        .blame("a.txt", "HEAD", None, &BlameOptions::default())
+        .unwrap();
+    assert_eq!(blamed_lines(&hunks), [&first, &second, &first, &first]);
+
+    // Older revisions can be blamed
+    let hunks = t
+        .repo()
+        .blame("a.txt", &first, None, &BlameOptions::default())
+        .unwrap();
+    assert_eq!(blamed_lines(&hunks), [&first; 4]);
+}
+
+#[test]
+fn test_blame_line_range() {
+    let t = common::TempRepository::try_init().unwrap();
+    let first = commit_file(&t, "a.txt", "one\ntwo\nthree\nfour\n", "First");
+    let second = commit_file(&t, "a.txt", "one\nTWO\nthree\nfour\n", "Second");
+
+    let hunks = t
+        .repo()
+        .blame("a.txt", "HEAD", Some(2..=3), &BlameOptions::default())
+        .unwrap();
+    assert_eq!(blamed_lines(&hunks), [&second, &first]);
+    assert_eq!(hunks[0].line_range(), 2..=2);
+    assert_eq!(hunks[1].line_range(), 3..=3);
+
+    for range in [0..=2, RangeInclusive::new(3, 2), 5..=5] {
+        assert!(matches!(
+            t.repo()
+                .blame("a.txt", "HEAD", Some(range), &BlameOptions::default()),
+            Err(git2_ox::error::Error::Invalid(_))
+        ));
+    }
+    // The file has 4 lines
+    assert!(matches!(
+        t.repo()
+            .blame("a.txt", "HEAD", Some(3..=5), &BlameOptions::default()),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+    assert!(matches!(
+        t.repo()
+            .blame("b.txt", "HEAD", None, &BlameOptions::default()),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+}
+
+#[test]
+fn test_blame_ignore_whitespace() {
+    let t = common::TempRepository::try_init().unwrap();
+    let first = commit_file(&t, "a.txt", "one\ntwo\n", "First");
+    let second = commit_file(&t, "a.txt", "one\ntwo  \n", "Whitespace");
+
+    let hunks = t
+        .repo()
+        .blame("a.txt", "HEAD", None, &BlameOptions::default())
+        .unwrap();
+    assert_eq!(blamed_lines(&hunks), [&first, &second]);
+
+    let options = BlameOptions {
+        ignore_whitespace: true,
+        ..Default::default()
+    };
+    let hunks = t.repo().blame("a.txt", "HEAD", None, &options).unwrap();
+    assert_eq!(blamed_lines(&hunks), [&first, &first]);
+}
+
+#[test]
+fn test_blame_follow_renames() {
+    let t = common::TempRepository::try_init().unwrap();
+    let first = commit_file(&t, "a.txt", "one\ntwo\nthree\n", "First");
+    let renamed = commit_files(
+        &t,
+        &[("a.txt", None), ("b.txt", Some("one\ntwo\nthree\n"))],
+        "Rename",
+    );
+    let third = commit_file(&t, "b.txt", "one\ntwo\nTHREE\n", "Third");
+
+    let hunks = t
+        .repo()
+        .blame("b.txt", "HEAD", None, &BlameOptions::default())
+        .unwrap();
+    assert_eq!(blamed_lines(&hunks), [&first, &first, &third]);
+    assert_eq!(hunks[0].path(), "a.txt");
+    assert_eq!(hunks[1].path(), "b.txt");
+
+    let options = BlameOptions {
+        follow_renames: false,
+        ..Default::default()
+    };
+    let hunks = t.repo().blame("b.txt", "HEAD", None, &options).unwrap();
+    assert_eq!(blamed_lines(&hunks), [&renamed, &renamed, &third]);
+    assert!(hunks[0].is_boundary());
+    assert_eq!(hunks[0].path(), "b.txt");
+}
@@ -0,0 +1,180 @@
+mod common;
+use git2_ox::{CheckoutMode, CommitProperties};
+
+#[test]
+fn test_checkout_refuses_to_overwrite_changes() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (second_file, second) = t.create_and_commit_random_file();
+    std::fs::write(t.path().join(&second_file), "changed").unwrap();
+
+    // Checking out the first commit would delete the changed file
+    match t.repo().checkout_revision(&first) {
+        Err(git2_ox::error::Error::CheckoutConflict { rev, paths }) => {
+            assert_eq!(rev, first);
+            assert_eq!(paths, [second_file.as_str()]);
+        }
+        result => panic!("Unexpected result {result:?}"),
+    }
+    assert_eq!(
+        t.repo().get_commit_for_revision("HEAD").unwrap().id(),
+        second
+    );
+    assert_eq!(
+        std::fs::read_to_string(t.path().join(&second_file)).unwrap(),
+        "changed"
+    );
+
+    // Changes which are not overwritten are kept
+    let (third_file, _) = t.create_and_commit_random_file();
+    let commit = t.repo().checkout_revision(&second).unwrap();
+    assert_eq!(commit.id(), second);
+    assert!(!t.path().join(&third_file).exists());
+    assert_eq!(
+        std::fs::read_to_string(t.path().join(&second_file)).unwrap(),
+        "changed"
+    );
+}
+
+#[test]
+fn test_checkout_force() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (second_file, _) = t.create_and_commit_random_file();
+    std::fs::write(t.path().join(&second_file), "changed").unwrap();
+
+    let mut repo = git2_ox::Repository::try_open(t.path()).unwrap();
+    let checkout = repo
+        .checkout_revision_with_mode(&first, CheckoutMode::Force)
+        .unwrap();
+    assert_eq!(checkout.commit().id(), first);
+    assert!(checkout.stash().is_none());
+    assert!(!t.path().join(&second_file).exists());
+}
+
+#[test]
+fn test_checkout_stash_and_restore() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (first_file, first) = t.create_and_commit_random_file();
+    let (_, second) = t.create_and_commit_random_file();
+    std::fs::write(t.path().join(&first_file), "changed").unwrap();
+
+    let mut repo = git2_ox::Repository::try_open(t.path()).unwrap();
+    let checkout = repo
+        .checkout_revision_with_mode(&first, CheckoutMode::Stash)
+        .unwrap();
+    assert_eq!(checkout.commit().id(), first);
+    let stash = checkout.stash().unwrap().clone();
+    assert_eq!(
+        std::fs::read_to_string(t.path().join(&first_file)).unwrap(),
+        "random content"
+    );
+    assert!(!repo.status().unwrap().is_dirty());
+
+    repo.checkout_revision(&second).unwrap();
+    repo.restore_stash(stash.id()).unwrap();
+    assert_eq!(
+        std::fs::read_to_string(t.path().join(&first_file)).unwrap(),
+        "changed"
+    );
+    // The stash is dropped once it is restored
+    assert!(matches!(
+        repo.restore_stash(stash.id()),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+
+    // Nothing is stashed without changes
+    repo.checkout_revision_with_mode(&second, CheckoutMode::Force)
+        .unwrap();
+    let checkout = repo
+        .checkout_revision_with_mode(&first, CheckoutMode::Stash)
+        .unwrap();
+    assert!(checkout.stash().is_none());
+}
+
+#[test]
+fn test_checkout_stash_blocked_by_untracked_file() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (first_file, first) = t.create_and_commit_random_file();
+    let (second_file, second) = t.create_and_commit_random_file();
+    let mut repo = git2_ox::Repository::try_open(t.path()).unwrap();
+    repo.checkout_revision(&first).unwrap();
+    // The untracked file would be overwritten by the second commit
+    std::fs::write(t.path().join(&second_file), "untracked").unwrap();
+    std::fs::write(t.path().join(&first_file), "changed").unwrap();
+
+    match repo.checkout_revision_with_mode(&second, CheckoutMode::Stash) {
+        Err(git2_ox::error::Error::CheckoutConflict { paths, .. }) => {
+            assert_eq!(paths, [second_file.as_str()])
+        }
+        result => panic!("Unexpected result {result:?}"),
+    }
+    // The stashed changes are restored
+    assert_eq!(
+        std::fs::read_to_string(t.path().join(&first_file)).unwrap(),
+        "changed"
+    );
+    let mut stashes = 0;
+    git2::Repository::open(t.path())
+        .unwrap()
+        .stash_foreach(|_, _, _| {
+            stashes += 1;
+            true
+        })
+        .unwrap();
+    assert_eq!(stashes, 0);
+}
+
+#[test]
+fn test_restore_stash_with_conflicts() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (file, first) = t.create_and_commit_random_file();
+    std::fs::write(t.path().join(&file), "changed").unwrap();
+
+    let mut repo = git2_ox::Repository::try_open(t.path()).unwrap();
+    let stash = repo
+        .checkout_revision_with_mode(&first, CheckoutMode::Stash)
+        .unwrap()
+        .stash()
+        .unwrap()
+        .clone();
+    // Commit a different change of the stashed file
+    {
+        std::fs::write(t.path().join(&file), "committed").unwrap();
+        let git_repo = repo.repo();
+        let mut index = git_repo.index().unwrap();
+        index.add_path(std::path::Path::new(&file)).unwrap();
+        index.write().unwrap();
+        let tree = git_repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("test", "test@example.com").unwrap();
+        let parent = git_repo.head().unwrap().peel_to_commit().unwrap();
+        git_repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                "Change",
+                &tree,
+                &[&parent],
+            )
+            .unwrap();
+    }
+
+    match repo.restore_stash(stash.id()) {
+        Err(git2_ox::error::Error::StashConflict { id, paths }) => {
+            assert_eq!(id, stash.id());
+            assert_eq!(paths, [file.as_str()]);
+        }
+        result => panic!("Unexpected result {result:?}"),
+    }
+    // The stash is kept
+    let mut stashes = 0;
+    git2::Repository::open(t.path())
+        .unwrap()
+        .stash_foreach(|_, _, _| {
+            stashes += 1;
+            true
+        })
+        .unwrap();
+    assert_eq!(stashes, 1);
+}
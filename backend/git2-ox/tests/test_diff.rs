@@ -0,0 +1,262 @@
+mod common;
+use git2_ox::{DiffFileStatus, DiffLineKind, DiffOptions, WhitespaceMode};
+
+/// Write the files with `Some` content and delete the files with `None`, then commit all of them to `HEAD`
+fn commit_files(t: &common::TempRepository, files: &[(&str, Option<&[u8]>)]) -> String {
+    let repo = t.repo().repo();
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        let path = std::path::Path::new(name);
+        match content {
+            Some(content) => {
+                std::fs::write(t.path().join(path), content).unwrap();
+                index.add_path(path).unwrap();
+            }
+            None => {
+                std::fs::remove_file(t.path().join(path)).unwrap();
+                index.remove_path(path).unwrap();
+            }
+        }
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let parent = repo.head().unwrap().peel_to_commit().unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Change",
+        &tree,
+        &[&parent],
+    )
+    .unwrap()
+    .to_string()
+}
+
+const RENAMED_CONTENT: &[u8] =
+    b"a file with enough content\nto be detected as renamed\nafter a rename\n";
+
+#[test]
+fn test_diff_files() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (random_file, _) = t.create_and_commit_random_file();
+    let base = commit_files(
+        &t,
+        &[
+            ("text.txt", Some(b"one\ntwo\nthree\n")),
+            ("old.txt", Some(RENAMED_CONTENT)),
+            ("data.bin", Some(b"\x00\x01")),
+        ],
+    );
+    let head = commit_files(
+        &t,
+        &[
+            ("text.txt", Some(b"one\nTWO\nthree")),
+            ("old.txt", None),
+            ("new.txt", Some(RENAMED_CONTENT)),
+            ("data.bin", Some(b"\x00\x02")),
+            ("added.txt", Some(b"added\n")),
+            (&random_file, None),
+        ],
+    );
+
+    let diff = t
+        .repo()
+        .diff(Some(&base), Some(&head), &DiffOptions::default())
+        .unwrap();
+    // The patch is still available
+    assert!(diff.patch().contains("+TWO"));
+    let file = |path: &str| {
+        diff.files()
+            .iter()
+            .find(|file| file.new_path().or(file.old_path()) == Some(path))
+            .unwrap()
+    };
+    assert_eq!(diff.files().len(), 5);
+
+    let added = file("added.txt");
+    assert_eq!(added.status(), DiffFileStatus::Added);
+    assert_eq!(added.old_path(), None);
+    assert_eq!(added.insertions(), 1);
+
+    let deleted = file(&random_file);
+    assert_eq!(deleted.status(), DiffFileStatus::Deleted);
+    assert_eq!(deleted.new_path(), None);
+    assert_eq!(deleted.deletions(), 1);
+
+    let renamed = file("new.txt");
+    assert_eq!(renamed.status(), DiffFileStatus::Renamed);
+    assert_eq!(renamed.old_path(), Some("old.txt"));
+    assert_eq!(renamed.similarity(), Some(100));
+    assert!(renamed.hunks().is_empty());
+
+    let binary = file("data.bin");
+    assert_eq!(binary.status(), DiffFileStatus::Modified);
+    assert!(binary.is_binary());
+    assert!(binary.hunks().is_empty());
+
+    let modified = file("text.txt");
+    assert_eq!(modified.status(), DiffFileStatus::Modified);
+    assert_eq!(modified.similarity(), None);
+    assert!(!modified.is_binary());
+    assert_eq!((modified.insertions(), modified.deletions()), (2, 2));
+    let [hunk] = modified.hunks() else {
+        panic!("Expected a single hunk");
+    };
+    assert_eq!((hunk.old_start(), hunk.old_lines()), (1, 3));
+    assert_eq!((hunk.new_start(), hunk.new_lines()), (1, 3));
+    assert!(hunk.header().starts_with("@@ -1,3 +1,3 @@"));
+    let lines: Vec<_> = hunk
+        .lines()
+        .iter()
+        .map(|line| {
+            (
+                line.kind(),
+                line.old_line_no(),
+                line.new_line_no(),
+                line.content(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        lines[..5],
+        [
+            (DiffLineKind::Context, Some(1), Some(1), "one\n"),
+            (DiffLineKind::Deletion, Some(2), None, "two\n"),
+            (DiffLineKind::Deletion, Some(3), None, "three\n"),
+            (DiffLineKind::Addition, None, Some(2), "TWO\n"),
+            (DiffLineKind::Addition, None, Some(3), "three"),
+        ]
+    );
+    assert_eq!(lines[5].0, DiffLineKind::NoNewlineAtEof);
+}
+
+#[test]
+fn test_diff_options() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (random_file, _) = t.create_and_commit_random_file();
+    let lines: Vec<String> = (1..=20).map(|i| format!("line {i}\n")).collect();
+    let mut changed = lines.clone();
+    changed[9] = "line  10 \n".to_string();
+    let base = commit_files(
+        &t,
+        &[
+            ("a.txt", Some(lines.concat().as_bytes())),
+            ("old.txt", Some(RENAMED_CONTENT)),
+        ],
+    );
+    let head = commit_files(
+        &t,
+        &[
+            ("a.txt", Some(changed.concat().as_bytes())),
+            ("old.txt", None),
+            (
+                "new.txt",
+                Some(b"a file with enough content\nto be detected as renamed\n"),
+            ),
+            (&random_file, Some(b"changed")),
+        ],
+    );
+    let diff = |options: &DiffOptions| t.repo().diff(Some(&base), Some(&head), options).unwrap();
+
+    let all = diff(&DiffOptions::default());
+    assert_eq!(all.files().len(), 3);
+    let a = all
+        .files()
+        .iter()
+        .find(|file| file.new_path() == Some("a.txt"))
+        .unwrap();
+    let hunk = &a.hunks()[0];
+    assert_eq!((hunk.old_start(), hunk.old_lines()), (7, 7));
+
+    let options = DiffOptions {
+        pathspecs: vec!["a.txt".to_string(), "*.bin".to_string()],
+        context_lines: Some(1),
+        ..Default::default()
+    };
+    let filtered = diff(&options);
+    assert_eq!(filtered.files().len(), 1);
+    assert_eq!(filtered.files()[0].new_path(), Some("a.txt"));
+    let hunk = &filtered.files()[0].hunks()[0];
+    assert_eq!((hunk.old_start(), hunk.old_lines()), (9, 3));
+    assert!(!filtered.patch().contains(&random_file));
+
+    // Only whitespace changed in a.txt
+    let options = DiffOptions {
+        pathspecs: vec!["a.txt".to_string()],
+        whitespace: WhitespaceMode::IgnoreChange,
+        ..Default::default()
+    };
+    assert!(diff(&options).files()[0].hunks().is_empty());
+    let options = DiffOptions {
+        whitespace: WhitespaceMode::IgnoreEol,
+        ..options
+    };
+    assert_eq!(diff(&options).files()[0].hunks().len(), 1);
+
+    let renamed = |options: &DiffOptions| {
+        diff(options)
+            .files()
+            .iter()
+            .any(|file| file.status() == DiffFileStatus::Renamed)
+    };
+    assert!(renamed(&DiffOptions::default()));
+    let options = DiffOptions {
+        rename_threshold: Some(95),
+        ..Default::default()
+    };
+    assert!(!renamed(&options));
+
+    // The changed random file is treated as binary
+    let options = DiffOptions {
+        max_file_size: Some(4),
+        ..Default::default()
+    };
+    let diff_with_max_size = diff(&options);
+    let random = diff_with_max_size
+        .files()
+        .iter()
+        .find(|file| file.new_path() == Some(random_file.as_str()))
+        .unwrap();
+    assert!(random.is_binary());
+
+    let options = DiffOptions {
+        copy_threshold: Some(101),
+        ..Default::default()
+    };
+    assert!(matches!(
+        t.repo().diff(Some(&base), Some(&head), &options),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_diff_find_copies() {
+    let t = common::TempRepository::try_init().unwrap();
+    t.create_and_commit_random_file();
+    let base = commit_files(&t, &[("a.txt", Some(RENAMED_CONTENT))]);
+    let mut modified = RENAMED_CONTENT.to_vec();
+    modified.extend_from_slice(b"modified\n");
+    let head = commit_files(
+        &t,
+        &[("a.txt", Some(&modified)), ("b.txt", Some(RENAMED_CONTENT))],
+    );
+
+    let status = |options: &DiffOptions| {
+        t.repo()
+            .diff(Some(&base), Some(&head), options)
+            .unwrap()
+            .files()
+            .iter()
+            .find(|file| file.new_path() == Some("b.txt"))
+            .unwrap()
+            .status()
+    };
+    assert_eq!(status(&DiffOptions::default()), DiffFileStatus::Added);
+    let options = DiffOptions {
+        copy_threshold: Some(50),
+        ..Default::default()
+    };
+    assert_eq!(status(&options), DiffFileStatus::Copied);
+}
@@ -0,0 +1,179 @@
+mod common;
+use git2_ox::CommitProperties;
+
+const REFERENCE: &str = "refs/test/history";
+
+#[test]
+fn test_commit_file_to_reference() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, head) = t.create_and_commit_random_file();
+
+    let first = t
+        .repo()
+        .commit_file_to_reference(REFERENCE, "a.json", b"1", "First")
+        .unwrap()
+        .unwrap();
+    // Unchanged content is not committed again
+    assert!(
+        t.repo()
+            .commit_file_to_reference(REFERENCE, "a.json", b"1", "Again")
+            .unwrap()
+            .is_none()
+    );
+    t.repo()
+        .commit_file_to_reference(REFERENCE, "b.json", b"other", "Other file")
+        .unwrap()
+        .unwrap();
+    let third = t
+        .repo()
+        .commit_file_to_reference(REFERENCE, "a.json", b"2", "Second")
+        .unwrap()
+        .unwrap();
+
+    // HEAD is not touched
+    assert_eq!(
+        t.repo()
+            .get_commit_for_revision("HEAD")
+            .unwrap()
+            .commit()
+            .id(),
+        head
+    );
+
+    let versions = t.repo().file_versions(REFERENCE, "a.json").unwrap();
+    assert_eq!(
+        versions.iter().map(|v| v.commit().id()).collect::<Vec<_>>(),
+        [third.id(), first.id()]
+    );
+    assert_eq!(
+        t.repo()
+            .read_file(versions[1].commit().id(), "a.json")
+            .unwrap(),
+        b"1"
+    );
+    assert_eq!(t.repo().read_file(REFERENCE, "a.json").unwrap(), b"2");
+    assert_eq!(t.repo().read_file(REFERENCE, "b.json").unwrap(), b"other");
+}
+
+#[test]
+fn test_commit_file_to_reference_invalid_name() {
+    let t = common::TempRepository::try_init().unwrap();
+    assert!(matches!(
+        t.repo()
+            .commit_file_to_reference(REFERENCE, "dir/a.json", b"1", "Nested"),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+}
+
+#[test]
+fn test_file_versions_missing_reference() {
+    let t = common::TempRepository::try_init().unwrap();
+    assert!(matches!(
+        t.repo().file_versions(REFERENCE, "a.json"),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+}
+
+/// Write the files with `Some` content and delete the files with `None`, then commit all of them to `HEAD`
+fn commit_files(
+    t: &common::TempRepository,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+) -> String {
+    let repo = t.repo().repo();
+    let mut index = repo.index().unwrap();
+    for (name, content) in files {
+        let path = std::path::Path::new(name);
+        match content {
+            Some(content) => {
+                let file_path = t.path().join(path);
+                std::fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+                std::fs::write(file_path, content).unwrap();
+                index.add_path(path).unwrap();
+            }
+            None => {
+                std::fs::remove_file(t.path().join(path)).unwrap();
+                index.remove_path(path).unwrap();
+            }
+        }
+    }
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )
+    .unwrap()
+    .to_string()
+}
+
+fn commits_for_path(t: &common::TempRepository, path: &str, follow_renames: bool) -> Vec<String> {
+    t.repo()
+        .iter_commits_for_path(None, None, path, follow_renames)
+        .unwrap()
+        .map(|commit| commit.unwrap().id().to_string())
+        .collect()
+}
+
+#[test]
+fn test_iter_commits_for_path() {
+    let t = common::TempRepository::try_init().unwrap();
+    let first = commit_files(&t, &[("dir/a.txt", Some("1"))], "First");
+    t.create_and_commit_random_file();
+    let second = commit_files(&t, &[("dir/b.txt", Some("1"))], "Second");
+    let third = commit_files(&t, &[("dir/a.txt", Some("2"))], "Third");
+
+    assert_eq!(
+        commits_for_path(&t, "dir/a.txt", true),
+        [third.as_str(), first.as_str()]
+    );
+    assert_eq!(
+        commits_for_path(&t, "dir", true),
+        [third.as_str(), second.as_str(), first.as_str()]
+    );
+    assert_eq!(
+        commits_for_path(&t, "dir/", true),
+        [third.as_str(), second.as_str(), first.as_str()]
+    );
+    assert!(commits_for_path(&t, "missing.txt", true).is_empty());
+
+    // The range is respected
+    let commits: Vec<String> = t
+        .repo()
+        .iter_commits_for_path(Some(&first), Some(&second), "dir", true)
+        .unwrap()
+        .map(|commit| commit.unwrap().id().to_string())
+        .collect();
+    assert_eq!(commits, [second.as_str()]);
+}
+
+#[test]
+fn test_iter_commits_for_path_follow_renames() {
+    let t = common::TempRepository::try_init().unwrap();
+    let content = "a file with enough content\nto be detected as renamed\n";
+    let first = commit_files(&t, &[("a.txt", Some(content))], "First");
+    let renamed = commit_files(&t, &[("a.txt", None), ("b.txt", Some(content))], "Rename");
+    let third = commit_files(&t, &[("b.txt", Some("changed"))], "Third");
+    // A new file at the old path is not part of the history of the renamed file
+    let unrelated = commit_files(&t, &[("a.txt", Some("unrelated"))], "Unrelated");
+
+    assert_eq!(
+        commits_for_path(&t, "b.txt", true),
+        [third.as_str(), renamed.as_str(), first.as_str()]
+    );
+    assert_eq!(
+        commits_for_path(&t, "b.txt", false),
+        [third.as_str(), renamed.as_str()]
+    );
+    assert_eq!(
+        commits_for_path(&t, "a.txt", true),
+        [unrelated.as_str(), renamed.as_str(), first.as_str()]
+    );
+}
@@ -39,6 +39,97 @@ fn test_get_commit_for_revision() {
     );
 }
 
+#[test]
+fn test_signature() {
+    let t = common::TempRepository::try_init().unwrap();
+    let mut config = t.repo().repo().config().unwrap();
+    config.set_str("user.name", "Jane Doe").unwrap();
+    config.set_str("user.email", "jane@example.com").unwrap();
+
+    let signature = t.repo().signature().unwrap();
+    assert_eq!(signature.name(), "Jane Doe");
+    assert_eq!(signature.email(), "jane@example.com");
+}
+
+#[test]
+fn test_set_config() {
+    let t = common::TempRepository::try_init().unwrap();
+    t.repo()
+        .set_config("merge.test.driver", "true %O %A %B")
+        .unwrap();
+
+    let config = t.repo().repo().config().unwrap();
+    assert_eq!(
+        config.get_string("merge.test.driver").unwrap(),
+        "true %O %A %B"
+    );
+}
+
+#[test]
+fn test_is_reachable() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (_, second) = t.create_and_commit_random_file();
+    assert!(t.repo().is_reachable(&first).unwrap());
+    assert!(t.repo().is_reachable(&second).unwrap());
+
+    // Resetting the branch leaves the second commit only in the object database
+    let first_commit = t.repo().repo().revparse_single(&first).unwrap();
+    t.repo()
+        .repo()
+        .reset(&first_commit, git2::ResetType::Hard, None)
+        .unwrap();
+    assert!(t.repo().is_reachable(&first).unwrap());
+    assert!(!t.repo().is_reachable(&second).unwrap());
+
+    t.repo()
+        .create_lightweight_tag("keep", &second, false)
+        .unwrap();
+    assert!(t.repo().is_reachable(&second).unwrap());
+    assert!(t.repo().is_reachable("not-a-revision").is_err());
+}
+
+#[test]
+fn test_sync_references() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (_, second) = t.create_and_commit_random_file();
+    let target = |name: &str| {
+        t.repo()
+            .repo()
+            .refname_to_id(name)
+            .ok()
+            .map(|oid| oid.to_string())
+    };
+
+    t.repo()
+        .sync_references("refs/pins/a", [("1", first.as_str()), ("2", "HEAD")], "pin")
+        .unwrap();
+    t.repo()
+        .sync_references("refs/pins/b", [("1", first.as_str())], "pin")
+        .unwrap();
+    assert_eq!(target("refs/pins/a/1"), Some(first.clone()));
+    assert_eq!(target("refs/pins/a/2"), Some(second.clone()));
+
+    // Moved and removed targets, other namespaces are kept
+    t.repo()
+        .sync_references("refs/pins/a/", [("1", second.as_str())], "pin")
+        .unwrap();
+    assert_eq!(target("refs/pins/a/1"), Some(second.clone()));
+    assert_eq!(target("refs/pins/a/2"), None);
+    assert_eq!(target("refs/pins/b/1"), Some(first.clone()));
+
+    t.repo()
+        .sync_references::<_, &str, &str>("refs/pins/a", [], "unpin")
+        .unwrap();
+    assert_eq!(target("refs/pins/a/1"), None);
+    assert!(
+        t.repo()
+            .sync_references("refs/pins/a", [("1", "not-a-revision")], "pin")
+            .is_err()
+    );
+}
+
 /// Shift all characters in `s` one code point forward
 fn shift_chars_forward(s: &str) -> String {
     s.chars()
@@ -81,7 +172,6 @@ fn test_list_commits() {
         t.repo()
             .iter_commits(None, None)
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -94,7 +184,6 @@ fn test_list_commits() {
         t.repo()
             .iter_commits(None, Some("HEAD"))
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -108,7 +197,6 @@ fn test_list_commits() {
         t.repo()
             .iter_commits(None, Some(&branch_name))
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -122,7 +210,6 @@ fn test_list_commits() {
         t.repo()
             .iter_commits(Some(&commit_ids[commit_ids.len() - 2]), None)
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -168,7 +255,6 @@ fn test_list_commits_multiple_branches() {
         t.repo()
             .iter_commits(None, Some(&default_branch_name))
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -180,7 +266,6 @@ fn test_list_commits_multiple_branches() {
         t.repo()
             .iter_commits(None, Some(branch_name))
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -192,7 +277,6 @@ fn test_list_commits_multiple_branches() {
         t.repo()
             .iter_commits(None, None)
             .unwrap()
-            .into_iter()
             .map(|r| {
                 let c = r.unwrap();
                 c.id().to_string()
@@ -237,7 +321,6 @@ fn test_iter_branches() {
         .repo()
         .iter_branches()
         .unwrap()
-        .into_iter()
         .map(|b| b.name().to_string());
     let mut expected_branch_names: HashSet<String> =
         HashSet::from_iter(branch_names.iter().map(|s| s.to_string()));
@@ -262,12 +345,7 @@ fn test_iter_tags() {
             .unwrap();
     }
 
-    let existing_tag_names = t
-        .repo()
-        .iter_tags()
-        .unwrap()
-        .into_iter()
-        .map(|b| b.name().to_string());
+    let existing_tag_names = t.repo().iter_tags().unwrap().map(|b| b.name().to_string());
     let expected_tag_names: HashSet<String> =
         HashSet::from_iter(tag_names.iter().map(|s| s.to_string()));
     assert_eq!(HashSet::from_iter(existing_tag_names), expected_tag_names);
@@ -297,7 +375,6 @@ fn test_iter_references() {
         .repo()
         .iter_references()
         .unwrap()
-        .into_iter()
         .map(|b| b.name().to_string());
     let mut expected_ref_names: HashSet<String> = HashSet::from_iter(
         tag_names
@@ -0,0 +1,120 @@
+mod common;
+use git2_ox::TreeEntryKind;
+
+/// Commit a tree with a text file, an executable, a binary file, a symbolic link and a directory on top of
+/// the random file committed to `HEAD`
+fn commit_tree(t: &common::TempRepository) -> (String, String) {
+    let (random_file, _) = t.create_and_commit_random_file();
+    let repo = t.repo().repo();
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+
+    let mut dir = repo.treebuilder(None).unwrap();
+    let nested = repo.blob(b"nested\n").unwrap();
+    dir.insert("nested.txt", nested, 0o100644).unwrap();
+    let dir_id = dir.write().unwrap();
+
+    let mut root = repo.treebuilder(Some(&head.tree().unwrap())).unwrap();
+    root.insert("text.txt", repo.blob(b"one\ntwo\n").unwrap(), 0o100644)
+        .unwrap();
+    root.insert("run.sh", repo.blob(b"#!/bin/sh\n").unwrap(), 0o100755)
+        .unwrap();
+    root.insert("data.bin", repo.blob(b"\x00\x01\x02").unwrap(), 0o100644)
+        .unwrap();
+    root.insert("link", repo.blob(b"dir/nested.txt").unwrap(), 0o120000)
+        .unwrap();
+    root.insert("dir", dir_id, 0o040000).unwrap();
+    let tree = repo.find_tree(root.write().unwrap()).unwrap();
+
+    let signature = git2::Signature::now("test", "test@example.com").unwrap();
+    let commit = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Tree",
+            &tree,
+            &[&head],
+        )
+        .unwrap();
+    (random_file, commit.to_string())
+}
+
+#[test]
+fn test_list_tree() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (random_file, commit) = commit_tree(&t);
+
+    let entries = t.repo().list_tree("HEAD", "").unwrap();
+    let mut names: Vec<&str> = entries.iter().map(|entry| entry.name()).collect();
+    names.sort();
+    let mut expected = vec![
+        "data.bin",
+        "dir",
+        "link",
+        "run.sh",
+        "text.txt",
+        random_file.as_str(),
+    ];
+    expected.sort();
+    assert_eq!(names, expected);
+
+    let entry = |name: &str| entries.iter().find(|entry| entry.name() == name).unwrap();
+    assert_eq!(entry("text.txt").kind(), TreeEntryKind::File);
+    assert_eq!(entry("text.txt").size(), Some(8));
+    assert!(!entry("text.txt").is_executable());
+    assert!(entry("run.sh").is_executable());
+    assert_eq!(entry("run.sh").mode(), 0o100755);
+    assert_eq!(entry("link").kind(), TreeEntryKind::Symlink);
+    assert_eq!(entry("dir").kind(), TreeEntryKind::Directory);
+    assert_eq!(entry("dir").size(), None);
+
+    let entries = t.repo().list_tree(&commit, "dir/").unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].path(), "dir/nested.txt");
+
+    assert!(matches!(
+        t.repo().list_tree("HEAD", "text.txt"),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+    assert!(matches!(
+        t.repo().list_tree("HEAD", "missing"),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+    // The working directory is not listed
+    assert!(t.path().join(&random_file).exists());
+    assert!(!t.path().join("text.txt").exists());
+}
+
+#[test]
+fn test_read_blob() {
+    let t = common::TempRepository::try_init().unwrap();
+    commit_tree(&t);
+
+    let blob = t.repo().read_blob("HEAD", "text.txt").unwrap();
+    assert_eq!(blob.content(), Some("one\ntwo\n"));
+    assert_eq!(blob.size(), 8);
+    assert!(!blob.is_binary());
+    assert_eq!(blob.symlink_target(), None);
+
+    let blob = t.repo().read_blob("HEAD", "data.bin").unwrap();
+    assert!(blob.is_binary());
+    assert_eq!(blob.content(), None);
+    assert_eq!(blob.size(), 3);
+
+    let blob = t.repo().read_blob("HEAD", "link").unwrap();
+    assert_eq!(blob.kind(), TreeEntryKind::Symlink);
+    assert_eq!(blob.symlink_target(), Some("dir/nested.txt"));
+    assert_eq!(blob.content(), None);
+
+    let blob = t.repo().read_blob("HEAD", "dir/nested.txt").unwrap();
+    assert_eq!(blob.content(), Some("nested\n"));
+
+    assert!(matches!(
+        t.repo().read_blob("HEAD", "dir"),
+        Err(git2_ox::error::Error::Invalid(_))
+    ));
+    assert!(matches!(
+        t.repo().read_blob("HEAD~1", "text.txt"),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+}
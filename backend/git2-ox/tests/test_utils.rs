@@ -0,0 +1,14 @@
+use git2_ox::utils;
+
+#[test]
+fn test_hash_blob() {
+    // Reference values created with `git hash-object`
+    assert_eq!(
+        utils::hash_blob(b"").unwrap(),
+        "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+    );
+    assert_eq!(
+        utils::hash_blob(b"hello\n").unwrap(),
+        "ce013625030ba8dba906f756967f9e9ca394464a"
+    );
+}
@@ -0,0 +1,130 @@
+mod common;
+use git2_ox::CommitProperties;
+
+#[test]
+fn test_checkout_worktree() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (first_file, first) = t.create_and_commit_random_file();
+    let (second_file, second) = t.create_and_commit_random_file();
+    let path = t.path().join("worktrees").join("node-1");
+    let branch = t.repo().current_branch_name();
+
+    let commit = t.repo().checkout_worktree("node-1", &path, &first).unwrap();
+    assert_eq!(commit.id(), first);
+    assert!(path.join(&first_file).exists());
+    assert!(!path.join(&second_file).exists());
+
+    // The worktree is reused and local changes are discarded
+    std::fs::write(path.join(&first_file), "changed").unwrap();
+    t.repo()
+        .checkout_worktree("node-1", &path, &second)
+        .unwrap();
+    assert!(path.join(&second_file).exists());
+    assert_eq!(
+        std::fs::read_to_string(path.join(&first_file)).unwrap(),
+        "random content"
+    );
+
+    let worktree_repo = git2_ox::Repository::try_open(&path).unwrap();
+    assert!(worktree_repo.repo().head_detached().unwrap());
+    // No branch is left behind and the main working directory is untouched
+    assert_eq!(t.repo().iter_branches().unwrap().count(), 1);
+    assert_eq!(t.repo().current_branch_name(), branch);
+
+    // A removed working directory is recreated
+    std::fs::remove_dir_all(&path).unwrap();
+    t.repo().checkout_worktree("node-1", &path, &first).unwrap();
+    assert!(path.join(&first_file).exists());
+
+    assert!(
+        t.repo()
+            .checkout_worktree("node-1", &path, "not-a-revision")
+            .is_err()
+    );
+}
+
+#[test]
+fn test_checkout_worktree_keeps_existing_branch() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (_, second) = t.create_and_commit_random_file();
+    let path = t.path().join("worktrees").join("node-1");
+    let commit = t
+        .repo()
+        .repo()
+        .find_commit(git2::Oid::from_str(&first).unwrap())
+        .unwrap();
+    t.repo()
+        .repo()
+        .branch("node-1-worktree", &commit, false)
+        .unwrap();
+
+    // The branch used to add the worktree exists already, it is neither moved nor deleted
+    assert!(matches!(
+        t.repo().checkout_worktree("node-1", &path, &second),
+        Err(git2_ox::error::Error::Conflict(_))
+    ));
+    let branch = t
+        .repo()
+        .repo()
+        .find_branch("node-1-worktree", git2::BranchType::Local)
+        .unwrap();
+    assert_eq!(branch.get().target().unwrap().to_string(), first);
+}
+
+#[test]
+fn test_manage_worktrees() {
+    let t = common::TempRepository::try_init().unwrap();
+    let (_, first) = t.create_and_commit_random_file();
+    let (_, second) = t.create_and_commit_random_file();
+    let path_a = t.path().join("worktrees").join("a");
+    let path_b = t.path().join("worktrees").join("b");
+
+    assert!(t.repo().worktrees().unwrap().is_empty());
+    let b = t.repo().create_worktree("b", &path_b, &second).unwrap();
+    assert_eq!(b.name(), "b");
+    assert_eq!(b.head(), Some(second.as_str()));
+    assert!(b.is_valid());
+    assert!(!b.is_locked());
+    t.repo().create_worktree("a", &path_a, &first).unwrap();
+    // Names are unique
+    assert!(matches!(
+        t.repo().create_worktree("a", &path_b, &first),
+        Err(git2_ox::error::Error::Conflict(_))
+    ));
+
+    let worktrees = t.repo().worktrees().unwrap();
+    assert_eq!(
+        worktrees.iter().map(|w| w.name()).collect::<Vec<_>>(),
+        ["a", "b"]
+    );
+    assert_eq!(worktrees[0].head(), Some(first.as_str()));
+    let status = t.repo().status().unwrap();
+    assert_eq!(status.linked_worktrees(), worktrees);
+
+    // Locked worktrees are not pruned
+    let a = t.repo().lock_worktree("a", Some("in use")).unwrap();
+    assert!(a.is_locked());
+    assert_eq!(a.lock_reason(), Some("in use"));
+    assert!(matches!(
+        t.repo().prune_worktree("a"),
+        Err(git2_ox::error::Error::Conflict(_))
+    ));
+    assert!(path_a.exists());
+    assert!(!t.repo().unlock_worktree("a").unwrap().is_locked());
+
+    t.repo().prune_worktree("a").unwrap();
+    assert!(!path_a.exists());
+    assert_eq!(t.repo().worktrees().unwrap().len(), 1);
+
+    // A worktree without working directory is invalid
+    std::fs::remove_dir_all(&path_b).unwrap();
+    let b = &t.repo().worktrees().unwrap()[0];
+    assert!(!b.is_valid());
+    assert_eq!(b.head(), None);
+
+    assert!(matches!(
+        t.repo().prune_worktree("unknown"),
+        Err(git2_ox::error::Error::NotFound(_))
+    ));
+}
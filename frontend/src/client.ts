@@ -4,7 +4,11 @@ import createClient, { type Middleware } from "openapi-fetch";
 import z from "zod";
 import { ApiError } from "./lib/errors";
 import type { paths } from "./types/api";
-import type { FlowMetadata } from "./types/api-types";
+import type {
+  ApiStatusDetailResponse,
+  FlowMetadata,
+  FlowNode,
+} from "./types/api-types";
 import type { AppNode } from "./types/nodes";
 
 const logger = log.getLogger("api-client");
@@ -288,37 +292,63 @@ export async function fetchStatus(): Promise<GitStatus> {
   };
 }
 
-export async function createFlow(name: string): Promise<FlowMetadata> {
+export async function createFlow(
+  name: string,
+): Promise<FlowMetadata & { revision?: string }> {
   const { data, error } = await client.POST("/api/v1/flows", {
     body: { name },
   });
   if (error) {
     throw new ApiError(error, "Error creating flow");
   }
-  return data.flow;
+  return { ...data.flow, revision: data.revision };
 }
 
+/**
+ * Store a flow on the server
+ *
+ * @param revision Revision of the stored flow the changes are based on. The flow is not stored if it was
+ * modified on the server since.
+ * @returns The revision of the stored flow
+ */
 export async function pushFlow(
   id: string,
   name: string,
   nodes: AppNode[],
   edges: Edge[],
-): Promise<void> {
-  const { error } = await client.POST("/api/v1/flows/{id}", {
+  revision?: string,
+): Promise<string> {
+  const { data, error, response } = await client.POST("/api/v1/flows/{id}", {
     params: {
       path: { id },
     },
     body: {
       flow: {
         name: name,
-        reactflow: { nodes, edges },
+        reactflow: { nodes: nodes as FlowNode[], edges },
       },
+      revision,
     },
   });
 
+  if (response.status === 409) {
+    throw new ApiError(
+      {
+        status: response.status,
+        reason: "Conflict",
+        message:
+          "The flow was modified in the meantime, reload it to get the latest changes",
+      },
+      `Error saving flow ${name}`,
+    );
+  }
   if (error) {
-    throw new ApiError(error, `Error saving flow ${name}`);
+    throw new ApiError(
+      error as ApiStatusDetailResponse,
+      `Error saving flow ${name}`,
+    );
   }
+  return data.revision;
 }
 
 export async function deleteFlow(id: string): Promise<void> {
@@ -234,7 +234,13 @@ export const useStore = create<AppState>()(
         }
 
         // TODO: If current flow is set, save it first. Or maybe save every time this dialog is opened
-        set({ currentFlow: flow });
+        set({
+          currentFlow: {
+            id: flow.id,
+            name: flow.name,
+            revision: flow.revision,
+          },
+        });
 
         const store = get();
         store.setNodes([]);
@@ -278,7 +284,11 @@ export const useStore = create<AppState>()(
         if (data) {
           // The flow has been loaded successfully, first set the metadata of the flow that is currently loaded
           set({
-            currentFlow: { id: id, name: data.flow.name },
+            currentFlow: {
+              id: id,
+              name: data.flow.name,
+              revision: data.revision,
+            },
           });
 
           const store = get();
@@ -292,15 +302,25 @@ export const useStore = create<AppState>()(
           return;
         }
 
+        let revision;
         try {
-          await pushFlow(currentFlow.id, currentFlow.name, nodes, edges);
+          revision = await pushFlow(
+            currentFlow.id,
+            currentFlow.name,
+            nodes,
+            edges,
+            currentFlow.revision,
+          );
         } catch (error) {
           notify.error(error);
           return;
         }
 
         notify.success("Saved");
-        set({ hasUnsavedChanges: false });
+        set({
+          currentFlow: { ...currentFlow, revision },
+          hasUnsavedChanges: false,
+        });
       },
       closeCurrentFlow: () => {
         set({
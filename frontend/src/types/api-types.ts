@@ -1,6 +1,7 @@
 import { type components } from "./api";
 
 export type FlowMetadata = components["schemas"]["FlowMetadata"];
+export type FlowNode = components["schemas"]["Node"];
 export type ApiStatusDetailResponse =
   components["schemas"]["ApiStatusDetailResponse"];
 
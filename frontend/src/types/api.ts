@@ -13,7 +13,7 @@ export interface paths {
         };
         /**
          * List debug flows
-         * @description List all debug flows
+         * @description List all debug flows, the most recently changed debug flow comes first
          */
         get: operations["list_flows"];
         put?: never;
@@ -28,6 +28,46 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
+    "/api/v1/flows/search": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Search debug flows
+         * @description Search the names of all debug flows and the titles, descriptions and linked Git revisions of their nodes. Searching ignores case, every match contains a snippet of the matching text with the character ranges of the search terms.
+         */
+        get: operations["search_flows"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/templates": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List templates
+         * @description List the templates in `.debug-flow/templates/` a debug flow can be created from
+         */
+        get: operations["list_templates"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
     "/api/v1/flows/{id}": {
         parameters: {
             query?: never;
@@ -43,7 +83,7 @@ export interface paths {
         put?: never;
         /**
          * Store debug flow
-         * @description Store debug flow
+         * @description Store debug flow. If the request contains a revision and the stored debug flow was modified since that revision, the debug flow is not stored and the current version is returned instead. The debug flow is always stored under the ID in the path, use the rename endpoint to change the ID of a debug flow.
          */
         post: operations["store_flow"];
         /**
@@ -53,10 +93,14 @@ export interface paths {
         delete: operations["delete_flow"];
         options?: never;
         head?: never;
-        patch?: never;
+        /**
+         * Rename debug flow
+         * @description Rename a debug flow. The ID of a debug flow is derived from its name, so the stored file is moved and the response contains the new ID. Renaming fails if a different debug flow with the new ID exists already.
+         */
+        patch: operations["rename_flow"];
         trace?: never;
     };
-    "/api/v1/git/branches": {
+    "/api/v1/flows/{id}/bisect": {
         parameters: {
             query?: never;
             header?: never;
@@ -64,49 +108,67 @@ export interface paths {
             cookie?: never;
         };
         /**
-         * List branches
-         * @description List all local branches in the repository, optionally filtered by a glob pattern.
+         * Get bisection
+         * @description Get the marked commits and the next step of the running bisection of the debug flow
          */
-        get: operations["list_branches"];
+        get: operations["get_bisect"];
         put?: never;
         /**
-         * Create new branch
-         * @description Creates a new branch at the specified revision.
+         * Start bisection
+         * @description Start a bisection to find the first commit between the good revisions and the bad revision which has the problem. The commit to test next is added as a status node in progress, connected to the root node. A running bisection of the debug flow is replaced, the nodes it added are kept.
          */
-        post: operations["create_branch"];
-        delete?: never;
+        post: operations["start_bisect"];
+        /**
+         * Reset bisection
+         * @description End the running bisection of the debug flow, the nodes it added are kept
+         */
+        delete: operations["reset_bisect"];
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/commit/{revision}": {
+    "/api/v1/flows/{id}/bisect/mark": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
+        get?: never;
+        put?: never;
         /**
-         * Get commit for a revision
-         * @description Get a single commit by its revision.
-         *         The revision can be anything accepted by `git rev-parse`. For a branch it will return the HEAD of the branch.
+         * Mark bisection commit
+         * @description Mark the commit to test as good, bad or skipped. The state of its node is set to success, fail or unknown and the next commit to test is added as a status node. Once the first bad commit is found, it is added as a failed status node and the bisection is finished.
          */
-        get: operations["get_revision"];
+        post: operations["mark_bisect"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/{id}/bisect/run": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
         put?: never;
         /**
-         * Checkout commit for a revision
-         * @description Checkout a commit by its revision.
-         *         The revision can be anything accepted by `git rev-parse`. For a branch it will checkout the HEAD of the branch.
+         * Run bisection
+         * @description Bisect automatically by running a test command for every commit to test, like `git bisect run`. The exit code 0 marks the commit as good, 125 skips it and the other codes up to 127 mark it as bad. The bisection stops if a test times out or is terminated. Every test is a command run of the node of the commit, its output is streamed as `run` events on `/{id}/runs/stream`, the worktree it ran in is removed afterwards. Commits cannot be marked manually while the bisection is running.
          */
-        post: operations["checkout_revision"];
+        post: operations["run_bisect"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/commits": {
+    "/api/v1/flows/{id}/diff": {
         parameters: {
             query?: never;
             header?: never;
@@ -114,10 +176,10 @@ export interface paths {
             cookie?: never;
         };
         /**
-         * List commits
-         * @description List the commits in a range similar to `git log`, the commits are always ordered from newest to oldest in the tree.
+         * Diff debug flow
+         * @description Compare the debug flow as it is stored in a Git revision of the repository with the stored debug flow. Nodes and edges are matched by their IDs, changed nodes and edges list the paths of the changed fields, e.g. `data.state`.
          */
-        get: operations["list_commits"];
+        get: operations["diff_flow"];
         put?: never;
         post?: never;
         delete?: never;
@@ -126,18 +188,38 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/diff": {
+    "/api/v1/flows/{id}/duplicate": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
+        get?: never;
+        put?: never;
         /**
-         * Get diff
-         * @description Get the diff of a commit range. The diff consists of the patch describing the differences between the two revisions, similar to the output of `git diff rev1..rev2`, the stats of this diff similar to `git diff --stat` and a map of old source file contents, these can be used to expand diffs on the client side.
+         * Duplicate debug flow
+         * @description Store a copy of a debug flow under a new name
          */
-        get: operations["get_diff"];
+        post: operations["duplicate_flow"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/{id}/export": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Export debug flow
+         * @description Export a debug flow to a text format. The Markdown report starts at the root node and follows the edges of the flow, branches of the flow become nested sections. Git revisions of the nodes are resolved to show the summary, author and hash of the linked commits. The Mermaid and DOT graphs color status nodes by their state and label all nodes with their Git revision.
+         */
+        get: operations["export_flow"];
         put?: never;
         post?: never;
         delete?: never;
@@ -146,7 +228,7 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/references": {
+    "/api/v1/flows/{id}/history": {
         parameters: {
             query?: never;
             header?: never;
@@ -154,10 +236,10 @@ export interface paths {
             cookie?: never;
         };
         /**
-         * List references
-         * @description List all references in the repository, optionally filtered by a glob pattern and type.
+         * List debug flow versions
+         * @description List the recorded versions of a debug flow, newest first. Versions are only recorded if the flow history is enabled, they are stored as commits on `refs/debug-flow/history`.
          */
-        get: operations["list_references"];
+        get: operations["list_flow_versions"];
         put?: never;
         post?: never;
         delete?: never;
@@ -166,7 +248,7 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/repository/status": {
+    "/api/v1/flows/{id}/history/{version}": {
         parameters: {
             query?: never;
             header?: never;
@@ -174,10 +256,10 @@ export interface paths {
             cookie?: never;
         };
         /**
-         * Get repository status
-         * @description Get the current status of the repository, including the current HEAD commit and branch.
+         * Get debug flow version
+         * @description Get a debug flow as it was recorded in a version. The revision in the response is the revision of the debug flow in that version.
          */
-        get: operations["get_repository_status"];
+        get: operations["get_flow_version"];
         put?: never;
         post?: never;
         delete?: never;
@@ -186,228 +268,1977 @@ export interface paths {
         patch?: never;
         trace?: never;
     };
-    "/api/v1/git/tags": {
+    "/api/v1/flows/{id}/nodes/{node_id}/checkout": {
         parameters: {
             query?: never;
             header?: never;
             path?: never;
             cookie?: never;
         };
+        get?: never;
+        put?: never;
         /**
-         * List tags
-         * @description List the tags in the repository
+         * Check out node revision
+         * @description Check out the Git revision of the node in the working directory of the repository. Uncommitted changes which would be overwritten block the checkout in the mode `refuse`, `force` discards them. The mode `stash` stashes the changes to tracked files first and attaches the stash to the node, it can be restored with `/{id}/nodes/{node_id}/stash/restore`.
          */
-        get: operations["list_tags"];
+        post: operations["checkout_node_revision"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/{id}/nodes/{node_id}/run": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
         put?: never;
         /**
-         * Create new tag
-         * @description Creates a new lightweight git tag with the specified name on the provided revision.
+         * Run command at node revision
+         * @description Check out the Git revision of the node in the worktree `.debug-flow/worktrees/<flow-id>/<node-id>`, named `debug-flow-<flow-id>.<node-id>`, and run a shell command in it. The working tree of the repository is not touched. The command runs in the background, its output is streamed as `run` events on `/{id}/runs/stream`. Once it finished, the exit code and the end of the output are attached to the node and status nodes are marked as success or fail.
          */
-        post: operations["create_tag"];
+        post: operations["run_node_command"];
         delete?: never;
         options?: never;
         head?: never;
         patch?: never;
         trace?: never;
     };
-}
-export type webhooks = Record<string, never>;
-export interface components {
-    schemas: {
-        ApiStatusDetailResponse: {
-            /** @description More details */
-            details?: string[] | null;
-            /** @description Error message */
-            message: string;
-            /** @description Canonical reason for the error */
-            reason: string;
-            /**
-             * Format: int32
-             * @description HTTP status code
-             */
-            status: number;
-        };
-        /** @description Basic serializable API status response */
-        ApiStatusResponse: {
-            /** @description Canonical reason for the error */
-            reason: string;
-            /**
-             * Format: int32
-             * @description HTTP status code
-             */
-            status: number;
-        };
-        Branch: {
-            /** @description Commit ID of the branch head */
-            head: components["schemas"]["Commit"];
-            /** @description Name of the branch */
-            name: string;
+    "/api/v1/flows/{id}/nodes/{node_id}/stash/restore": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        Commit: {
-            author: components["schemas"]["Signature"];
-            body: string;
-            committer: components["schemas"]["Signature"];
-            id: string;
-            summary: string;
-            /** Format: date-time */
-            time: string;
+        get?: never;
+        put?: never;
+        /**
+         * Restore node stash
+         * @description Apply the last stash attached to the node by `/{id}/nodes/{node_id}/checkout` to the working directory of the repository and drop it. The stash is removed from the node.
+         */
+        post: operations["restore_node_stash"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/{id}/pin": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        CommitWithReferences: components["schemas"]["Commit"] & {
-            /** @description References pointing to the commit */
-            references: components["schemas"]["ReferenceMetadata"][];
+        get?: never;
+        put?: never;
+        /**
+         * Pin debug flow revisions
+         * @description Replace the Git revisions linked to the nodes of the debug flow by the full hashes of the commits they resolve to, so rebases or moved branches do not change what the nodes refer to. Revisions which do not resolve are kept.
+         */
+        post: operations["pin_flow_revisions"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/flows/{id}/validate": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        CreateFlowRequest: {
-            name: string;
+        /**
+         * Validate debug flow revisions
+         * @description Resolve the Git revisions linked to the nodes of the debug flow and report revisions which do not resolve anymore, commits which are not reachable from any branch or tag and branches or tags which moved to another commit since they were linked.
+         */
+        get: operations["validate_flow"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/blame": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        CreateFlowResponse: {
-            flow: components["schemas"]["FlowMetadata"];
+        /**
+         * Blame file
+         * @description Get the commit which last changed each line of a file, similar to `git blame`. Lines are grouped into hunks of consecutive lines changed in the same commit. If the file was renamed, lines changed before the rename are blamed on their original commits unless `followRenames` is false.
+         */
+        get: operations["get_blame"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/blob": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        Diff: {
-            /** @description Map of old source paths to the old content */
-            oldSources: components["schemas"]["HashMap"];
-            /** @description Patch between old and new */
-            patch: string;
-            /** @description Stats of the diff */
-            stats: components["schemas"]["DiffStats"];
+        /**
+         * Read file
+         * @description Read a file at a revision, similar to `git show <rev>:<path>`. The content is only returned for text files, binary files only report their size. Symbolic links are not resolved, their target path is returned instead.
+         */
+        get: operations["read_blob"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/branches": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
         };
-        DiffStats: {
-            /** @description Number of deletions */
-            deletions: number;
-            /** @description Number of files changed */
-            filesChanged: number;
-            /** @description Number of insertions */
-            insertions: number;
-            /** @description Number of lines in the old versions of all affected files */
+        /**
+         * List branches
+         * @description List all local branches in the repository, optionally filtered by a glob pattern.
+         */
+        get: operations["list_branches"];
+        put?: never;
+        /**
+         * Create new branch
+         * @description Creates a new branch at the specified revision.
+         */
+        post: operations["create_branch"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/commit/{revision}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Get commit for a revision
+         * @description Get a single commit by its revision.
+         *         The revision can be anything accepted by `git rev-parse`. For a branch it will return the HEAD of the branch.
+         */
+        get: operations["get_revision"];
+        put?: never;
+        /**
+         * Checkout commit for a revision
+         * @description Checkout a commit by its revision.
+         *         The revision can be anything accepted by `git rev-parse`. For a branch it will checkout the HEAD of the branch.
+         *         Uncommitted changes which would be overwritten block the checkout in the mode `refuse`, the mode `stash` stashes the changes to tracked files first and `force` discards them.
+         *         With `flowId` and `nodeId` the commit is checked out with a detached HEAD in the worktree of the node, which is added if it does not exist yet. Changes in an existing worktree of the node are discarded.
+         */
+        post: operations["checkout_revision"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/commits": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List commits
+         * @description List the commits in a range similar to `git log`, the commits are always ordered from newest to oldest in the tree. With a `path` only the commits changing the file or directory are listed, following renames of a file unless `followRenames` is false.
+         */
+        get: operations["list_commits"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/diff": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Get diff
+         * @description Get the diff of a commit range. The diff consists of the patch describing the differences between the two revisions, similar to the output of `git diff rev1..rev2`, the stats of this diff similar to `git diff --stat` and a map of old source file contents, these can be used to expand diffs on the client side. The same changes are also provided as structured `files`, with their paths, status, similarity, binary flag and hunks of typed lines with old and new line numbers, so the patch does not need to be parsed. The diff can be narrowed to paths and tuned with the number of context lines, whitespace handling, rename and copy detection thresholds and a maximum file size.
+         */
+        get: operations["get_diff"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/references": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List references
+         * @description List all references in the repository, optionally filtered by a glob pattern and type.
+         */
+        get: operations["list_references"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/repository/status": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * Get repository status
+         * @description Get the current status of the repository, including the current HEAD commit and branch and the linked worktrees with the nodes they belong to.
+         */
+        get: operations["get_repository_status"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/tags": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List tags
+         * @description List the tags in the repository
+         */
+        get: operations["list_tags"];
+        put?: never;
+        /**
+         * Create new tag
+         * @description Creates a new lightweight git tag with the specified name on the provided revision.
+         */
+        post: operations["create_tag"];
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/tree": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List tree
+         * @description List the entries of a directory at a revision, similar to `git ls-tree`. The working directory is not considered.
+         */
+        get: operations["list_tree"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/worktrees": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        /**
+         * List worktrees
+         * @description List the linked worktrees of the repository, like `git worktree list`, and the nodes the worktrees in `.debug-flow/worktrees/` belong to.
+         */
+        get: operations["list_worktrees"];
+        put?: never;
+        post?: never;
+        delete?: never;
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/worktrees/{name}": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        post?: never;
+        /**
+         * Prune worktree
+         * @description Remove a linked worktree and its working directory, changes in it are lost. Locked worktrees are not removed.
+         */
+        delete: operations["prune_worktree"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+    "/api/v1/git/worktrees/{name}/lock": {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        get?: never;
+        put?: never;
+        /**
+         * Lock worktree
+         * @description Lock a linked worktree so it is not pruned, like `git worktree lock`.
+         */
+        post: operations["lock_worktree"];
+        /**
+         * Unlock worktree
+         * @description Unlock a linked worktree, like `git worktree unlock`.
+         */
+        delete: operations["unlock_worktree"];
+        options?: never;
+        head?: never;
+        patch?: never;
+        trace?: never;
+    };
+}
+export type webhooks = Record<string, never>;
+export interface components {
+    schemas: {
+        /** @description Data of an action node */
+        ActionNodeData: {
+            /** @description Results attached to the node, e.g. of commands run at the revision */
+            attachments?: components["schemas"]["Attachment"][];
+            /** @description Markdown description of the node */
+            description: string;
+            git?: null | components["schemas"]["GitMetadata"];
+            /** @description Title of the node */
+            title: string;
+            [key: string]: unknown;
+        };
+        ApiStatusDetailResponse: {
+            /** @description More details */
+            details?: string[] | null;
+            /** @description Error message */
+            message: string;
+            /** @description Canonical reason for the error */
+            reason: string;
+            /**
+             * Format: int32
+             * @description HTTP status code
+             */
+            status: number;
+        };
+        /** @description Basic serializable API status response */
+        ApiStatusResponse: {
+            /** @description Canonical reason for the error */
+            reason: string;
+            /**
+             * Format: int32
+             * @description HTTP status code
+             */
+            status: number;
+        };
+        /** @description Result attached to a node */
+        Attachment: (components["schemas"]["CommandResult"] & {
+            /** @enum {string} */
+            type: "commandResult";
+        }) | (components["schemas"]["StashRecord"] & {
+            /** @enum {string} */
+            type: "stash";
+        });
+        /** @description Person who created or changed a debug flow, taken from the Git configuration of the repository */
+        Author: {
+            /** @description Value of `user.email` */
+            email: string;
+            /** @description Value of `user.name` */
+            name: string;
+        };
+        /** @description Revisions marked in a bisection, all revisions are full commit IDs */
+        Bisect: {
+            /** @description Newest known bad commit */
+            bad: string;
+            /** @description Commits without the problem */
+            good: string[];
+            /** @description Commits which cannot be tested */
+            skipped: string[];
+        };
+        BisectResponse: {
+            bisect: components["schemas"]["BisectSession"];
+            /** @description Revision of the debug flow with the nodes added by the bisection */
+            revision: string;
+        };
+        /** @description Bisection of a debug flow */
+        BisectSession: {
+            /** @description Marked commits */
+            bisect: components["schemas"]["Bisect"];
+            /** @description ID of the node of the commit to test, `None` once the bisection is finished */
+            nodeId?: string | null;
+            /** @description Next step of the bisection */
+            step: components["schemas"]["BisectStep"];
+        };
+        /** @description Next step of a bisection */
+        BisectStep: {
+            /** @description Number of commits which can still be the first bad commit */
+            candidates: number;
+            commit: components["schemas"]["Commit"];
+            /**
+             * Format: int32
+             * @description Estimated number of remaining tests
+             */
+            steps: number;
+            /** @enum {string} */
+            type: "test";
+        } | {
+            commit: components["schemas"]["Commit"];
+            /** @enum {string} */
+            type: "firstBad";
+        } | {
+            candidates: components["schemas"]["Commit"][];
+            /** @enum {string} */
+            type: "inconclusive";
+        };
+        /**
+         * @description Verdict on a tested revision
+         * @enum {string}
+         */
+        BisectVerdict: "good" | "bad" | "skip";
+        /** @description Consecutive lines of a file last changed in the same commit */
+        BlameHunk: {
+            /** @description Commit which last changed the lines */
+            commit: components["schemas"]["CommitWithReferences"];
+            /** @description Whether the lines are blamed on the oldest considered commit, they can be older */
+            isBoundary: boolean;
+            /** @description Number of lines in the hunk */
+            lines: number;
+            /** @description Number of the first line of the hunk in the file of the commit, starting at 1 */
+            origStartLine: number;
+            /** @description Path of the file in the commit, it differs from the blamed path if the file was renamed since */
+            path: string;
+            /** @description Number of the first line of the hunk in the blamed file, starting at 1 */
+            startLine: number;
+        };
+        BlameResponse: {
+            /** @description Hunks of consecutive lines last changed in the same commit, ordered by line */
+            hunks: components["schemas"]["BlameHunk"][];
+            /** @description Path of the blamed file */
+            path: string;
+        };
+        /** @description File at a revision */
+        Blob: {
+            /** @description Content of the file, `None` for symbolic links, binary files or content which is not valid UTF-8 */
+            content?: string | null;
+            /** @description ID of the blob */
+            id: string;
+            /** @description Whether the content is binary, as detected by git */
+            isBinary: boolean;
+            /** @description Kind of the file, either a regular file or a symbolic link */
+            kind: components["schemas"]["TreeEntryKind"];
+            /**
+             * Format: int32
+             * @description Git file mode of the file
+             */
+            mode: number;
+            /** @description Path of the file relative to the repository root */
+            path: string;
+            /** @description Size of the blob in bytes */
+            size: number;
+            /** @description Target path of a symbolic link, it is not resolved */
+            symlinkTarget?: string | null;
+        };
+        Branch: {
+            /** @description Commit ID of the branch head */
+            head: components["schemas"]["Commit"];
+            /** @description Name of the branch */
+            name: string;
+        };
+        /**
+         * @description How uncommitted changes are handled when checking out a revision
+         * @enum {string}
+         */
+        CheckoutMode: "refuse" | "stash" | "force";
+        CheckoutNodeRequest: {
+            /** @description How uncommitted changes in the working directory are handled, defaults to `refuse` */
+            mode?: components["schemas"]["CheckoutMode"];
+        };
+        CheckoutNodeResponse: {
+            /** @description The checked out commit */
+            commit: components["schemas"]["CommitWithReferences"];
+            /** @description Revision of the debug flow, only set if the stash was attached to the node */
+            revision?: string | null;
+            stash?: null | components["schemas"]["StashRecord"];
+        };
+        CheckoutResponse: components["schemas"]["CommitWithReferences"] & {
+            stash?: null | components["schemas"]["Stash"];
+        };
+        /** @description Result of a command run in a checkout of the revision of a node */
+        CommandResult: {
+            /** @description Shell command */
+            command: string;
+            /** @description ID of the commit the command was run on */
+            commit: string;
+            /**
+             * Format: int32
+             * @description Exit code of the command, `None` if it was terminated by a signal or timed out
+             */
+            exitCode?: number | null;
+            /**
+             * Format: date-time
+             * @description Date the command finished
+             */
+            finishedAt: string;
+            /**
+             * Format: date-time
+             * @description Date the command was started
+             */
+            startedAt: string;
+            /** @description End of the standard error output */
+            stderr: string;
+            /** @description End of the standard output */
+            stdout: string;
+            /** @description Whether the command was killed after the timeout */
+            timedOut: boolean;
+        };
+        Commit: {
+            author: components["schemas"]["Signature"];
+            body: string;
+            committer: components["schemas"]["Signature"];
+            id: string;
+            summary: string;
+            /** Format: date-time */
+            time: string;
+        };
+        CommitWithReferences: components["schemas"]["Commit"] & {
+            /** @description References pointing to the commit */
+            references: components["schemas"]["ReferenceMetadata"][];
+        };
+        CreateFlowRequest: {
+            name: string;
+            /** @description ID of the template to seed the nodes and edges from */
+            template?: string | null;
+        };
+        CreateFlowResponse: {
+            flow: components["schemas"]["FlowMetadata"];
+            /** @description Revision of the stored debug flow, send it when storing changes of the debug flow */
+            revision?: string;
+        };
+        Diff: {
+            /** @description Changes of the files, the same changes as in the patch */
+            files: components["schemas"]["DiffFile"][];
+            /** @description Map of old source paths to the old content */
+            oldSources: components["schemas"]["HashMap"];
+            /** @description Patch between old and new */
+            patch: string;
+            /** @description Stats of the diff */
+            stats: components["schemas"]["DiffStats"];
+        };
+        /** @description Changes of a single file in a diff */
+        DiffFile: {
+            /** @description Number of deleted lines */
+            deletions: number;
+            /** @description Hunks of the changes */
+            hunks: components["schemas"]["DiffHunk"][];
+            /** @description Number of added lines */
+            insertions: number;
+            /** @description Whether the old or new file is binary, binary files have no hunks */
+            isBinary: boolean;
+            /** @description Path of the new file, `None` for deleted files */
+            newPath?: string | null;
+            /** @description Path of the old file, `None` for added files */
+            oldPath?: string | null;
+            /**
+             * Format: int32
+             * @description Similarity of the old and new file in percent, only set for renamed and copied files
+             */
+            similarity?: number | null;
+            /** @description Kind of the change */
+            status: components["schemas"]["DiffFileStatus"];
+        };
+        /**
+         * @description Change of a file in a diff
+         * @enum {string}
+         */
+        DiffFileStatus: "added" | "deleted" | "modified" | "renamed" | "copied" | "typechange";
+        /** @description Hunk of consecutive changed lines with their context */
+        DiffHunk: {
+            /** @description Header of the hunk, e.g. `@@ -1,3 +1,4 @@ fn main()` */
+            header: string;
+            /** @description Lines of the hunk */
+            lines: components["schemas"]["DiffLine"][];
+            /**
+             * Format: int32
+             * @description Number of lines of the hunk in the new file
+             */
+            newLines: number;
+            /**
+             * Format: int32
+             * @description First line of the hunk in the new file
+             */
+            newStart: number;
+            /**
+             * Format: int32
+             * @description Number of lines of the hunk in the old file
+             */
+            oldLines: number;
+            /**
+             * Format: int32
+             * @description First line of the hunk in the old file
+             */
+            oldStart: number;
+        };
+        /** @description Line of a hunk */
+        DiffLine: {
+            /** @description Content of the line including its newline */
+            content: string;
+            /** @description Kind of the line */
+            kind: components["schemas"]["DiffLineKind"];
+            /**
+             * Format: int32
+             * @description Line number in the new file, `None` for deletions
+             */
+            newLineNo?: number | null;
+            /**
+             * Format: int32
+             * @description Line number in the old file, `None` for additions
+             */
+            oldLineNo?: number | null;
+        };
+        /**
+         * @description Kind of a line in a hunk
+         * @enum {string}
+         */
+        DiffLineKind: "context" | "addition" | "deletion" | "noNewlineAtEof";
+        DiffStats: {
+            /** @description Number of deletions */
+            deletions: number;
+            /** @description Number of files changed */
+            filesChanged: number;
+            /** @description Number of insertions */
+            insertions: number;
+            /** @description Number of lines in the old versions of all affected files */
             totalOldNumLines: number;
         };
-        FlowData: {
-            /** @description Name of the debug flow */
-            name: string;
-            /** @description Representation of the reactflow state */
-            reactflow: components["schemas"]["ReactFlowState"];
+        DuplicateFlowRequest: {
+            /** @description Name of the copy, the ID of the copy is derived from it */
+            name: string;
+        };
+        /** @description Edge between two nodes of a debug flow */
+        Edge: {
+            /** @description Unique ID of the edge in the flow */
+            id: string;
+            /** @description ID of the source node */
+            source: string;
+            /** @description ID of the target node */
+            target: string;
+            [key: string]: unknown;
+        };
+        /** @description Changes of a node or an edge which exists in both versions */
+        ElementChange: {
+            /** @description Changed fields */
+            changes: components["schemas"]["FieldChange"][];
+            /** @description ID of the node or edge */
+            id: string;
+        };
+        /** @description Change of a single field */
+        FieldChange: {
+            /** @description Path of the field, nested fields are separated by dots */
+            field: string;
+            /** @description New value, `null` if the field was removed */
+            new: unknown;
+            /** @description Old value, `null` if the field was added */
+            old: unknown;
+        };
+        FlowData: {
+            /**
+             * Format: date-time
+             * @description Date the debug flow was created
+             */
+            createdAt?: string | null;
+            createdBy?: null | components["schemas"]["Author"];
+            /** @description Name of the debug flow */
+            name: string;
+            /** @description Representation of the reactflow state */
+            reactflow: components["schemas"]["ReactFlowState"];
+            /**
+             * Format: int32
+             * @description Version of the schema the debug flow is stored in, defaults to the current version
+             */
+            schemaVersion?: number;
+            /**
+             * Format: date-time
+             * @description Date of the last change of the debug flow
+             */
+            updatedAt?: string | null;
+            updatedBy?: null | components["schemas"]["Author"];
+        };
+        /** @description Differences between two versions of a debug flow */
+        FlowDiff: {
+            /** @description Edges only in the new version */
+            addedEdges: components["schemas"]["Edge"][];
+            /** @description Nodes only in the new version */
+            addedNodes: components["schemas"]["Node"][];
+            /** @description Edges in both versions with different fields */
+            changedEdges: components["schemas"]["ElementChange"][];
+            /** @description Nodes in both versions with different fields */
+            changedNodes: components["schemas"]["ElementChange"][];
+            name?: null | components["schemas"]["FieldChange"];
+            /** @description Edges only in the old version */
+            removedEdges: components["schemas"]["Edge"][];
+            /** @description Nodes only in the old version */
+            removedNodes: components["schemas"]["Node"][];
+        };
+        FlowMetadata: {
+            /**
+             * Format: date-time
+             * @description Date the debug flow was created
+             */
+            createdAt?: string | null;
+            createdBy?: null | components["schemas"]["Author"];
+            /** @description ID of the debug flow */
+            id: string;
+            /**
+             * Format: date-time
+             * @description Date of the last change, the modification time of the file for debug flows without an update date
+             */
+            lastModifiedDate: string;
+            /** @description Name of the debug flow */
+            name: string;
+            /** @description Number of edges in the debug flow */
+            numEdges: number;
+            /** @description Number of nodes in the debug flow */
+            numNodes: number;
+            /**
+             * Format: date-time
+             * @description Date of the last change of the debug flow
+             */
+            updatedAt?: string | null;
+            updatedBy?: null | components["schemas"]["Author"];
+        };
+        /** @description Result of validating the revisions of a debug flow */
+        FlowValidation: {
+            /** @description Number of nodes linked to a revision */
+            checked: number;
+            /** @description Revisions which need attention, in the order of the nodes */
+            issues: components["schemas"]["RevisionIssue"][];
+        };
+        /** @description Recorded version of a debug flow */
+        FlowVersion: {
+            /** @description Author configured in the repository when the version was recorded */
+            author: components["schemas"]["Author"];
+            /**
+             * Format: date-time
+             * @description Date the version was recorded
+             */
+            date: string;
+            /** @description Revision of the debug flow in this version, see [`super::Flow::revision`] */
+            revision: string;
+            /** @description ID of the commit on the history reference, use this to fetch the version */
+            version: string;
+        };
+        FullFlowRequestResponse: {
+            flow: components["schemas"]["FlowData"];
+            /** @description Revision of the stored debug flow, this is the Git blob ID of the stored file.
+             *
+             *     When storing a debug flow, this is the revision the changes are based on. If it is set and the
+             *     stored debug flow has a different revision, the debug flow is not stored. */
+            revision?: string;
+        };
+        /** @description Git revision linked to a node */
+        GitMetadata: {
+            /** @description Commit ID, tag name or branch name */
+            rev: string;
+            /** @description Summary of the referred commit, for branches the summary of the branch head */
+            summary: string;
+            /** @description Kind of the revision */
+            type: components["schemas"]["GitMetadataKind"];
+        };
+        /**
+         * @description Kind of the Git revision a node is linked to
+         * @enum {string}
+         */
+        GitMetadataKind: "commit" | "tag" | "branch";
+        HashMap: {
+            [key: string]: string;
+        };
+        /** @description Range of characters in a snippet which matched a search term */
+        Highlight: {
+            /** @description Index after the last matching character */
+            end: number;
+            /** @description Index of the first matching character */
+            start: number;
+        };
+        ListBranchesResponse: {
+            /** @description Found branches */
+            branches: components["schemas"]["Branch"][];
+        };
+        ListCommitsResponse: {
+            /** @description Array of commits between the base and head commit IDs
+             *     in reverse chronological order. */
+            commits: components["schemas"]["CommitWithReferences"][];
+        };
+        ListDiffsResponse: {
+            /** @description Diff between base and head revision */
+            diff: components["schemas"]["Diff"];
+        };
+        ListFlowVersionsResponse: {
+            versions: components["schemas"]["FlowVersion"][];
+        };
+        ListFlowsResponse: {
+            flows: components["schemas"]["FlowMetadata"][];
+        };
+        ListReferencesResponse: {
+            /** @description Array of references */
+            references: components["schemas"]["ResolvedReference"][];
+        };
+        ListTagsResponse: {
+            tags: components["schemas"]["TaggedCommit"][];
+        };
+        ListTemplatesResponse: {
+            templates: components["schemas"]["TemplateMetadata"][];
+        };
+        ListTreeResponse: {
+            /** @description Files, directories, symbolic links and submodules in the directory */
+            entries: components["schemas"]["TreeEntry"][];
+            /** @description Path of the listed directory */
+            path: string;
+        };
+        ListWorktreesResponse: {
+            /** @description Linked worktrees of nodes */
+            nodeWorktrees: components["schemas"]["NodeWorktree"][];
+            /** @description Linked worktrees ordered by name */
+            worktrees: components["schemas"]["Worktree"][];
+        };
+        MarkBisectRequest: {
+            /** @description Verdict on the commit to test */
+            verdict: components["schemas"]["BisectVerdict"];
+        };
+        /** @description Node of a debug flow */
+        Node: components["schemas"]["NodeKind"] & {
+            /** @description Unique ID of the node in the flow */
+            id: string;
+            /** @description Position of the node on the canvas */
+            position: components["schemas"]["Position"];
+            [key: string]: unknown;
+        };
+        /** @description Type and data of a node */
+        NodeKind: {
+            data: components["schemas"]["ActionNodeData"];
+            /** @enum {string} */
+            type: "actionNode";
+        } | {
+            data: components["schemas"]["StatusNodeData"];
+            /** @enum {string} */
+            type: "statusNode";
+        };
+        /** @description Linked worktree belonging to a node of a debug flow */
+        NodeWorktree: {
+            /** @description ID of the debug flow of the node */
+            flowId: string;
+            /** @description Name of the linked worktree */
+            name: string;
+            /** @description ID of the node the revision is checked out for */
+            nodeId: string;
+        };
+        PinFlowRevisionsResponse: {
+            /** @description Number of nodes whose revision was replaced by a full commit hash */
+            pinned: number;
+            /** @description Revision of the debug flow after pinning */
+            revision: string;
+        };
+        /** @description Position of a node on the canvas */
+        Position: {
+            /** Format: double */
+            x: number;
+            /** Format: double */
+            y: number;
+        };
+        ReactFlowState: {
+            /** @description Edges of the reactflow state */
+            edges: components["schemas"]["Edge"][];
+            /** @description Nodes of the reactflow state */
+            nodes: components["schemas"]["Node"][];
+        };
+        /** @enum {string} */
+        ReferenceKind: "tag" | "branch" | "note" | "remotebranch";
+        ReferenceMetadata: {
+            kind: components["schemas"]["ReferenceKind"];
+            name: string;
+        };
+        RenameFlowRequest: {
+            /** @description New name of the debug flow, the ID of the debug flow is derived from it */
+            name: string;
+        };
+        RepositoryStatusResponse: components["schemas"]["Status"] & {
+            /** @description Linked worktrees of nodes, a subset of `linkedWorktrees` */
+            nodeWorktrees: components["schemas"]["NodeWorktree"][];
+        };
+        ResolvedReference: components["schemas"]["ReferenceMetadata"] & {
+            target: components["schemas"]["Commit"];
+        };
+        RestoreStashResponse: {
+            /** @description Revision of the debug flow without the stash attached to the node */
+            revision: string;
+            /** @description The restored stash */
+            stash: components["schemas"]["StashRecord"];
+        };
+        /** @description Revision of a node which needs attention */
+        RevisionIssue: {
+            /** @description Commit the revision resolves to now, `None` if it is missing */
+            commit?: string | null;
+            /** @description Kind of the linked revision */
+            kind: components["schemas"]["GitMetadataKind"];
+            /** @description Human readable description of the problem */
+            message: string;
+            /** @description ID of the node */
+            nodeId: string;
+            /** @description Problem of the revision */
+            problem: components["schemas"]["RevisionProblem"];
+            /** @description Linked revision */
+            rev: string;
+        };
+        /**
+         * @description Problem of a revision linked to a node
+         * @enum {string}
+         */
+        RevisionProblem: "missing" | "unreachable" | "moved";
+        RunBisectRequest: {
+            /** @description Shell command testing a commit, it is run with `sh -c` in the root of the worktree */
+            command: string;
+            /**
+             * Format: int64
+             * @description A test is killed if it runs longer, defaults to 300 seconds
+             */
+            timeoutSecs?: number | null;
+        };
+        RunCommandRequest: {
+            /** @description Shell command, it is run with `sh -c` in the root of the worktree */
+            command: string;
+            /**
+             * Format: int64
+             * @description The command is killed if it runs longer, defaults to 300 seconds
+             */
+            timeoutSecs?: number | null;
+        };
+        RunCommandResponse: {
+            /**
+             * Format: int64
+             * @description ID of the run, the events of the run in the run stream of the debug flow carry it
+             */
+            runId: number;
+        };
+        /**
+         * @description Part of a debug flow a search matched
+         * @enum {string}
+         */
+        SearchField: "name" | "title" | "description" | "revision" | "summary";
+        SearchFlowsResponse: {
+            matches: components["schemas"]["SearchMatch"][];
+        };
+        /** @description Match of a search in a debug flow */
+        SearchMatch: {
+            /** @description Matching part of the debug flow */
+            field: components["schemas"]["SearchField"];
+            /** @description ID of the debug flow */
+            flowId: string;
+            /** @description Name of the debug flow */
+            flowName: string;
+            /** @description Character ranges of the search terms in the snippet */
+            highlights: components["schemas"]["Highlight"][];
+            /** @description ID of the matching node, `None` if the name of the debug flow matched */
+            nodeId?: string | null;
+            /** @description Excerpt of the matching text */
+            snippet: string;
+        };
+        Signature: {
+            email: string;
+            name: string;
+        };
+        StartBisectRequest: {
+            /** @description Revision which has the problem */
+            bad: string;
+            /** @description Revisions without the problem */
+            good: string[];
+        };
+        /** @description Stash created for a checkout */
+        Stash: {
+            /** @description ID of the stash commit */
+            id: string;
+            /** @description Message of the stash */
+            message: string;
+        };
+        /** @description Uncommitted changes stashed to check out the revision of a node in the working directory */
+        StashRecord: {
+            /** @description ID of the commit which was checked out */
+            commit: string;
+            /**
+             * Format: date-time
+             * @description Date the changes were stashed
+             */
+            createdAt: string;
+            /** @description ID of the stash commit */
+            id: string;
+            /** @description Message of the stash */
+            message: string;
+        };
+        Status: {
+            /** @description Paths with conflicts */
+            conflicts: components["schemas"]["Vec"];
+            /** @description Name of the current branch, not set if `is_detached_head` is true */
+            currentBranch?: string | null;
+            /** @description The commit current HEAD points to */
+            head: components["schemas"]["CommitWithReferences"];
+            /** @description Status of the index */
+            index: components["schemas"]["TreeStatus"];
+            /** @description Whether the head is detached */
+            isDetachedHead: boolean;
+            /** @description Whether the worktree or index have changes */
+            isDirty: boolean;
+            /** @description Linked worktrees of the repository */
+            linkedWorktrees: components["schemas"]["Worktree"][];
+            /** @description Status in the worktree */
+            worktree: components["schemas"]["TreeStatus"];
+        };
+        /** @description Data of a status node */
+        StatusNodeData: {
+            /** @description Results attached to the node, e.g. of commands run at the revision */
+            attachments?: components["schemas"]["Attachment"][];
+            /** @description Markdown description of the node */
+            description: string;
+            git?: null | components["schemas"]["GitMetadata"];
+            /** @description Whether this is the root node of the flow, root nodes have no target handle and cannot be deleted */
+            isRootNode: boolean;
+            /** @description State of the status */
+            state: components["schemas"]["StatusNodeState"];
+            /** @description Title of the node */
+            title: string;
+            [key: string]: unknown;
+        };
+        /**
+         * @description State of a status node
+         * @enum {string}
+         */
+        StatusNodeState: "unknown" | "fail" | "progress" | "success";
+        StoreFlowResponse: {
+            /** @description Revision of the stored debug flow */
+            revision: string;
+        };
+        TaggedCommit: {
+            /** @description Commit the tag is on */
+            commit: components["schemas"]["Commit"];
+            /** @description Tag on the commit */
+            tag: string;
+        };
+        /** @description Metadata of a template */
+        TemplateMetadata: {
+            /** @description ID of the template, this is the stem of the file it is stored in */
+            id: string;
+            /** @description Name of the template */
+            name: string;
+            /** @description Number of edges the template seeds */
+            numEdges: number;
+            /** @description Number of nodes the template seeds */
+            numNodes: number;
+        };
+        /** @description Entry of a tree, i.e. a file or directory at a revision */
+        TreeEntry: {
+            /** @description ID of the blob, tree or submodule commit of the entry */
+            id: string;
+            /** @description Kind of the entry */
+            kind: components["schemas"]["TreeEntryKind"];
+            /**
+             * Format: int32
+             * @description Git file mode of the entry, e.g. `0o100644` for a regular file or `0o100755` for an executable file
+             */
+            mode: number;
+            /** @description Name of the entry */
+            name: string;
+            /** @description Path of the entry relative to the repository root */
+            path: string;
+            /** @description Size of the blob in bytes, only set for files and symbolic links */
+            size?: number | null;
+        };
+        /**
+         * @description Kind of an entry of a tree
+         * @enum {string}
+         */
+        TreeEntryKind: "file" | "directory" | "symlink" | "submodule";
+        TreeStatus: {
+            /** @description Deleted files */
+            deletedFiles: components["schemas"]["Vec"];
+            /** @description Modified files */
+            modifiedFiles: components["schemas"]["Vec"];
+            /** @description Added files */
+            newFiles: components["schemas"]["Vec"];
+            /** @description Renamed files */
+            renamedFiles: components["schemas"]["Vec"];
+        };
+        Vec: string[];
+        /** @description Linked worktree of a repository */
+        Worktree: {
+            /** @description ID of the commit checked out in the worktree, `None` if the worktree is not valid */
+            head?: string | null;
+            /** @description Whether the worktree is locked, locked worktrees are not pruned */
+            isLocked: boolean;
+            /** @description Whether the working directory of the worktree exists */
+            isValid: boolean;
+            /** @description Reason the worktree was locked for */
+            lockReason?: string | null;
+            /** @description Name of the worktree in the repository */
+            name: string;
+            /** @description Working directory of the worktree */
+            path: string;
+        };
+    };
+    responses: never;
+    parameters: never;
+    requestBodies: never;
+    headers: never;
+    pathItems: never;
+}
+export type $defs = Record<string, never>;
+export interface operations {
+    list_flows: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description List debug flows */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ListFlowsResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    create_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CreateFlowRequest"];
+            };
+        };
+        responses: {
+            /** @description Debug Flow created */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CreateFlowResponse"];
+                };
+            };
+            /** @description Template not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow exists already */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    search_flows: {
+        parameters: {
+            query: {
+                /** @description Search terms separated by whitespace, all terms must occur in a text for it to match */
+                q: string;
+                /** @description Maximum number of matches */
+                limit?: number | null;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Matches of the search */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["SearchFlowsResponse"];
+                };
+            };
+        };
+    };
+    list_templates: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Available templates */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ListTemplatesResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    get_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Debug flow is available */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FullFlowRequestResponse"];
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    store_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["FullFlowRequestResponse"];
+            };
+        };
+        responses: {
+            /** @description Debug flow is stored */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["StoreFlowResponse"];
+                };
+            };
+            /** @description Debug flow was modified in the meantime */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FullFlowRequestResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    delete_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Debug flow is deleted */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusResponse"];
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    rename_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["RenameFlowRequest"];
+            };
+        };
+        responses: {
+            /** @description Debug flow is renamed */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CreateFlowResponse"];
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow with the new ID exists already */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    get_bisect: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Running bisection */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["BisectSession"];
+                };
+            };
+            /** @description No bisection is running */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    start_bisect: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["StartBisectRequest"];
+            };
+        };
+        responses: {
+            /** @description Bisection is started */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["BisectResponse"];
+                };
+            };
+            /** @description No good revision or the bad revision is an ancestor of a good revision */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow or revision not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    reset_bisect: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Bisection is ended */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusResponse"];
+                };
+            };
+            /** @description No bisection is running */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    mark_bisect: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        FlowMetadata: {
-            /** @description ID of the debug flow */
-            id: string;
-            /**
-             * Format: date-time
-             * @description Last modified date
-             */
-            lastModifiedDate: string;
-            /** @description Name of the debug flow */
-            name: string;
-            /** @description Number of edges in the debug flow */
-            numEdges: number;
-            /** @description Number of nodes in the debug flow */
-            numNodes: number;
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["MarkBisectRequest"];
+            };
         };
-        FullFlowRequestResponse: {
-            flow: components["schemas"]["FlowData"];
+        responses: {
+            /** @description Commit is marked */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["BisectResponse"];
+                };
+            };
+            /** @description No bisection is running */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description The bisection is finished or a command is testing its commits */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        HashMap: {
-            [key: string]: string;
+    };
+    run_bisect: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        ListBranchesResponse: {
-            /** @description Found branches */
-            branches: components["schemas"]["Branch"][];
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["RunBisectRequest"];
+            };
         };
-        ListCommitsResponse: {
-            /** @description Array of commits between the base and head commit IDs
-             *     in reverse chronological order. */
-            commits: components["schemas"]["CommitWithReferences"][];
+        responses: {
+            /** @description Bisection is running */
+            202: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusResponse"];
+                };
+            };
+            /** @description Empty command */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description No bisection is running */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description The bisection is finished or a command is already testing its commits */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        ListDiffsResponse: {
-            /** @description Diff between base and head revision */
-            diff: components["schemas"]["Diff"];
+    };
+    diff_flow: {
+        parameters: {
+            query: {
+                /** @description Git revision of the repository to compare the stored debug flow to, e.g. `HEAD` or a commit hash */
+                rev: string;
+            };
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        ListFlowsResponse: {
-            flows: components["schemas"]["FlowMetadata"][];
+        requestBody?: never;
+        responses: {
+            /** @description Differences of the debug flow */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FlowDiff"];
+                };
+            };
+            /** @description Debug flow or revision not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        ListReferencesResponse: {
-            /** @description Array of references */
-            references: components["schemas"]["ResolvedReference"][];
+    };
+    duplicate_flow: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        ListTagsResponse: {
-            tags: components["schemas"]["TaggedCommit"][];
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["DuplicateFlowRequest"];
+            };
         };
-        ReactFlowState: {
-            /** @description Edges of the reactflow state, the types of the nodes are managed on the frontend */
-            edges: unknown[];
-            /** @description Nodes of the reactflow state, the types of the nodes are managed on the frontend */
-            nodes: unknown[];
+        responses: {
+            /** @description Debug flow is duplicated */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["CreateFlowResponse"];
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow with the new ID exists already */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        /** @enum {string} */
-        ReferenceKind: "tag" | "branch" | "note" | "remotebranch";
-        ReferenceMetadata: {
-            kind: components["schemas"]["ReferenceKind"];
-            name: string;
+    };
+    export_flow: {
+        parameters: {
+            query: {
+                /** @description Format to export the debug flow to */
+                format: components["schemas"]["Format"];
+            };
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        RepositoryStatusResponse: components["schemas"]["Status"];
-        ResolvedReference: components["schemas"]["ReferenceMetadata"] & {
-            target: components["schemas"]["Commit"];
+        requestBody?: never;
+        responses: {
+            /** @description Exported debug flow */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "text/markdown": string;
+                    "text/vnd.mermaid": string;
+                    "text/vnd.graphviz": string;
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        Signature: {
-            email: string;
-            name: string;
+    };
+    list_flow_versions: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+            };
+            cookie?: never;
         };
-        Status: {
-            /** @description Paths with conflicts */
-            conflicts: components["schemas"]["Vec"];
-            /** @description Name of the current branch, not set if `is_detached_head` is true */
-            currentBranch?: string | null;
-            /** @description The commit current HEAD points to */
-            head: components["schemas"]["CommitWithReferences"];
-            /** @description Status of the index */
-            index: components["schemas"]["TreeStatus"];
-            /** @description Whether the head is detached */
-            isDetachedHead: boolean;
-            /** @description Whether the worktree or index have changes */
-            isDirty: boolean;
-            /** @description Status in the worktree */
-            worktree: components["schemas"]["TreeStatus"];
+        requestBody?: never;
+        responses: {
+            /** @description Recorded versions of the debug flow */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ListFlowVersionsResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        TaggedCommit: {
-            /** @description Commit the tag is on */
-            commit: components["schemas"]["Commit"];
-            /** @description Tag on the commit */
-            tag: string;
+    };
+    get_flow_version: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+                version: string;
+            };
+            cookie?: never;
         };
-        TreeStatus: {
-            /** @description Deleted files */
-            deletedFiles: components["schemas"]["Vec"];
-            /** @description Modified files */
-            modifiedFiles: components["schemas"]["Vec"];
-            /** @description Added files */
-            newFiles: components["schemas"]["Vec"];
-            /** @description Renamed files */
-            renamedFiles: components["schemas"]["Vec"];
+        requestBody?: never;
+        responses: {
+            /** @description Debug flow in the version */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FullFlowRequestResponse"];
+                };
+            };
+            /** @description Version not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
         };
-        Vec: string[];
     };
-    responses: never;
-    parameters: never;
-    requestBodies: never;
-    headers: never;
-    pathItems: never;
-}
-export type $defs = Record<string, never>;
-export interface operations {
-    list_flows: {
+    checkout_node_revision: {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                id: string;
+                node_id: string;
+            };
             cookie?: never;
         };
-        requestBody?: never;
+        requestBody: {
+            content: {
+                "application/json": components["schemas"]["CheckoutNodeRequest"];
+            };
+        };
         responses: {
-            /** @description List debug flows */
+            /** @description Revision checked out successfully */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["ListFlowsResponse"];
+                    "application/json": components["schemas"]["CheckoutNodeResponse"];
+                };
+            };
+            /** @description Node without Git revision */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow or node not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Uncommitted changes block the checkout, the details list the paths */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
                 };
             };
             /** @description Internal server error */
@@ -421,26 +2252,97 @@ export interface operations {
             };
         };
     };
-    create_flow: {
+    run_node_command: {
         parameters: {
             query?: never;
             header?: never;
-            path?: never;
+            path: {
+                id: string;
+                node_id: string;
+            };
             cookie?: never;
         };
         requestBody: {
             content: {
-                "application/json": components["schemas"]["CreateFlowRequest"];
+                "application/json": components["schemas"]["RunCommandRequest"];
             };
         };
         responses: {
-            /** @description Debug Flow created */
+            /** @description Command is started */
+            202: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["RunCommandResponse"];
+                };
+            };
+            /** @description Empty command or node without Git revision */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Debug flow or node not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    restore_node_stash: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                id: string;
+                node_id: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Stash restored */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["CreateFlowResponse"];
+                    "application/json": components["schemas"]["RestoreStashResponse"];
+                };
+            };
+            /** @description Debug flow, node or stash not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description The stash was restored with conflicts or conflicts with uncommitted changes, it is kept on the node. The details list the conflicting paths. */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
                 };
             };
             /** @description Internal server error */
@@ -454,7 +2356,7 @@ export interface operations {
             };
         };
     };
-    get_flow: {
+    pin_flow_revisions: {
         parameters: {
             query?: never;
             header?: never;
@@ -465,13 +2367,13 @@ export interface operations {
         };
         requestBody?: never;
         responses: {
-            /** @description Debug flow is available */
+            /** @description Revisions are pinned */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["FullFlowRequestResponse"];
+                    "application/json": components["schemas"]["PinFlowRevisionsResponse"];
                 };
             };
             /** @description File not found */
@@ -494,7 +2396,7 @@ export interface operations {
             };
         };
     };
-    store_flow: {
+    validate_flow: {
         parameters: {
             query?: never;
             header?: never;
@@ -503,19 +2405,85 @@ export interface operations {
             };
             cookie?: never;
         };
-        requestBody: {
-            content: {
-                "application/json": components["schemas"]["FullFlowRequestResponse"];
+        requestBody?: never;
+        responses: {
+            /** @description Revisions which need attention */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["FlowValidation"];
+                };
+            };
+            /** @description File not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    get_blame: {
+        parameters: {
+            query: {
+                /** @description Path of the file relative to the repository root */
+                path: string;
+                /** @description The revision to blame the file at, this can be short hash, full hash, a tag,
+                 *     or any other reference such a branch name. If empty, the current HEAD is used. */
+                rev?: string;
+                /** @description First line to blame, starting at 1. Must be given together with `endLine`. */
+                startLine?: number;
+                /** @description Last line to blame, inclusive. Must be given together with `startLine`. */
+                endLine?: number;
+                /** @description Follow the file across renames, defaults to true */
+                followRenames?: boolean;
+                /** @description Ignore whitespace changes, defaults to false */
+                ignoreWhitespace?: boolean;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Blame of the file */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["BlameResponse"];
+                };
             };
-        };
-        responses: {
-            /** @description Debug flow is stored */
-            200: {
+            /** @description Invalid line range */
+            400: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["ApiStatusResponse"];
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Revision or file not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
                 };
             };
             /** @description Internal server error */
@@ -529,27 +2497,40 @@ export interface operations {
             };
         };
     };
-    delete_flow: {
+    read_blob: {
         parameters: {
-            query?: never;
-            header?: never;
-            path: {
-                id: string;
+            query: {
+                /** @description Path of the file relative to the repository root */
+                path: string;
+                /** @description The revision of the file, this can be short hash, full hash, a tag,
+                 *     or any other reference such a branch name. If empty, the current HEAD is used. */
+                rev?: string;
             };
+            header?: never;
+            path?: never;
             cookie?: never;
         };
         requestBody?: never;
         responses: {
-            /** @description Debug flow is deleted */
+            /** @description The file */
             200: {
                 headers: {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["ApiStatusResponse"];
+                    "application/json": components["schemas"]["Blob"];
                 };
             };
-            /** @description File not found */
+            /** @description The path is not a file */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Revision or file not found */
             404: {
                 headers: {
                     [name: string]: unknown;
@@ -692,7 +2673,16 @@ export interface operations {
     };
     checkout_revision: {
         parameters: {
-            query?: never;
+            query?: {
+                /** @description ID of the debug flow of the node given by `nodeId` */
+                flowId?: string;
+                /** @description Check out the revision in the linked worktree of this node in `.debug-flow/worktrees/` instead of the
+                 *     working directory of the repository, requires `flowId` */
+                nodeId?: string;
+                /** @description How uncommitted changes in the working directory are handled, defaults to `refuse`. Not supported
+                 *     together with `nodeId`, worktrees of nodes are always reset. */
+                mode?: components["schemas"]["CheckoutMode"];
+            };
             header?: never;
             path: {
                 /**
@@ -713,7 +2703,16 @@ export interface operations {
                     [name: string]: unknown;
                 };
                 content: {
-                    "application/json": components["schemas"]["Commit"];
+                    "application/json": components["schemas"]["CheckoutResponse"];
+                };
+            };
+            /** @description Mode given for the worktree of a node or node without debug flow */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
                 };
             };
             /** @description Revision not found */
@@ -725,6 +2724,15 @@ export interface operations {
                     "application/json": components["schemas"]["ApiStatusDetailResponse"];
                 };
             };
+            /** @description Uncommitted changes block the checkout, the details list the paths, or a command is running in the worktree of the node */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
             /** @description Internal server error */
             500: {
                 headers: {
@@ -741,6 +2749,11 @@ export interface operations {
             query?: {
                 /** @description string filter for the commits. Filters commits by their ID or summary. */
                 filter?: string;
+                /** @description Only list commits changing the file or directory at this path relative to the repository root,
+                 *     like `git log -- <path>` */
+                path?: string;
+                /** @description Follow the file at `path` across renames like `git log --follow`, defaults to true */
+                followRenames?: boolean;
                 /** @description The base revision of the range, this can be short hash, full hash, a tag,
                  *     or any other reference such a branch name. If empty, the first commit is used. */
                 baseRev?: string;
@@ -783,6 +2796,19 @@ export interface operations {
                 /** @description The head revision of the range, this can be short hash, full hash, a tag,
                  *     or any other reference such a branch name. If empty, the current HEAD is used. */
                 headRev?: string;
+                /** @description Only diff files matching one of these pathspecs, e.g. `src/` or `*.rs`.
+                 *     Renames are only detected if both paths match. */
+                path?: string[];
+                /** @description Number of unchanged lines around changes, defaults to 3 */
+                contextLines?: number;
+                /** @description How whitespace changes are handled, defaults to `show` */
+                whitespace?: components["schemas"]["WhitespaceMode"];
+                /** @description Minimum similarity in percent for renamed files, defaults to 50 */
+                renameThreshold?: number;
+                /** @description Minimum similarity in percent for copied files, copies are only detected if it is set */
+                copyThreshold?: number;
+                /** @description Size in bytes above which files are treated as binary, defaults to 512 MiB */
+                maxFileSize?: number;
             };
             header?: never;
             path?: never;
@@ -799,6 +2825,15 @@ export interface operations {
                     "application/json": components["schemas"]["ListDiffsResponse"];
                 };
             };
+            /** @description Invalid diff options */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
             /** @description Internal server error */
             500: {
                 headers: {
@@ -950,4 +2985,230 @@ export interface operations {
             };
         };
     };
+    list_tree: {
+        parameters: {
+            query?: {
+                /** @description The revision of the tree, this can be short hash, full hash, a tag,
+                 *     or any other reference such a branch name. If empty, the current HEAD is used. */
+                rev?: string;
+                /** @description Path of the directory relative to the repository root. If empty, the root directory is listed. */
+                path?: string;
+            };
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Entries of the directory */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ListTreeResponse"];
+                };
+            };
+            /** @description The path is not a directory */
+            400: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Revision or path not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    list_worktrees: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path?: never;
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description List of worktrees */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ListWorktreesResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    prune_worktree: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Name of the linked worktree */
+                name: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Worktree removed */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusResponse"];
+                };
+            };
+            /** @description Worktree not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description The worktree is locked or a command is running in the worktree of the node */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    lock_worktree: {
+        parameters: {
+            query?: {
+                /** @description Reason the worktree is locked for */
+                reason?: string;
+            };
+            header?: never;
+            path: {
+                /** @description Name of the linked worktree */
+                name: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Worktree locked */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Worktree"];
+                };
+            };
+            /** @description Worktree not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description The worktree is locked already */
+            409: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
+    unlock_worktree: {
+        parameters: {
+            query?: never;
+            header?: never;
+            path: {
+                /** @description Name of the linked worktree */
+                name: string;
+            };
+            cookie?: never;
+        };
+        requestBody?: never;
+        responses: {
+            /** @description Worktree unlocked */
+            200: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["Worktree"];
+                };
+            };
+            /** @description Worktree not found */
+            404: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+            /** @description Internal server error */
+            500: {
+                headers: {
+                    [name: string]: unknown;
+                };
+                content: {
+                    "application/json": components["schemas"]["ApiStatusDetailResponse"];
+                };
+            };
+        };
+    };
 }
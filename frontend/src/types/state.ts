@@ -19,6 +19,8 @@ import type {
 export interface FlowIdAndName {
   id: string;
   name: string;
+  /** Revision of the stored flow the local changes are based on */
+  revision?: string;
 }
 
 interface NodesAndEdges {